@@ -22,6 +22,7 @@ type ClapMcpAttrs = (
     Option<String>,
     Option<String>,
     Option<String>,
+    Option<String>,
 );
 
 fn meta_string_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<String> {
@@ -46,6 +47,7 @@ fn parse_clap_mcp_attrs(attrs: &[syn::Attribute]) -> ClapMcpAttrs {
     let mut stateful = None;
     let mut mcp_flag = None;
     let mut mcp_http_flag = None;
+    let mut mcp_unix_flag = None;
     let mut export_skills_flag = None;
 
     for attr in attrs {
@@ -107,6 +109,8 @@ fn parse_clap_mcp_attrs(attrs: &[syn::Attribute]) -> ClapMcpAttrs {
                 mcp_flag = Some(meta_string_value(&meta)?);
             } else if meta.path.is_ident("mcp_http_flag") {
                 mcp_http_flag = Some(meta_string_value(&meta)?);
+            } else if meta.path.is_ident("mcp_unix_flag") {
+                mcp_unix_flag = Some(meta_string_value(&meta)?);
             } else if meta.path.is_ident("export_skills_flag") {
                 export_skills_flag = Some(meta_string_value(&meta)?);
             } else if meta.path.is_ident("args_metadata") && meta.input.peek(syn::token::Eq) {
@@ -114,6 +118,10 @@ fn parse_clap_mcp_attrs(attrs: &[syn::Attribute]) -> ClapMcpAttrs {
                 if !expr_to_bool(&value) {
                     return Err(meta.error("args_metadata only supports `true` or bare flag"));
                 }
+            } else if meta.input.peek(syn::token::Eq) {
+                // Parsed elsewhere (`poison_policy`, `instructions`, ...); skip the value so
+                // later keys in the same attribute are still seen.
+                let _: Expr = meta.value()?.parse()?;
             }
             Ok(())
         });
@@ -129,6 +137,7 @@ fn parse_clap_mcp_attrs(attrs: &[syn::Attribute]) -> ClapMcpAttrs {
         stateful,
         mcp_flag,
         mcp_http_flag,
+        mcp_unix_flag,
         export_skills_flag,
     )
 }
@@ -195,6 +204,31 @@ fn has_clap_mcp_task(attrs: &[syn::Attribute]) -> bool {
         let _ = attr.parse_nested_meta(|meta| {
             if meta.path.is_ident("task") {
                 found = true;
+            } else if meta.input.peek(syn::token::Eq) {
+                let _: Expr = meta.value()?.parse()?;
+            }
+            Ok(())
+        });
+        if found {
+            return true;
+        }
+    }
+    false
+}
+
+/// Parses `#[clap_mcp(elicit_missing)]` on enum variants — when a call omits required args and
+/// the client supports form elicitation, the server asks the user for them instead of failing.
+fn has_clap_mcp_elicit_missing(attrs: &[syn::Attribute]) -> bool {
+    for attr in attrs {
+        if !attr.path().is_ident("clap_mcp") {
+            continue;
+        }
+        let mut found = false;
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("elicit_missing") {
+                found = true;
+            } else if meta.input.peek(syn::token::Eq) {
+                let _: Expr = meta.value()?.parse()?;
             }
             Ok(())
         });
@@ -339,6 +373,183 @@ fn has_clap_mcp_schema_only(attrs: &[syn::Attribute]) -> bool {
     false
 }
 
+/// Parses `#[clap_mcp(poison_policy = "...")]` into a `ClapMcpPoisonPolicy` variant path.
+fn get_clap_mcp_poison_policy(
+    attrs: &[syn::Attribute],
+) -> Result<Option<proc_macro2::TokenStream>, syn::Error> {
+    for attr in attrs {
+        if !attr.path().is_ident("clap_mcp") {
+            continue;
+        }
+        let mut result = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("poison_policy") {
+                let value = meta_string_value(&meta)?;
+                result = Some(match value.as_str() {
+                    "continue" => quote! { clap_mcp::ClapMcpPoisonPolicy::Continue },
+                    "disable_tool" => quote! { clap_mcp::ClapMcpPoisonPolicy::DisableTool },
+                    "refuse_all" => quote! { clap_mcp::ClapMcpPoisonPolicy::RefuseAll },
+                    "exit" => quote! { clap_mcp::ClapMcpPoisonPolicy::Exit },
+                    other => {
+                        return Err(meta.error(format!(
+                            "unknown poison_policy `{other}`; expected \"continue\", \
+                             \"disable_tool\", \"refuse_all\", or \"exit\""
+                        )));
+                    }
+                });
+            } else if meta.input.peek(syn::token::Eq) {
+                let _: Expr = meta.value()?.parse()?;
+            }
+            Ok(())
+        })?;
+        if result.is_some() {
+            return Ok(result);
+        }
+    }
+    Ok(None)
+}
+
+/// Parses `#[clap_mcp(instructions = "...")]` into an `Option<String>` expression.
+fn get_clap_mcp_instructions(
+    attrs: &[syn::Attribute],
+) -> Result<Option<proc_macro2::TokenStream>, syn::Error> {
+    for attr in attrs {
+        if !attr.path().is_ident("clap_mcp") {
+            continue;
+        }
+        let mut result = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("instructions") {
+                let value = meta_string_value(&meta)?;
+                result = Some(quote! { Some(String::from(#value)) });
+            } else if meta.input.peek(syn::token::Eq) {
+                let _: Expr = meta.value()?.parse()?;
+            }
+            Ok(())
+        })?;
+        if result.is_some() {
+            return Ok(result);
+        }
+    }
+    Ok(None)
+}
+
+/// Parses a positive integer `#[clap_mcp(key = N)]` (e.g. `max_concurrency`, `queue_timeout_ms`).
+fn get_clap_mcp_positive_int(
+    attrs: &[syn::Attribute],
+    key: &str,
+) -> Result<Option<u64>, syn::Error> {
+    for attr in attrs {
+        if !attr.path().is_ident("clap_mcp") {
+            continue;
+        }
+        let mut result = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(key) {
+                let value: Expr = meta.value()?.parse()?;
+                let n = match &value {
+                    Expr::Lit(lit) => match &lit.lit {
+                        Lit::Int(i) => i.base10_parse::<u64>().ok(),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                match n {
+                    Some(n) if n > 0 => result = Some(n),
+                    _ => return Err(meta.error(format!("{key} expects a positive integer"))),
+                }
+            } else if meta.input.peek(syn::token::Eq) {
+                let _: Expr = meta.value()?.parse()?;
+            }
+            Ok(())
+        })?;
+        if result.is_some() {
+            return Ok(result);
+        }
+    }
+    Ok(None)
+}
+
+/// Parses `#[clap_mcp(complete_with = "path::to::fn")]` on a field.
+fn get_clap_mcp_complete_with(attrs: &[syn::Attribute]) -> Result<Option<syn::Path>, syn::Error> {
+    for attr in attrs {
+        if !attr.path().is_ident("clap_mcp") {
+            continue;
+        }
+        let mut result = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("complete_with") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                result = Some(value.parse::<syn::Path>().map_err(|_| {
+                    meta.error("complete_with expects a function path, e.g. \"complete_names\"")
+                })?);
+            } else if meta.input.peek(syn::token::Eq) {
+                let _: Expr = meta.value()?.parse()?;
+            }
+            Ok(())
+        })?;
+        if result.is_some() {
+            return Ok(result);
+        }
+    }
+    Ok(None)
+}
+
+/// Parses `#[clap_mcp(confirm = "Delete {name}?")]` on a variant.
+fn get_clap_mcp_confirm(attrs: &[syn::Attribute]) -> Result<Option<String>, syn::Error> {
+    for attr in attrs {
+        if !attr.path().is_ident("clap_mcp") {
+            continue;
+        }
+        let mut result = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("confirm") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if value.value().trim().is_empty() {
+                    return Err(meta.error("confirm expects a non-empty message"));
+                }
+                result = Some(value.value());
+            } else if meta.input.peek(syn::token::Eq) {
+                let _: Expr = meta.value()?.parse()?;
+            }
+            Ok(())
+        })?;
+        if result.is_some() {
+            return Ok(result);
+        }
+    }
+    Ok(None)
+}
+
+/// Parses `#[clap_mcp(in_process)]` / `#[clap_mcp(subprocess)]` on a variant.
+/// Returns `Some(true)` for in-process, `Some(false)` for subprocess.
+fn get_clap_mcp_execution_mode(attrs: &[syn::Attribute]) -> Result<Option<bool>, syn::Error> {
+    let mut mode: Option<bool> = None;
+    for attr in attrs {
+        if !attr.path().is_ident("clap_mcp") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            let this = if meta.path.is_ident("in_process") {
+                true
+            } else if meta.path.is_ident("subprocess") {
+                false
+            } else {
+                if meta.input.peek(syn::token::Eq) {
+                    let _: Expr = meta.value()?.parse()?;
+                }
+                return Ok(());
+            };
+            if mode.is_some_and(|m| m != this) {
+                return Err(meta.error("use either `in_process` or `subprocess`, not both"));
+            }
+            mode = Some(this);
+            Ok(())
+        })?;
+    }
+    Ok(mode)
+}
+
 enum ClapMcpSkipMode {
     None,
     Bare,
@@ -726,6 +937,24 @@ fn get_clap_mcp_output_from(attrs: &[syn::Attribute]) -> Option<Path> {
     None
 }
 
+/// Parses `#[clap_mcp_output_from_async = "run"]`; `run` is an `async fn` awaited by the
+/// generated `execute_for_mcp_async`.
+fn get_clap_mcp_output_from_async(attrs: &[syn::Attribute]) -> Option<Path> {
+    for attr in attrs {
+        if !attr.path().is_ident("clap_mcp_output_from_async") {
+            continue;
+        }
+        if let Meta::NameValue(MetaNameValue { value, .. }) = &attr.meta
+            && let Expr::Lit(lit) = value
+            && let Lit::Str(s) = &lit.lit
+            && let Ok(path) = syn::parse_str::<Path>(&s.value())
+        {
+            return Some(path);
+        }
+    }
+    None
+}
+
 fn get_clap_mcp_output_from_with_state(attrs: &[syn::Attribute]) -> Option<Path> {
     for attr in attrs {
         if !attr.path().is_ident("clap_mcp_output_from_with_state") {
@@ -1067,6 +1296,12 @@ fn nested_subcommand_type_paths_from_enum(data: &syn::DataEnum) -> Vec<syn::Path
 /// - `catch_in_process_panics` / `catch_in_process_panics = true|false` — When reinvocation_safe,
 ///   if true, panics in tool code are caught and returned as MCP errors instead of crashing the
 ///   server. Default is false. See [`ClapMcpConfig::catch_in_process_panics`].
+/// - `poison_policy = "continue" | "disable_tool" | "refuse_all" | "exit"` — What the server does
+///   after a caught panic (default `"continue"`). See [`ClapMcpConfig::poison_policy`].
+/// - `max_concurrency = N` — Global cap on concurrently executing tool calls. See
+///   [`ClapMcpConfig::max_concurrency`].
+/// - `queue_timeout_ms = N` — Fail calls that wait longer than `N` ms for a concurrency slot with
+///   a "server busy" tool error. See [`ClapMcpConfig::queue_timeout`].
 /// - `allow_mcp_without_subcommand` / `allow_mcp_without_subcommand = true|false` — When true
 ///   (default), `myapp --mcp` starts MCP even when the root has `subcommand_required = true`
 ///   (argv is checked before clap). Does **not** change non-MCP CLI behavior; do not switch to
@@ -1074,10 +1309,15 @@ fn nested_subcommand_type_paths_from_enum(data: &syn::DataEnum) -> Vec<syn::Path
 /// - `mcp_flag = "long_name"` — Rename the stdio MCP flag long name (default `"mcp"`). clap arg
 ///   id stays [`CLAP_MCP_STDIO_FLAG_ID`](clap_mcp::CLAP_MCP_STDIO_FLAG_ID).
 /// - `mcp_http_flag = "long_name"` — Rename the HTTP MCP flag (requires `http` feature).
+/// - `mcp_unix_flag = "long_name"` — Rename the Unix socket MCP flag (requires `http` feature,
+///   Unix only).
 /// - `export_skills_flag = "long_name"` — Rename the export-skills flag.
+/// - `instructions = "..."` — Server `instructions` returned from `initialize` (merged with the
+///   logging guide when logging is enabled). See [`ClapMcpConfig::instructions`].
 /// - `task_augmented_tools` / `task_augmented_tools = true|false` — When true, advertise MCP task
-///   support and handle task-augmented `tools/call` (in-process only). Requires
-///   `reinvocation_safe`; combining with `reinvocation_safe = false` is a **compile error**.
+///   support and handle task-augmented `tools/call`. Subprocess tools (`reinvocation_safe = false`
+///   or `#[clap_mcp(subprocess)]`) run under the task: stderr lines become the task status and
+///   `tasks/cancel` kills the child's process group.
 ///   With `parallel_safe = false`, task and plain tool bodies share one serialization queue.
 ///   With `parallel_safe = true`, task bodies may overlap with each other and with plain
 ///   `tools/call`; logging during tasks uses per-task context so `meta.taskId` stays correct.
@@ -1100,6 +1340,14 @@ fn nested_subcommand_type_paths_from_enum(data: &syn::DataEnum) -> Vec<syn::Path
 /// `Option<O>`, `Result<O, E>`). The macro generates `execute_for_mcp(self)` as
 /// `run(self).into_tool_result()`. **Required** for enums.
 ///
+/// ## `#[clap_mcp_output_from_async = "run"]` (on the enum)
+///
+/// Like `clap_mcp_output_from`, but `run` is an `async fn` taking the CLI type by value. The macro
+/// implements [`ClapMcpAsyncToolExecutor`] instead of `ClapMcpToolExecutor`; serve with
+/// `run_or_serve_mcp_async` / `ServeMcpBuilder::for_cli_async`. In-process calls are awaited on
+/// the MCP server's runtime (no per-call runtime or `block_on`). Cannot be combined with
+/// `clap_mcp_output_from`, `clap_mcp_output_from_with_state`, or `#[clap_mcp(stateful)]`.
+///
 /// ## `#[clap_mcp_output_type = "TypeName"]` (on the enum, requires `output-schema` feature)
 ///
 /// When present and the crate is built with `output-schema`, the type's JSON schema (via
@@ -1166,6 +1414,12 @@ fn nested_subcommand_type_paths_from_enum(data: &syn::DataEnum) -> Vec<syn::Path
 /// Make the argument required in the MCP tool schema even if optional in clap.
 /// Use `requires` for the field's own id, or `requires = "name"` to specify.
 ///
+/// ## `#[clap_mcp(complete_with = "complete_fn")]` (on field)
+///
+/// Answers MCP `completion/complete` for this argument with
+/// `fn(&clap_mcp::ArgCompletionRequest) -> Vec<String>` instead of the clap `possible_values`
+/// or path value hint. Recorded in [`ClapMcpSchemaMetadata::arg_completers`].
+///
 /// ## `#[clap_mcp(serialized)]` / `#[clap_mcp(serialized = "arg1, arg2")]` (on variant)
 ///
 /// When [`ClapMcpConfig::parallel_safe`] is true, serializes concurrent MCP invocations of this
@@ -1180,6 +1434,36 @@ fn nested_subcommand_type_paths_from_enum(data: &syn::DataEnum) -> Vec<syn::Path
 /// clap arg ids (field ident by default; `#[arg(id = "...")]` when set). Topical locks do not isolate session
 /// state. See [execution-safety](https://github.com/canardleteer/clap-mcp/blob/main/docs/execution-safety.md).
 ///
+/// ## `#[clap_mcp(max_concurrency = N)]` (on variant)
+///
+/// At most `N` calls of this tool execute at once; further calls queue (see `queue_timeout_ms`
+/// on the root). Recorded in [`ClapMcpSchemaMetadata::tool_max_concurrency`] and advertised as
+/// `meta.clapMcp.maxConcurrency`.
+///
+/// ## `#[clap_mcp(in_process)]` / `#[clap_mcp(subprocess)]` (on variant)
+///
+/// Overrides the root `reinvocation_safe` for this tool: `in_process` runs it in the server
+/// process, `subprocess` re-invokes the executable for each call (e.g. for `migrate` or `exec`
+/// in an otherwise in-process CLI). Recorded in
+/// [`ClapMcpSchemaMetadata::tool_execution_modes`] and reported as
+/// `meta.clapMcp.reinvocationSafe`. Subprocess tools are not task-augmented.
+///
+/// ## `#[clap_mcp(elicit_missing)]` (on variant)
+///
+/// When a call omits required arguments and the client supports form elicitation, the server
+/// sends `elicitation/create` with a form built from the missing args (type, possible values,
+/// help text) and continues the call with the answers. A decline or cancel returns a tool
+/// error. Recorded in [`ClapMcpSchemaMetadata::elicit_missing_tools`]; without client support
+/// the usual "Missing required argument(s)" error is returned.
+///
+/// ## `#[clap_mcp(confirm = "Delete {name}?")]` (on variant)
+///
+/// Before running the tool (in-process or subprocess), the server asks the user to confirm via
+/// `elicitation/create`. `{arg}` placeholders in the message are replaced with the call's
+/// argument values. A decline, cancel, or unchecked confirmation returns a tool error, and
+/// the call fails closed when the client does not support form elicitation. Recorded in
+/// [`ClapMcpSchemaMetadata::confirm_tools`].
+///
 /// ## `#[clap_mcp(requires = "arg1,arg2")]` (on variant)
 ///
 /// Variant-level alternative: one or more optional args to make required (single name or
@@ -1212,6 +1496,7 @@ fn nested_subcommand_type_paths_from_enum(data: &syn::DataEnum) -> Vec<syn::Path
     attributes(
         clap_mcp,
         clap_mcp_output_from,
+        clap_mcp_output_from_async,
         clap_mcp_output_from_with_state,
         clap_mcp_state_type,
         clap_mcp_output_type,
@@ -1344,6 +1629,7 @@ pub fn derive_clap_mcp(input: TokenStream) -> TokenStream {
     }
 
     let output_from_with_state = get_clap_mcp_output_from_with_state(&input.attrs);
+    let output_from_async = get_clap_mcp_output_from_async(&input.attrs);
     let state_type = get_clap_mcp_state_type(&input.attrs);
     let schema_only = has_clap_mcp_schema_only(&input.attrs);
     if schema_only {
@@ -1357,6 +1643,7 @@ pub fn derive_clap_mcp(input: TokenStream) -> TokenStream {
             );
         }
         if get_clap_mcp_output_from(&input.attrs).is_some()
+            || output_from_async.is_some()
             || output_from_with_state.is_some()
             || get_clap_mcp_state_type(&input.attrs).is_some()
         {
@@ -1364,13 +1651,28 @@ pub fn derive_clap_mcp(input: TokenStream) -> TokenStream {
                 syn::Error::new_spanned(
                     &input.ident,
                     "clap_mcp: #[clap_mcp(schema_only)] cannot be combined with \
-                     #[clap_mcp_output_from], #[clap_mcp_output_from_with_state], or \
-                     #[clap_mcp_state_type]",
+                     #[clap_mcp_output_from], #[clap_mcp_output_from_async], \
+                     #[clap_mcp_output_from_with_state], or #[clap_mcp_state_type]",
                 )
                 .to_compile_error(),
             );
         }
     }
+    if output_from_async.is_some()
+        && (get_clap_mcp_output_from(&input.attrs).is_some()
+            || output_from_with_state.is_some()
+            || parse_clap_mcp_attrs(&input.attrs).6 == Some(true))
+    {
+        return TokenStream::from(
+            syn::Error::new_spanned(
+                &input.ident,
+                "clap_mcp: #[clap_mcp_output_from_async = \"run\"] cannot be combined with \
+                 #[clap_mcp_output_from], #[clap_mcp_output_from_with_state], or \
+                 #[clap_mcp(stateful)]",
+            )
+            .to_compile_error(),
+        );
+    }
     if state_type.is_some() && output_from_with_state.is_none() {
         return TokenStream::from(
             syn::Error::new_spanned(
@@ -1389,24 +1691,16 @@ pub fn derive_clap_mcp(input: TokenStream) -> TokenStream {
         share_runtime,
         catch_in_process_panics,
         allow_mcp_without_subcommand,
-        task_augmented_tools,
+        _,
         stateful,
         mcp_flag,
         mcp_http_flag,
+        mcp_unix_flag,
         export_skills_flag,
     ) = parse_clap_mcp_attrs(&input.attrs);
     let stateful_effective = stateful.unwrap_or(false);
 
     let reinvocation_effective = reinvocation_safe.unwrap_or(false);
-    if task_augmented_tools == Some(true) && !reinvocation_effective {
-        return TokenStream::from(
-            syn::Error::new(
-                proc_macro2::Span::call_site(),
-                "clap_mcp: task_augmented_tools requires reinvocation_safe (in-process execution); subprocess mode cannot use MCP task-augmented tools/call",
-            )
-            .to_compile_error(),
-        );
-    }
     if output_from_with_state.is_some() && !reinvocation_effective {
         return TokenStream::from(
             syn::Error::new_spanned(
@@ -1447,6 +1741,29 @@ pub fn derive_clap_mcp(input: TokenStream) -> TokenStream {
     let catch_in_process_panics_expr = catch_in_process_panics
         .map(|b| quote! { #b })
         .unwrap_or_else(|| quote! { clap_mcp::ClapMcpConfig::default().catch_in_process_panics });
+    let poison_policy_expr = match get_clap_mcp_poison_policy(&input.attrs) {
+        Ok(Some(policy)) => policy,
+        Ok(None) => quote! { clap_mcp::ClapMcpConfig::default().poison_policy },
+        Err(e) => return TokenStream::from(e.to_compile_error()),
+    };
+    let max_concurrency_expr = match get_clap_mcp_positive_int(&input.attrs, "max_concurrency") {
+        Ok(Some(n)) => {
+            let n = n as usize;
+            quote! { Some(#n) }
+        }
+        Ok(None) => quote! { clap_mcp::ClapMcpConfig::default().max_concurrency },
+        Err(e) => return TokenStream::from(e.to_compile_error()),
+    };
+    let queue_timeout_expr = match get_clap_mcp_positive_int(&input.attrs, "queue_timeout_ms") {
+        Ok(Some(ms)) => quote! { Some(std::time::Duration::from_millis(#ms)) },
+        Ok(None) => quote! { clap_mcp::ClapMcpConfig::default().queue_timeout },
+        Err(e) => return TokenStream::from(e.to_compile_error()),
+    };
+    let instructions_expr = match get_clap_mcp_instructions(&input.attrs) {
+        Ok(Some(instructions)) => instructions,
+        Ok(None) => quote! { clap_mcp::ClapMcpConfig::default().instructions },
+        Err(e) => return TokenStream::from(e.to_compile_error()),
+    };
     let allow_mcp_without_subcommand_expr = allow_mcp_without_subcommand
         .map(|b| quote! { #b })
         .unwrap_or_else(
@@ -1460,6 +1777,9 @@ pub fn derive_clap_mcp(input: TokenStream) -> TokenStream {
     if let Some(long) = mcp_http_flag {
         builtin_flag_stmts.push(quote! { flags = flags.with_http_long(#long); });
     }
+    if let Some(long) = mcp_unix_flag {
+        builtin_flag_stmts.push(quote! { flags = flags.with_unix_long(#long); });
+    }
     if let Some(long) = export_skills_flag {
         builtin_flag_stmts.push(quote! { flags = flags.with_export_skills_long(#long); });
     }
@@ -1481,15 +1801,32 @@ pub fn derive_clap_mcp(input: TokenStream) -> TokenStream {
                     reinvocation_safe: #reinvocation_safe_expr,
                     share_runtime: #share_runtime_expr,
                     catch_in_process_panics: #catch_in_process_panics_expr,
+                    poison_policy: #poison_policy_expr,
+                    max_concurrency: #max_concurrency_expr,
+                    queue_timeout: #queue_timeout_expr,
                     allow_mcp_without_subcommand: #allow_mcp_without_subcommand_expr,
                     builtin_flags: #builtin_flags_impl,
+                    instructions: #instructions_expr,
                 }
             }
         }
     };
 
-    let executor_impl = match &input.data {
-        syn::Data::Enum(data) => {
+    let executor_impl = match (&input.data, &output_from_async) {
+        (syn::Data::Enum(_) | syn::Data::Struct(_), Some(run)) => {
+            quote! {
+                impl clap_mcp::ClapMcpAsyncToolExecutor for #name {
+                    fn execute_for_mcp_async(
+                        self,
+                    ) -> impl std::future::Future<
+                        Output = std::result::Result<clap_mcp::ClapMcpToolOutput, clap_mcp::ClapMcpToolError>,
+                    > + Send {
+                        async move { clap_mcp::IntoClapMcpResult::into_tool_result(#run(self).await) }
+                    }
+                }
+            }
+        }
+        (syn::Data::Enum(data), None) => {
             if schema_only {
                 quote! {}
             } else {
@@ -1569,7 +1906,7 @@ pub fn derive_clap_mcp(input: TokenStream) -> TokenStream {
                 }
             }
         }
-        syn::Data::Struct(data) => {
+        (syn::Data::Struct(data), None) => {
             let struct_run_path = get_clap_mcp_output_from(&input.attrs);
             let subcommand_field = data
                 .fields
@@ -1706,6 +2043,39 @@ pub fn derive_clap_mcp(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// `confirm_tools` entries for `#[clap_mcp(confirm = "...")]` variants.
+fn confirm_tools_quote(
+    target: &syn::Ident,
+    confirm_tools: &[(String, String)],
+) -> proc_macro2::TokenStream {
+    let entries = confirm_tools.iter().map(|(cmd, message)| {
+        let cmd_lit = syn::LitStr::new(cmd, proc_macro2::Span::call_site());
+        let message_lit = syn::LitStr::new(message, proc_macro2::Span::call_site());
+        quote! {
+            #target.confirm_tools.insert(#cmd_lit.to_string(), #message_lit.to_string());
+        }
+    });
+    quote! { #(#entries)* }
+}
+
+/// `arg_completers` entries for `#[clap_mcp(complete_with = "...")]` fields.
+fn arg_completer_bindings_quote(
+    target: &syn::Ident,
+    bindings: &[(String, String, syn::Path)],
+) -> proc_macro2::TokenStream {
+    let entries = bindings.iter().map(|(cmd, arg, path)| {
+        let cmd_lit = syn::LitStr::new(cmd, proc_macro2::Span::call_site());
+        let arg_lit = syn::LitStr::new(arg, proc_macro2::Span::call_site());
+        quote! {
+            #target.arg_completers
+                .entry(#cmd_lit.to_string())
+                .or_default()
+                .insert(#arg_lit.to_string(), #path as clap_mcp::ArgCompleterFn);
+        }
+    });
+    quote! { #(#entries)* }
+}
+
 /// Builds the ClapMcpSchemaMetadataProvider impl from #[clap_mcp(skip)], #[clap_mcp(requires)], and #[clap_mcp(task)].
 fn serialize_topic_bindings_quote(
     target: &syn::Ident,
@@ -1730,7 +2100,7 @@ fn serialize_topic_bindings_quote(
 /// Builds the ClapMcpSchemaMetadataProvider impl from #[clap_mcp(skip)], #[clap_mcp(requires)], and #[clap_mcp(task)].
 fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
     let name = &input.ident;
-    let (_, _, _, _, _, task_augmented_tools, _, _, _, _) = parse_clap_mcp_attrs(&input.attrs);
+    let (_, _, _, _, _, task_augmented_tools, _, _, _, _, _) = parse_clap_mcp_attrs(&input.attrs);
     let task_augmented_tools_expr = task_augmented_tools
         .map(|b| quote! { #b })
         .unwrap_or(quote! { false });
@@ -1740,9 +2110,14 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
     let mut requires_args: std::collections::HashMap<String, Vec<String>> =
         std::collections::HashMap::new();
     let mut task_tool_names = Vec::<String>::new();
+    let mut elicit_missing_tools = Vec::<String>::new();
+    let mut confirm_tools: Vec<(String, String)> = Vec::new();
     let mut serialize_tools: std::collections::HashMap<String, ClapMcpSerialized> =
         std::collections::HashMap::new();
     let mut serialize_topic_bindings: Vec<(String, String, syn::Type)> = Vec::new();
+    let mut arg_completer_bindings: Vec<(String, String, syn::Path)> = Vec::new();
+    let mut tool_max_concurrency: Vec<(String, usize)> = Vec::new();
+    let mut tool_execution_modes: Vec<(String, bool)> = Vec::new();
     let mut flatten_serialize_topic_cmds: Vec<(String, syn::Type)> = Vec::new();
     let mut flatten_skip_entries: Vec<FlattenSkipEntry> = Vec::new();
     let mut flatten_skip_error: Option<syn::Error> = None;
@@ -1778,6 +2153,14 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
                 if has_clap_mcp_task(&v.attrs) {
                     task_tool_names.push(cmd_name.clone());
                 }
+                if has_clap_mcp_elicit_missing(&v.attrs) {
+                    elicit_missing_tools.push(cmd_name.clone());
+                }
+                match get_clap_mcp_confirm(&v.attrs) {
+                    Ok(Some(message)) => confirm_tools.push((cmd_name.clone(), message)),
+                    Ok(None) => {}
+                    Err(e) => return e.to_compile_error(),
+                }
                 let variant_has_serialized_args = matches!(
                     get_clap_mcp_serialized(&v.attrs),
                     Some(ClapMcpSerialized::Args(_))
@@ -1785,6 +2168,18 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
                 if let Some(serialized) = get_clap_mcp_serialized(&v.attrs) {
                     serialize_tools.insert(cmd_name.clone(), serialized);
                 }
+                match get_clap_mcp_positive_int(&v.attrs, "max_concurrency") {
+                    Ok(Some(n)) => tool_max_concurrency.push((cmd_name.clone(), n as usize)),
+                    Ok(None) => {}
+                    Err(e) => return e.to_compile_error(),
+                }
+                match get_clap_mcp_execution_mode(&v.attrs) {
+                    Ok(Some(in_process)) => {
+                        tool_execution_modes.push((cmd_name.clone(), in_process));
+                    }
+                    Ok(None) => {}
+                    Err(e) => return e.to_compile_error(),
+                }
                 requires_args
                     .entry(cmd_name.clone())
                     .or_default()
@@ -1826,6 +2221,13 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
                             f.ty.clone(),
                         ));
                     }
+                    match get_clap_mcp_complete_with(&f.attrs) {
+                        Ok(Some(path)) => {
+                            arg_completer_bindings.push((cmd_name.clone(), arg_id.clone(), path));
+                        }
+                        Ok(None) => {}
+                        Err(e) => return e.to_compile_error(),
+                    }
                     if let Some(req) = get_clap_mcp_requires(&f.attrs) {
                         let req_id = if req.is_empty() { arg_id } else { req };
                         requires_args
@@ -1880,6 +2282,13 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
                 ) {
                     flatten_skip_error = Some(e);
                 }
+                match get_clap_mcp_complete_with(&f.attrs) {
+                    Ok(Some(path)) => {
+                        arg_completer_bindings.push((root_name.clone(), arg_id.clone(), path));
+                    }
+                    Ok(None) => {}
+                    Err(e) => return e.to_compile_error(),
+                }
                 if let Some(req) = get_clap_mcp_requires(&f.attrs) {
                     let req_id = if req.is_empty() { arg_id } else { req };
                     requires_args
@@ -1922,8 +2331,11 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
                         || !flatten_skip_entries.is_empty()
                         || !requires_args.is_empty()
                         || !task_tool_names.is_empty()
+                        || !elicit_missing_tools.is_empty()
+                        || !confirm_tools.is_empty()
                         || !serialize_tools.is_empty()
-                        || !serialize_topic_bindings.is_empty();
+                        || !serialize_topic_bindings.is_empty()
+                        || !arg_completer_bindings.is_empty();
                     if merge {
                         let skip_commands_lit = skip_commands.iter().map(|s| {
                             let lit = syn::LitStr::new(s, proc_macro2::Span::call_site());
@@ -1933,6 +2345,12 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
                             let lit = syn::LitStr::new(s, proc_macro2::Span::call_site());
                             quote! { #lit.to_string() }
                         });
+                        let elicit_missing_tools_lit = elicit_missing_tools.iter().map(|s| {
+                            let lit = syn::LitStr::new(s, proc_macro2::Span::call_site());
+                            quote! { #lit.to_string() }
+                        });
+                        let confirm_tools_entries =
+                            confirm_tools_quote(&quote::format_ident!("local"), &confirm_tools);
                         let skip_args_entries = skip_args.iter().map(|(k, v)| {
                             let k_lit = syn::LitStr::new(k, proc_macro2::Span::call_site());
                             let vs = v
@@ -1984,6 +2402,10 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
                             &quote::format_ident!("local"),
                             &serialize_topic_bindings,
                         );
+                        let arg_completer_entries = arg_completer_bindings_quote(
+                            &quote::format_ident!("local"),
+                            &arg_completer_bindings,
+                        );
                         let flatten_skip_stmts_local = quote_flatten_skip_stmts(
                             &flatten_skip_entries,
                             &quote::format_ident!("local"),
@@ -2012,12 +2434,15 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
                                     let mut local = clap_mcp::ClapMcpSchemaMetadata::default();
                                     local.skip_commands.extend([#(#skip_commands_lit),*]);
                                     local.task_tool_names.extend([#(#task_tool_names_lit),*]);
+                                    local.elicit_missing_tools.extend([#(#elicit_missing_tools_lit),*]);
+                                    #confirm_tools_entries
                                     local.task_augmented_tools = #task_augmented_tools_expr;
                                     #(#skip_args_entries)*
                                     #(#flatten_skip_stmts_local)*
                                     #(#requires_args_entries)*
                                     #(#serialize_tools_entries)*
                                     #serialize_topic_entries
+                                    #arg_completer_entries
                                     #(#flatten_topic_stmts_local)*
                                     #skip_root_assign_local
                                     #output_schema_assign_local
@@ -2109,6 +2534,8 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
     });
     let serialize_topic_entries =
         serialize_topic_bindings_quote(&quote::format_ident!("m"), &serialize_topic_bindings);
+    let arg_completer_entries =
+        arg_completer_bindings_quote(&quote::format_ident!("m"), &arg_completer_bindings);
     let flatten_skip_stmts =
         quote_flatten_skip_stmts(&flatten_skip_entries, &quote::format_ident!("m"));
     let flatten_topic_stmts = flatten_serialize_topic_cmds.iter().map(|(cmd, ty)| {
@@ -2124,6 +2551,28 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
         let lit = syn::LitStr::new(s, proc_macro2::Span::call_site());
         quote! { #lit.to_string() }
     });
+    let elicit_missing_tools_lit = elicit_missing_tools.iter().map(|s| {
+        let lit = syn::LitStr::new(s, proc_macro2::Span::call_site());
+        quote! { #lit.to_string() }
+    });
+    let confirm_tools_entries = confirm_tools_quote(&quote::format_ident!("m"), &confirm_tools);
+    let tool_max_concurrency_entries = tool_max_concurrency.iter().map(|(k, n)| {
+        let k_lit = syn::LitStr::new(k, proc_macro2::Span::call_site());
+        quote! {
+            m.tool_max_concurrency.insert(#k_lit.to_string(), #n);
+        }
+    });
+    let tool_execution_mode_entries = tool_execution_modes.iter().map(|(k, in_process)| {
+        let k_lit = syn::LitStr::new(k, proc_macro2::Span::call_site());
+        let mode = if *in_process {
+            quote! { clap_mcp::ClapMcpExecutionMode::InProcess }
+        } else {
+            quote! { clap_mcp::ClapMcpExecutionMode::Subprocess }
+        };
+        quote! {
+            m.tool_execution_modes.insert(#k_lit.to_string(), #mode);
+        }
+    });
 
     let warn_block = if warn_optional_positional {
         optional_positional_warn_block
@@ -2149,13 +2598,18 @@ fn build_schema_metadata_impl(input: &DeriveInput) -> proc_macro2::TokenStream {
                 #(#nested_merge_stmts)*
                 m.skip_commands.extend([#(#skip_commands_lit),*]);
                 m.task_tool_names.extend([#(#task_tool_names_lit),*]);
+                m.elicit_missing_tools.extend([#(#elicit_missing_tools_lit),*]);
+                #confirm_tools_entries
                 m.task_augmented_tools = m.task_augmented_tools || #task_augmented_tools_expr;
                 #(#skip_args_entries)*
                 #(#flatten_skip_stmts)*
                 #(#requires_args_entries)*
                 #(#serialize_tools_entries)*
                 #serialize_topic_entries
+                #arg_completer_entries
                 #(#flatten_topic_stmts)*
+                #(#tool_max_concurrency_entries)*
+                #(#tool_execution_mode_entries)*
                 #output_schema_assign
                 m
             }
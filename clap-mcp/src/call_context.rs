@@ -0,0 +1,61 @@
+//! Server handles installed while an in-process tool runs, so tool code can reach
+//! [`ResourceUpdateNotifier::current`], [`ClapMcpRegistry::current`],
+//! [`ClapMcpSampler::current`], and [`ClapMcpTask::current`] without capturing them.
+
+use crate::{
+    ClapMcpRegistry, ResourceUpdateNotifier, sampling::ClapMcpSampler, tasks::ClapMcpTask,
+};
+use std::{cell::RefCell, future::Future};
+
+tokio::task_local! {
+    static TASK_HANDLES: CallHandles;
+}
+
+thread_local! {
+    static THREAD_HANDLES: RefCell<Option<CallHandles>> = const { RefCell::new(None) };
+}
+
+/// Handles of the server running the current tool call.
+#[derive(Debug, Clone)]
+pub(crate) struct CallHandles {
+    pub resource_updates: ResourceUpdateNotifier,
+    pub registry: ClapMcpRegistry,
+    /// Set when the call's client declared sampling.
+    pub sampler: Option<ClapMcpSampler>,
+    /// Set for task-augmented calls.
+    pub task: Option<ClapMcpTask>,
+    /// Set for HTTP calls whose bearer token names a principal.
+    #[cfg(feature = "http")]
+    pub principal: Option<crate::HttpPrincipal>,
+}
+
+impl CallHandles {
+    /// Prefers the current async task's handles, then the current thread's.
+    pub(crate) fn current() -> Option<Self> {
+        TASK_HANDLES
+            .try_with(Clone::clone)
+            .ok()
+            .or_else(|| THREAD_HANDLES.with(|slot| slot.borrow().clone()))
+    }
+
+    /// Run `f` with these handles as [`Self::current`] on the current thread.
+    pub(crate) fn scope_sync<R>(&self, f: impl FnOnce() -> R) -> R {
+        let previous = THREAD_HANDLES.with(|slot| slot.replace(Some(self.clone())));
+        let _restore = RestoreThreadHandles(previous);
+        f()
+    }
+
+    /// Run `fut` with these handles as [`Self::current`] in the current async task.
+    pub(crate) async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        TASK_HANDLES.scope(self.clone(), fut).await
+    }
+}
+
+/// Restores the previous thread handles even if the scoped closure panics.
+struct RestoreThreadHandles(Option<CallHandles>);
+
+impl Drop for RestoreThreadHandles {
+    fn drop(&mut self) {
+        THREAD_HANDLES.with(|slot| slot.replace(self.0.take()));
+    }
+}
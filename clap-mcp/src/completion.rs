@@ -0,0 +1,293 @@
+//! `completion/complete` support for tool arguments.
+//!
+//! MCP completion references name a prompt or a resource template; there is no tool
+//! reference. clap-mcp therefore answers `ref/prompt` whose name is a **tool** name (when no
+//! prompt has that name) from the clap schema: `#[clap_mcp(complete_with)]` functions first,
+//! then `possible_values`, then path value hints, which list entries under the client's roots.
+
+// Roots remain functional in rmcp 3.x but are deprecated by SEP-2577.
+#![allow(deprecated)]
+
+use crate::{
+    ArgCompleterFn, ArgCompletionRequest, ClapMcpSchemaMetadata, ClapSchema, roots::SessionRoots,
+};
+use rmcp::model::{CompleteResult, CompletionInfo, Tool};
+use rmcp::{Peer, RoleServer};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+/// How one tool argument completes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ToolArgCompletion {
+    pub possible_values: Vec<String>,
+    pub path: Option<PathHint>,
+    pub completer: Option<ArgCompleterFn>,
+}
+
+/// Which filesystem entries a path value hint completes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PathHint {
+    Any,
+    Dir,
+}
+
+impl PathHint {
+    fn from_value_hint(hint: &str) -> Option<Self> {
+        match hint {
+            "AnyPath" | "FilePath" | "ExecutablePath" => Some(Self::Any),
+            "DirPath" => Some(Self::Dir),
+            _ => None,
+        }
+    }
+}
+
+/// Completion sources for every clap tool's arguments (tool name → arg id → source).
+pub(crate) fn tool_arg_completions(
+    schema: &ClapSchema,
+    tools: &[Tool],
+    metadata: &ClapMcpSchemaMetadata,
+) -> HashMap<String, HashMap<String, ToolArgCompletion>> {
+    let mut out = HashMap::new();
+    for tool in tools {
+        let completers = metadata.arg_completers.get(tool.name.as_ref());
+        let args: HashMap<String, ToolArgCompletion> =
+            crate::effective_args_for_tool(schema, &tool.name)
+                .into_iter()
+                .map(|arg| {
+                    let spec = ToolArgCompletion {
+                        possible_values: arg.possible_values,
+                        path: arg
+                            .value_hint
+                            .as_deref()
+                            .and_then(PathHint::from_value_hint),
+                        completer: completers.and_then(|c| c.get(&arg.id)).copied(),
+                    };
+                    (arg.id, spec)
+                })
+                .collect();
+        out.insert(tool.name.to_string(), args);
+    }
+    out
+}
+
+/// Candidates for one tool argument. The client's roots are only requested for path hints.
+pub(crate) async fn complete_tool_arg(
+    spec: &ToolArgCompletion,
+    request: &ArgCompletionRequest<'_>,
+    roots: &SessionRoots,
+    peer: &Peer<RoleServer>,
+) -> Vec<String> {
+    if let Some(completer) = spec.completer {
+        return completer(request);
+    }
+    if !spec.possible_values.is_empty() {
+        return spec
+            .possible_values
+            .iter()
+            .filter(|v| v.starts_with(request.value))
+            .cloned()
+            .collect();
+    }
+    match spec.path {
+        Some(hint) => {
+            // Relative values resolve against the primary root when the tool runs.
+            let roots = roots.get(peer).await;
+            let cwd = match roots.first() {
+                Some(primary) => primary.clone(),
+                None => std::env::current_dir().unwrap_or_default(),
+            };
+            complete_path(&roots, &cwd, request.value, hint == PathHint::Dir)
+        }
+        None => Vec::new(),
+    }
+}
+
+/// `file:///a%20b` → `/a b`. Returns `None` for other schemes and non-local hosts.
+pub(crate) fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+    if !rest.starts_with('/') {
+        return None;
+    }
+    let bytes = rest.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = rest
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    Some(PathBuf::from(
+        String::from_utf8_lossy(&decoded).into_owned(),
+    ))
+}
+
+/// Complete `value` as a path the tool will resolve (relative values against `cwd`), listing
+/// only directories inside `roots`. Symlinks are resolved before the check, so a link inside a
+/// root cannot list a directory outside it; a directory that does not resolve lists nothing.
+/// When the typed directory is outside every root, offer the roots themselves. Directories
+/// end with `/`; dot-entries appear only once `.` is typed.
+pub(crate) fn complete_path(
+    roots: &[PathBuf],
+    cwd: &Path,
+    value: &str,
+    dirs_only: bool,
+) -> Vec<String> {
+    if roots.is_empty()
+        || Path::new(value)
+            .components()
+            .any(|c| matches!(c, Component::ParentDir))
+    {
+        return Vec::new();
+    }
+    let (dir_part, name_prefix) = match value.rfind('/') {
+        Some(i) => (&value[..=i], &value[i + 1..]),
+        None => ("", value),
+    };
+    let base = if Path::new(value).is_absolute() {
+        PathBuf::from(dir_part)
+    } else {
+        cwd.join(dir_part)
+    };
+    let Ok(base) = base.canonicalize() else {
+        return Vec::new();
+    };
+    if !roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| base.starts_with(root))
+    {
+        return roots
+            .iter()
+            .map(|root| format!("{}/", root.display()))
+            .filter(|root| root.starts_with(value))
+            .collect();
+    }
+    let Ok(entries) = std::fs::read_dir(&base) else {
+        return Vec::new();
+    };
+    let mut out = BTreeSet::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name.starts_with(name_prefix)
+            || (name.starts_with('.') && !name_prefix.starts_with('.'))
+        {
+            continue;
+        }
+        let is_dir = entry.path().is_dir();
+        if dirs_only && !is_dir {
+            continue;
+        }
+        out.insert(format!("{dir_part}{name}{}", if is_dir { "/" } else { "" }));
+    }
+    out.into_iter().collect()
+}
+
+/// Cap at [`CompletionInfo::MAX_VALUES`], reporting the full count in `total` / `hasMore`.
+pub(crate) fn completion_result(mut values: Vec<String>) -> CompleteResult {
+    let total = values.len();
+    values.truncate(CompletionInfo::MAX_VALUES);
+    let info = CompletionInfo::with_pagination(
+        values,
+        Some(u32::try_from(total).unwrap_or(u32::MAX)),
+        total > CompletionInfo::MAX_VALUES,
+    )
+    .expect("values are truncated to MAX_VALUES");
+    CompleteResult::new(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("clap-mcp-completion-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "").unwrap();
+        std::fs::write(dir.join("src/main.rs"), "").unwrap();
+        std::fs::write(dir.join(".hidden"), "").unwrap();
+        dir
+    }
+
+    #[test]
+    fn file_uri_to_path_decodes_local_uris_only() {
+        assert_eq!(
+            file_uri_to_path("file:///home/me/my%20project"),
+            Some(PathBuf::from("/home/me/my project"))
+        );
+        assert_eq!(
+            file_uri_to_path("file://localhost/tmp"),
+            Some(PathBuf::from("/tmp"))
+        );
+        assert_eq!(file_uri_to_path("file://server/share"), None);
+        assert_eq!(file_uri_to_path("https://example.com/"), None);
+    }
+
+    #[test]
+    fn complete_path_lists_entries_inside_roots() {
+        let root = temp_root("inside");
+        let roots = [root.clone()];
+        assert_eq!(
+            complete_path(&roots, &root, "", false),
+            vec!["Cargo.toml".to_string(), "src/".to_string()]
+        );
+        assert_eq!(complete_path(&roots, &root, "", true), vec!["src/"]);
+        assert_eq!(
+            complete_path(&roots, &root, "src/m", false),
+            vec!["src/main.rs"]
+        );
+        assert_eq!(complete_path(&roots, &root, ".h", false), vec![".hidden"]);
+        let absolute = format!("{}/C", root.display());
+        assert_eq!(
+            complete_path(&roots, Path::new("/"), &absolute, false),
+            vec![format!("{}/Cargo.toml", root.display())]
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn complete_path_stays_within_roots() {
+        let root = temp_root("confined");
+        let roots = [root.clone()];
+        assert!(complete_path(&roots, &root, "../", false).is_empty());
+        assert!(complete_path(&[], &root, "", false).is_empty());
+        assert_eq!(
+            complete_path(&roots, Path::new("/"), "", false),
+            vec![format!("{}/", root.display())]
+        );
+        assert!(complete_path(&roots, Path::new("/"), "/etc/", false).is_empty());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn complete_path_does_not_follow_symlinks_out_of_roots() {
+        let root = temp_root("symlink");
+        let outside = temp_root("symlink-target");
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+        let roots = [root.clone()];
+        assert!(complete_path(&roots, &root, "link/", false).is_empty());
+        assert!(complete_path(&roots, &root, "link/C", false).is_empty());
+        assert_eq!(complete_path(&roots, &root, "l", false), vec!["link/"]);
+        std::fs::remove_dir_all(&root).unwrap();
+        std::fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn completion_result_caps_values_and_reports_total() {
+        let result = completion_result((0..150).map(|i| i.to_string()).collect());
+        assert_eq!(result.completion.values.len(), CompletionInfo::MAX_VALUES);
+        assert_eq!(result.completion.total, Some(150));
+        assert_eq!(result.completion.has_more, Some(true));
+    }
+}
@@ -279,6 +279,52 @@ impl CustomPrompt {
     }
 }
 
+/// What a `completion/complete` request refers to, for [`CompletionProvider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionReference<'a> {
+    /// A [`CustomPrompt`] by name (`ref/prompt`).
+    Prompt(&'a str),
+    /// A [`CustomResourceTemplate`] by URI template (`ref/resource`).
+    ResourceTemplate(&'a str),
+}
+
+/// Completes [`CustomPrompt`] arguments and [`CustomResourceTemplate`] `{param}` placeholders.
+///
+/// Set on [`crate::ClapMcpServeOptions::completion_provider`]. Called only for arguments the
+/// prompt declares or placeholders the template contains; return candidates for `value` (the
+/// partial input). The server sends at most 100 and reports the rest via `total` / `hasMore`.
+#[async_trait]
+pub trait CompletionProvider: Send + Sync {
+    /// Return completion candidates for `argument` of `reference`.
+    async fn complete(
+        &self,
+        reference: CompletionReference<'_>,
+        argument: &str,
+        value: &str,
+        context: &HashMap<String, String>,
+    ) -> std::result::Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+impl std::fmt::Debug for dyn CompletionProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CompletionProvider")
+    }
+}
+
+/// `{param}` placeholder names in a [`CustomResourceTemplate::uri_template`], in order.
+pub fn uri_template_params(template: &str) -> Vec<&str> {
+    let mut params = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start + 1..].find('}') else {
+            break;
+        };
+        params.push(&rest[start + 1..start + 1 + len]);
+        rest = &rest[start + len + 2..];
+    }
+    params
+}
+
 /// Resolve custom resource content (static text, static blob, or await dynamic text).
 pub async fn resolve_resource_content(
     r: &CustomResource,
@@ -0,0 +1,293 @@
+//! Asking the user for input during `tools/call` via `elicitation/create` (form mode).
+//!
+//! - `#[clap_mcp(elicit_missing)]`: when a call leaves required args out, the server sends a
+//!   flat form built from the missing [`ClapArg`]s and merges the answers into the call's
+//!   arguments. Clients without form support, schemas that cannot be expressed as a form, and
+//!   failed requests leave the arguments as they are, so the call fails with the usual
+//!   "Missing required argument(s)" error.
+//! - `#[clap_mcp(confirm = "...")]`: the user must confirm the call before it runs. Unlike
+//!   missing args this fails closed: no form support or a failed request refuses the call.
+
+use crate::{ClapArg, ClapSchema};
+use rmcp::{
+    Peer,
+    model::{
+        BooleanSchema, CallToolResult, ContentBlock, ElicitRequestParams, ElicitationAction,
+        ElicitationSchema, EnumSchema, IntegerSchema, PrimitiveSchemaDefinition, StringSchema,
+    },
+    service::{ElicitationMode, RoleServer},
+};
+
+/// Fill missing required args of `tool` in `arguments` from the user's answers. Returns a tool
+/// error result when the user declines or cancels.
+pub(crate) async fn elicit_missing_args(
+    peer: &Peer<RoleServer>,
+    schema: &ClapSchema,
+    tool: &str,
+    arguments: &mut serde_json::Map<String, serde_json::Value>,
+) -> Option<CallToolResult> {
+    if !peer
+        .supported_elicitation_modes()
+        .contains(&ElicitationMode::Form)
+    {
+        return None;
+    }
+    let missing = crate::missing_required_args(schema, tool, arguments);
+    if missing.is_empty() {
+        return None;
+    }
+    let requested_schema = elicitation_schema(&missing)?;
+    let names: Vec<&str> = missing.iter().map(|a| a.id.as_str()).collect();
+    let result = peer
+        .create_elicitation(ElicitRequestParams::FormElicitationParams {
+            meta: None,
+            message: format!(
+                "Tool '{tool}' needs the following argument(s): {}",
+                names.join(", ")
+            ),
+            requested_schema,
+        })
+        .await
+        .ok()?;
+    match result.action {
+        ElicitationAction::Accept => {
+            if let Some(serde_json::Value::Object(answers)) = result.content {
+                merge_answers(&missing, answers, arguments);
+            }
+            None
+        }
+        ElicitationAction::Decline => Some(refused("declined", &names)),
+        _ => Some(refused("cancelled", &names)),
+    }
+}
+
+fn refused(verb: &str, names: &[&str]) -> CallToolResult {
+    CallToolResult::error(vec![ContentBlock::text(format!(
+        "User {verb} the request for required argument(s): {}",
+        names.join(", ")
+    ))])
+}
+
+/// Ask the user to confirm running `tool` with `arguments`, showing `template` with `{arg}`
+/// placeholders filled in. Returns a tool error result unless the user confirms.
+pub(crate) async fn confirm_call(
+    peer: &Peer<RoleServer>,
+    tool: &str,
+    template: &str,
+    arguments: &serde_json::Map<String, serde_json::Value>,
+) -> Option<CallToolResult> {
+    if !peer
+        .supported_elicitation_modes()
+        .contains(&ElicitationMode::Form)
+    {
+        return Some(CallToolResult::error(vec![ContentBlock::text(format!(
+            "Tool '{tool}' requires user confirmation, but the client does not support \
+             elicitation; refusing to run it"
+        ))]));
+    }
+    let requested_schema = ElicitationSchema::builder()
+        .required_property(
+            CONFIRM_PROPERTY,
+            PrimitiveSchemaDefinition::Boolean(
+                BooleanSchema::new()
+                    .title("Confirm")
+                    .description(format!("Run '{tool}'"))
+                    .with_default(false),
+            ),
+        )
+        .build_unchecked();
+    let result = peer
+        .create_elicitation(ElicitRequestParams::FormElicitationParams {
+            meta: None,
+            message: render_message(template, arguments),
+            requested_schema,
+        })
+        .await;
+    let confirmed = match result {
+        Ok(result) => {
+            result.action == ElicitationAction::Accept
+                && result
+                    .content
+                    .as_ref()
+                    .and_then(|c| c.get(CONFIRM_PROPERTY))
+                    .and_then(serde_json::Value::as_bool)
+                    == Some(true)
+        }
+        Err(error) => {
+            return Some(CallToolResult::error(vec![ContentBlock::text(format!(
+                "Tool '{tool}' requires user confirmation, but the request failed: {error}"
+            ))]));
+        }
+    };
+    (!confirmed).then(|| {
+        CallToolResult::error(vec![ContentBlock::text(format!(
+            "User did not confirm running '{tool}'"
+        ))])
+    })
+}
+
+const CONFIRM_PROPERTY: &str = "confirm";
+
+/// Replace `{arg}` placeholders with argument values (lists joined with ", "); unknown or
+/// unset args render as `(not set)`.
+fn render_message(
+    template: &str,
+    arguments: &serde_json::Map<String, serde_json::Value>,
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) if !after[..end].contains('{') => {
+                let value = arguments
+                    .get(&after[..end])
+                    .and_then(crate::value_to_strings)
+                    .filter(|values| !values.is_empty())
+                    .map(|values| values.join(", "));
+                out.push_str(value.as_deref().unwrap_or("(not set)"));
+                rest = &after[end + 1..];
+            }
+            _ => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Form schema with one required property per missing arg, typed like the tool's input schema.
+fn elicitation_schema(missing: &[ClapArg]) -> Option<ElicitationSchema> {
+    missing
+        .iter()
+        .fold(ElicitationSchema::builder(), |builder, arg| {
+            builder.required_property(arg.id.clone(), property_schema(arg))
+        })
+        .build()
+        .ok()
+}
+
+fn property_schema(arg: &ClapArg) -> PrimitiveSchemaDefinition {
+    let description = arg
+        .help
+        .clone()
+        .or_else(|| arg.long_help.clone())
+        .or_else(|| arg.value_names.first().cloned())
+        .unwrap_or_else(|| arg.id.clone());
+    let action = arg.action.as_deref().unwrap_or("Set");
+    if matches!(action, "SetTrue" | "SetFalse") {
+        PrimitiveSchemaDefinition::Boolean(BooleanSchema::new().description(description))
+    } else if action == "Count" {
+        PrimitiveSchemaDefinition::Integer(IntegerSchema::new().minimum(0).description(description))
+    } else if !arg.possible_values.is_empty() {
+        let values = EnumSchema::builder(arg.possible_values.clone()).description(description);
+        PrimitiveSchemaDefinition::Enum(if crate::arg_takes_multiple_values(arg) {
+            values.multiselect().build()
+        } else {
+            values.build()
+        })
+    } else {
+        PrimitiveSchemaDefinition::String(StringSchema::new().description(description))
+    }
+}
+
+/// Copy accepted answers for the missing args; a free-text answer for a multi-valued arg
+/// becomes a one-element list.
+fn merge_answers(
+    missing: &[ClapArg],
+    mut answers: serde_json::Map<String, serde_json::Value>,
+    arguments: &mut serde_json::Map<String, serde_json::Value>,
+) {
+    for arg in missing {
+        let Some(value) = answers.remove(&arg.id).filter(|v| !v.is_null()) else {
+            continue;
+        };
+        let value = match value {
+            serde_json::Value::String(s) if crate::arg_takes_multiple_values(arg) => {
+                serde_json::Value::Array(vec![serde_json::Value::String(s)])
+            }
+            value => value,
+        };
+        arguments.insert(arg.id.clone(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arg(id: &str, action: &str) -> ClapArg {
+        ClapArg {
+            id: id.into(),
+            long: Some(id.into()),
+            short: None,
+            help: None,
+            long_help: None,
+            required: true,
+            global: false,
+            index: None,
+            action: Some(action.into()),
+            value_names: Vec::new(),
+            num_args: None,
+            possible_values: Vec::new(),
+            value_hint: None,
+        }
+    }
+
+    #[test]
+    fn schema_types_follow_arg_actions() {
+        let mut format = arg("format", "Set");
+        format.possible_values = vec!["json".into(), "text".into()];
+        format.help = Some("Output format".into());
+        let schema = elicitation_schema(&[
+            arg("name", "Set"),
+            arg("force", "SetTrue"),
+            arg("verbose", "Count"),
+            format,
+        ])
+        .expect("schema");
+        let json = serde_json::to_value(&schema).expect("json");
+        assert_eq!(json["properties"]["name"]["type"], "string");
+        assert_eq!(json["properties"]["force"]["type"], "boolean");
+        assert_eq!(json["properties"]["verbose"]["type"], "integer");
+        assert_eq!(
+            json["properties"]["format"]["enum"],
+            serde_json::json!(["json", "text"])
+        );
+        assert_eq!(json["properties"]["format"]["description"], "Output format");
+        assert_eq!(json["required"].as_array().map(Vec::len), Some(4));
+    }
+
+    #[test]
+    fn confirm_message_fills_placeholders() {
+        let arguments = serde_json::json!({ "name": "db", "tags": ["a", "b"], "force": true });
+        let arguments = arguments.as_object().expect("object");
+        assert_eq!(
+            render_message("Delete {name} ({tags}, force={force})?", arguments),
+            "Delete db (a, b, force=true)?"
+        );
+        assert_eq!(
+            render_message("Prune {missing} {unclosed", arguments),
+            "Prune (not set) {unclosed"
+        );
+    }
+
+    #[test]
+    fn answers_for_multi_valued_args_become_lists() {
+        let missing = [arg("name", "Set"), arg("tags", "Append")];
+        let answers = serde_json::json!({ "name": "a", "tags": "b", "other": "ignored" });
+        let mut arguments = serde_json::Map::new();
+        merge_answers(
+            &missing,
+            answers.as_object().cloned().expect("object"),
+            &mut arguments,
+        );
+        assert_eq!(
+            serde_json::Value::Object(arguments),
+            serde_json::json!({ "name": "a", "tags": ["b"] })
+        );
+    }
+}
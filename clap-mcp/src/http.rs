@@ -1,14 +1,205 @@
 //! Streamable HTTP MCP transport (optional `http` feature).
 
 use crate::{
-    ClapMcpConfig, ClapMcpError, ClapMcpSchemaMetadata, ClapMcpServeOptions, InProcessToolHandler,
+    ClapMcpConfig, ClapMcpError, ClapMcpSchemaMetadata, ClapMcpServeOptions, HttpEndpoint,
+    MCP_HTTP_TOKEN_ENV, MCP_HTTP_TOKEN_FILE_ENV, ToolHandler,
+    call_context::CallHandles,
+    http_endpoint::Cors,
     server::{self, ClapMcpServer, build_clap_mcp_server},
 };
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use rmcp::service::{RequestContext, RoleServer};
 use rmcp::transport::streamable_http_server::{
     StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
 };
 use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 
+/// Bearer-token authentication for the Streamable HTTP endpoint.
+///
+/// Every request to `/mcp` must carry `Authorization: Bearer <token>` with one of the
+/// configured tokens; anything else gets `401 Unauthorized` before it reaches MCP. A token
+/// added with [`with_principal`](Self::with_principal) also names the caller, which tools see
+/// as [`HttpPrincipal::current`] (in-process) or [`MCP_HTTP_PRINCIPAL_ENV`](crate::MCP_HTTP_PRINCIPAL_ENV)
+/// (subprocess).
+///
+/// Set it with [`ClapMcpServeOptions::http_auth`]. When unset, [`HttpAuth::from_env`] is used,
+/// so `--mcp-http` users can require a token without code changes.
+///
+/// # Example
+///
+/// ```
+/// use clap_mcp::{ClapMcpServeOptions, HttpAuth};
+///
+/// let opts = ClapMcpServeOptions {
+///     http_auth: Some(
+///         HttpAuth::default()
+///             .with_principal("token-for-alice", "alice")
+///             .with_principal("token-for-ci", "ci"),
+///     ),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Default)]
+pub struct HttpAuth {
+    tokens: Vec<(String, Option<HttpPrincipal>)>,
+}
+
+impl std::fmt::Debug for HttpAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let principals: Vec<_> = self
+            .tokens
+            .iter()
+            .map(|(_, principal)| principal.as_ref().map(HttpPrincipal::name))
+            .collect();
+        f.debug_struct("HttpAuth")
+            .field("principals", &principals)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpAuth {
+    /// Accept a single static token with no principal.
+    pub fn bearer(token: impl Into<String>) -> Self {
+        Self {
+            tokens: vec![(token.into(), None)],
+        }
+    }
+
+    /// Also accept `token`, identifying its caller as `principal`.
+    pub fn with_principal(
+        mut self,
+        token: impl Into<String>,
+        principal: impl Into<String>,
+    ) -> Self {
+        self.tokens
+            .push((token.into(), Some(HttpPrincipal(principal.into().into()))));
+        self
+    }
+
+    /// A static token from [`MCP_HTTP_TOKEN_ENV`], or else from the file named by
+    /// [`MCP_HTTP_TOKEN_FILE_ENV`] (surrounding whitespace trimmed). `Ok(None)` when neither is
+    /// set.
+    pub fn from_env() -> Result<Option<Self>, ClapMcpError> {
+        if let Ok(token) = std::env::var(MCP_HTTP_TOKEN_ENV) {
+            return Ok(Some(Self::bearer(token)));
+        }
+        let Some(path) = std::env::var_os(MCP_HTTP_TOKEN_FILE_ENV) else {
+            return Ok(None);
+        };
+        let token = std::fs::read_to_string(&path).map_err(|e| {
+            ClapMcpError::InvalidConfig(format!(
+                "cannot read {MCP_HTTP_TOKEN_FILE_ENV} file {}: {e}",
+                PathBuf::from(&path).display()
+            ))
+        })?;
+        Ok(Some(Self::bearer(token.trim())))
+    }
+
+    fn validate(&self) -> Result<(), ClapMcpError> {
+        if self.tokens.is_empty() || self.tokens.iter().any(|(token, _)| token.is_empty()) {
+            return Err(ClapMcpError::InvalidConfig(
+                "http_auth needs at least one token, and tokens must be non-empty".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The principal for an accepted `Authorization` header (`Ok(None)` for a token without one).
+    fn authorize(
+        &self,
+        authorization: Option<&HeaderValue>,
+    ) -> Result<Option<HttpPrincipal>, AuthRejection> {
+        let presented = authorization
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+            .map(|(_, token)| token.trim())
+            .ok_or(AuthRejection::Missing)?;
+        // Check every token so the response time does not reveal which one nearly matched.
+        let mut accepted = None;
+        for (token, principal) in &self.tokens {
+            if constant_time_eq(token.as_bytes(), presented.as_bytes()) && accepted.is_none() {
+                accepted = Some(principal.clone());
+            }
+        }
+        accepted.ok_or(AuthRejection::Invalid)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Why a request was refused, mapped to the RFC 6750 `WWW-Authenticate` challenge.
+enum AuthRejection {
+    Missing,
+    Invalid,
+}
+
+impl IntoResponse for AuthRejection {
+    fn into_response(self) -> Response {
+        let (challenge, body) = match self {
+            Self::Missing => (r#"Bearer realm="mcp""#, "missing bearer token"),
+            Self::Invalid => (
+                r#"Bearer realm="mcp", error="invalid_token""#,
+                "invalid bearer token",
+            ),
+        };
+        (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, challenge)],
+            body,
+        )
+            .into_response()
+    }
+}
+
+async fn require_bearer(
+    State(auth): State<Arc<HttpAuth>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    match auth.authorize(request.headers().get(header::AUTHORIZATION)) {
+        Ok(principal) => {
+            if let Some(principal) = principal {
+                request.extensions_mut().insert(principal);
+            }
+            next.run(request).await
+        }
+        Err(rejection) => rejection.into_response(),
+    }
+}
+
+/// The caller of an HTTP tool call, as named by its bearer token in [`HttpAuth`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpPrincipal(Arc<str>);
+
+impl HttpPrincipal {
+    /// The principal of the current in-process tool call, if its token named one.
+    pub fn current() -> Option<Self> {
+        CallHandles::current().and_then(|handles| handles.principal)
+    }
+
+    /// The principal name given to [`HttpAuth::with_principal`].
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// Reads the principal the auth layer attached to the HTTP request behind `context`.
+    pub(crate) fn from_context(context: &RequestContext<RoleServer>) -> Option<Self> {
+        context
+            .extensions
+            .get::<axum::http::request::Parts>()
+            .and_then(|parts| parts.extensions.get::<Self>())
+            .cloned()
+    }
+}
+
 fn allowed_hosts_for_listen(addr: SocketAddr) -> Vec<String> {
     let mut hosts = vec![
         "localhost".to_string(),
@@ -31,7 +222,7 @@ async fn build_server_from_schema_json(
     schema_json: String,
     executable_path: Option<PathBuf>,
     config: &ClapMcpConfig,
-    in_process_handler: Option<InProcessToolHandler>,
+    in_process_handler: Option<ToolHandler>,
     serve_options: &ClapMcpServeOptions,
     metadata: &ClapMcpSchemaMetadata,
 ) -> Result<ClapMcpServer, ClapMcpError> {
@@ -51,20 +242,119 @@ async fn build_server_from_schema_json(
 }
 
 /// Starts an MCP server over Streamable HTTP at `listen`, exposing `clap://schema`.
+///
+/// Runs until ctrl-c or SIGTERM.
 pub(crate) async fn serve_schema_json_over_http(
     listen: SocketAddr,
     schema_json: String,
     executable_path: Option<PathBuf>,
     config: ClapMcpConfig,
-    in_process_handler: Option<InProcessToolHandler>,
+    in_process_handler: Option<ToolHandler>,
     mut serve_options: ClapMcpServeOptions,
     metadata: &ClapMcpSchemaMetadata,
 ) -> Result<(), ClapMcpError> {
-    let server = build_server_from_schema_json(
+    #[cfg(feature = "http-tls")]
+    let tls_acceptor = match serve_options.http_tls.take() {
+        Some(tls) => Some(tls),
+        None => crate::HttpTls::from_env()?,
+    }
+    .map(|tls| tls.acceptor())
+    .transpose()?;
+    let router = build_router(
+        allowed_hosts_for_listen(listen),
+        schema_json,
+        executable_path,
+        &config,
+        in_process_handler,
+        serve_options,
+        metadata,
+    )
+    .await?;
+    let listener = tokio::net::TcpListener::bind(listen).await?;
+    #[cfg(feature = "http-tls")]
+    if let Some(acceptor) = tls_acceptor {
+        let listener = crate::http_tls::TlsListener::new(listener, acceptor);
+        return serve_until_shutdown(axum::serve(listener, router)).await;
+    }
+    serve_until_shutdown(axum::serve(listener, router)).await
+}
+
+/// Runs `serve` until it fails or the process gets ctrl-c or SIGTERM.
+async fn serve_until_shutdown(
+    serve: impl std::future::IntoFuture<Output = std::io::Result<()>>,
+) -> Result<(), ClapMcpError> {
+    tokio::select! {
+        served = serve.into_future() => served?,
+        () = crate::state::shutdown_signal() => {}
+    }
+    Ok(())
+}
+
+/// Starts an MCP server over Streamable HTTP on the Unix domain socket at `path`.
+///
+/// Runs until ctrl-c or SIGTERM, then removes the socket file.
+#[cfg(unix)]
+pub(crate) async fn serve_schema_json_over_unix(
+    path: PathBuf,
+    schema_json: String,
+    executable_path: Option<PathBuf>,
+    config: ClapMcpConfig,
+    in_process_handler: Option<ToolHandler>,
+    serve_options: ClapMcpServeOptions,
+    metadata: &ClapMcpSchemaMetadata,
+) -> Result<(), ClapMcpError> {
+    let mode = serve_options
+        .unix_socket_mode
+        .unwrap_or(crate::http_unix::DEFAULT_UNIX_SOCKET_MODE);
+    // Clients reach the socket by path, so only the default localhost Host values are allowed.
+    let router = build_router(
+        StreamableHttpServerConfig::default().allowed_hosts,
         schema_json,
         executable_path,
         &config,
         in_process_handler,
+        serve_options,
+        metadata,
+    )
+    .await?;
+    let (listener, _guard) = crate::http_unix::bind_unix_socket(&path, mode)?;
+    serve_until_shutdown(axum::serve(listener, router)).await
+}
+
+/// Builds the MCP server and its router at the [`HttpEndpoint`] route, behind [`HttpAuth`]
+/// and the CORS layer when configured. `allowed_hosts` is extended with the endpoint's.
+async fn build_router(
+    mut allowed_hosts: Vec<String>,
+    schema_json: String,
+    executable_path: Option<PathBuf>,
+    config: &ClapMcpConfig,
+    in_process_handler: Option<ToolHandler>,
+    mut serve_options: ClapMcpServeOptions,
+    metadata: &ClapMcpSchemaMetadata,
+) -> Result<axum::Router, ClapMcpError> {
+    let auth = match serve_options.http_auth.take() {
+        Some(auth) => Some(auth),
+        None => HttpAuth::from_env()?,
+    };
+    if let Some(auth) = &auth {
+        auth.validate()?;
+    }
+    let endpoint = serve_options
+        .http_endpoint
+        .take()
+        .unwrap_or_else(HttpEndpoint::from_env);
+    let route = endpoint.route()?;
+    let cors = Cors::new(&endpoint.allowed_origins)?;
+    allowed_hosts.extend(endpoint.allowed_hosts);
+    let mut http_config = StreamableHttpServerConfig::default().with_allowed_hosts(allowed_hosts);
+    if let Some(cors) = &cors {
+        http_config = http_config.with_allowed_origins(cors.allowed_origins());
+    }
+    let server = build_server_from_schema_json(
+        schema_json,
+        executable_path,
+        config,
+        in_process_handler,
         &serve_options,
         metadata,
     )
@@ -73,27 +363,41 @@ pub(crate) async fn serve_schema_json_over_http(
     server::spawn_log_forwarder(&server, serve_options.log_rx.take());
 
     let server = Arc::new(server);
-    let http_config =
-        StreamableHttpServerConfig::default().with_allowed_hosts(allowed_hosts_for_listen(listen));
-
     let service = StreamableHttpService::new(
         {
             let server = server.clone();
-            move || Ok((*server).clone())
+            move || Ok(server.for_new_session())
         },
         Arc::new(LocalSessionManager::default()),
         http_config,
     );
 
-    let router = axum::Router::new().nest_service("/mcp", service);
-    let listener = tokio::net::TcpListener::bind(listen).await?;
-    axum::serve(listener, router).await?;
-    Ok(())
+    // `nest_service` cannot mount at the root.
+    let mut router = if route == "/" {
+        axum::Router::new().route_service("/", service)
+    } else {
+        axum::Router::new().nest_service(&route, service)
+    };
+    if let Some(auth) = auth {
+        router = router.layer(axum::middleware::from_fn_with_state(
+            Arc::new(auth),
+            require_bearer,
+        ));
+    }
+    // Outermost, so preflights are answered before auth.
+    if let Some(cors) = cors {
+        router = router.layer(axum::middleware::from_fn_with_state(
+            Arc::new(cors),
+            crate::http_endpoint::cors,
+        ));
+    }
+    Ok(router)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::allowed_hosts_for_listen;
+    use super::{AuthRejection, HttpAuth, allowed_hosts_for_listen};
+    use axum::http::HeaderValue;
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
     #[test]
@@ -116,4 +420,37 @@ mod tests {
         let hosts = allowed_hosts_for_listen(addr);
         assert!(hosts.contains(&"0.0.0.0:9000".to_string()));
     }
+
+    #[test]
+    fn authorize_maps_tokens_to_principals() {
+        let auth = HttpAuth::bearer("shared").with_principal("alice-token", "alice");
+        let header = |value: &str| HeaderValue::from_str(value).unwrap();
+
+        assert_eq!(
+            auth.authorize(Some(&header("Bearer shared"))).ok(),
+            Some(None)
+        );
+        let principal = auth
+            .authorize(Some(&header("bearer alice-token")))
+            .ok()
+            .flatten()
+            .expect("alice's principal");
+        assert_eq!(principal.name(), "alice");
+        assert!(matches!(auth.authorize(None), Err(AuthRejection::Missing)));
+        assert!(matches!(
+            auth.authorize(Some(&header("Basic c2hhcmVk"))),
+            Err(AuthRejection::Missing)
+        ));
+        assert!(matches!(
+            auth.authorize(Some(&header("Bearer shared2"))),
+            Err(AuthRejection::Invalid)
+        ));
+    }
+
+    #[test]
+    fn empty_tokens_are_rejected() {
+        assert!(HttpAuth::default().validate().is_err());
+        assert!(HttpAuth::bearer("").validate().is_err());
+        assert!(HttpAuth::bearer("t").validate().is_ok());
+    }
 }
@@ -0,0 +1,325 @@
+//! Endpoint path, extra allowed hosts, and CORS for the Streamable HTTP transport (`http` feature).
+
+use crate::{
+    ClapMcpError, MCP_HTTP_ALLOWED_HOSTS_ENV, MCP_HTTP_ALLOWED_ORIGINS_ENV, MCP_HTTP_BASE_PATH_ENV,
+    MCP_HTTP_PATH_ENV,
+};
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+/// Route of the MCP endpoint when [`HttpEndpoint::path`] is unset.
+pub(crate) const DEFAULT_HTTP_PATH: &str = "/mcp";
+
+/// Request headers browsers may send cross-origin: auth, content negotiation, and the MCP
+/// session and resumption headers.
+const CORS_ALLOW_HEADERS: &str =
+    "authorization, content-type, accept, last-event-id, mcp-session-id, mcp-protocol-version";
+
+/// Response headers browser clients need to read.
+const CORS_EXPOSE_HEADERS: &str = "mcp-session-id, mcp-protocol-version, www-authenticate";
+
+/// Where the Streamable HTTP endpoint is mounted and which hosts and browser origins may
+/// call it.
+///
+/// Set it with [`ClapMcpServeOptions::http_endpoint`](crate::ClapMcpServeOptions::http_endpoint).
+/// When unset, [`HttpEndpoint::from_env`] is used.
+///
+/// # Example
+///
+/// ```
+/// use clap_mcp::{ClapMcpServeOptions, HttpEndpoint};
+///
+/// let opts = ClapMcpServeOptions {
+///     http_endpoint: Some(HttpEndpoint {
+///         base_path: Some("/tools/myctl".into()),
+///         allowed_hosts: vec!["myctl.local".into()],
+///         allowed_origins: vec!["https://console.example.com".into()],
+///         ..Default::default()
+///     }),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpEndpoint {
+    /// Route of the MCP endpoint. `None` (default) is `/mcp`.
+    pub path: Option<String>,
+    /// Prefix a reverse proxy forwards without stripping, such as `/tools/myctl`. The
+    /// endpoint is then served at `/tools/myctl/mcp`.
+    pub base_path: Option<String>,
+    /// `Host` values accepted besides the loopback names and bind address, as `host` or
+    /// `host:port`.
+    pub allowed_hosts: Vec<String>,
+    /// Browser origins allowed to call the endpoint, as `scheme://host[:port]` (`:*` for any
+    /// port; no port means the scheme's default). Requests with another `Origin` get
+    /// `403 Forbidden`, and allowed ones get CORS headers. Empty (default) leaves `Origin`
+    /// unchecked and sends no CORS headers.
+    pub allowed_origins: Vec<String>,
+}
+
+impl HttpEndpoint {
+    /// Settings from [`MCP_HTTP_PATH_ENV`], [`MCP_HTTP_BASE_PATH_ENV`], and the
+    /// comma-separated lists [`MCP_HTTP_ALLOWED_HOSTS_ENV`] and [`MCP_HTTP_ALLOWED_ORIGINS_ENV`].
+    /// Unset variables keep the defaults.
+    pub fn from_env() -> Self {
+        let var = |key| std::env::var(key).ok().filter(|v| !v.trim().is_empty());
+        let list = |key| {
+            var(key)
+                .map(|v: String| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Self {
+            path: var(MCP_HTTP_PATH_ENV),
+            base_path: var(MCP_HTTP_BASE_PATH_ENV),
+            allowed_hosts: list(MCP_HTTP_ALLOWED_HOSTS_ENV),
+            allowed_origins: list(MCP_HTTP_ALLOWED_ORIGINS_ENV),
+        }
+    }
+
+    /// The route to mount: [`base_path`](Self::base_path) followed by [`path`](Self::path).
+    pub(crate) fn route(&self) -> Result<String, ClapMcpError> {
+        let path = normalize_path(self.path.as_deref().unwrap_or(DEFAULT_HTTP_PATH), "path")?;
+        let base_path = match &self.base_path {
+            Some(base_path) => normalize_path(base_path, "base path")?,
+            None => String::new(),
+        };
+        let route = format!("{base_path}{path}");
+        Ok(if route.is_empty() { "/".into() } else { route })
+    }
+}
+
+/// Checks a configured path and drops its trailing `/` (so `/` becomes empty).
+fn normalize_path(value: &str, what: &str) -> Result<String, ClapMcpError> {
+    let valid = value.starts_with('/')
+        && !value.contains("//")
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '?' | '#' | '{' | '}' | '*' | ':'));
+    if !valid {
+        return Err(ClapMcpError::InvalidConfig(format!(
+            "invalid HTTP {what} {value:?}: expected an absolute path such as \"/mcp\""
+        )));
+    }
+    Ok(value.trim_end_matches('/').to_string())
+}
+
+/// An origin's `(scheme, host, port)`; `port` is `None` for the `:*` wildcard.
+#[derive(Debug, PartialEq, Eq)]
+struct Origin {
+    scheme: String,
+    host: String,
+    port: Option<u16>,
+}
+
+impl Origin {
+    /// Parses `scheme://host[:port]`; an omitted port is the scheme's default.
+    fn parse(value: &str, allow_any_port: bool) -> Option<Self> {
+        let (scheme, authority) = value.split_once("://")?;
+        let scheme = scheme.to_ascii_lowercase();
+        let default_port = match scheme.as_str() {
+            "http" => 80,
+            "https" => 443,
+            _ => return None,
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if !port.contains(']') => (host, Some(port)),
+            _ => (authority, None),
+        };
+        if host.is_empty() || host.contains(['/', '@', '?', '#']) {
+            return None;
+        }
+        let port = match port {
+            None => Some(default_port),
+            Some("*") if allow_any_port => None,
+            Some(port) => Some(port.parse().ok()?),
+        };
+        Some(Self {
+            scheme,
+            host: host.to_ascii_lowercase(),
+            port,
+        })
+    }
+
+    fn matches(&self, origin: &Self) -> bool {
+        self.scheme == origin.scheme
+            && self.host == origin.host
+            && self.port.is_none_or(|port| origin.port == Some(port))
+    }
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.port {
+            Some(port) => write!(f, "{}://{}:{port}", self.scheme, self.host),
+            None => write!(f, "{}://{}:*", self.scheme, self.host),
+        }
+    }
+}
+
+/// Parsed [`HttpEndpoint::allowed_origins`], shared by the CORS layer and rmcp's `Origin` check.
+pub(crate) struct Cors {
+    origins: Vec<Origin>,
+}
+
+impl Cors {
+    /// `Ok(None)` when no origins are configured; an unparsable entry is an error.
+    pub(crate) fn new(allowed_origins: &[String]) -> Result<Option<Self>, ClapMcpError> {
+        if allowed_origins.is_empty() {
+            return Ok(None);
+        }
+        let origins = allowed_origins
+            .iter()
+            .map(|entry| {
+                Origin::parse(entry, true).ok_or_else(|| {
+                    ClapMcpError::InvalidConfig(format!(
+                        "invalid HTTP allowed origin {entry:?}: expected scheme://host[:port]"
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Some(Self { origins }))
+    }
+
+    /// Entries with explicit ports, so rmcp's check agrees with [`Self::allows`].
+    pub(crate) fn allowed_origins(&self) -> Vec<String> {
+        self.origins.iter().map(Origin::to_string).collect()
+    }
+
+    fn allows(&self, origin: &HeaderValue) -> bool {
+        let Some(origin) = origin.to_str().ok().and_then(|o| Origin::parse(o, false)) else {
+            return false;
+        };
+        self.origins.iter().any(|allowed| allowed.matches(&origin))
+    }
+}
+
+/// Answers CORS preflights from allowed origins and adds CORS headers to their responses.
+/// Sits outside the bearer-token layer, since browsers send preflights without credentials;
+/// other requests still reach auth and rmcp's `Origin` check.
+pub(crate) async fn cors(State(cors): State<Arc<Cors>>, request: Request, next: Next) -> Response {
+    let origin = request
+        .headers()
+        .get(header::ORIGIN)
+        .filter(|origin| cors.allows(origin))
+        .cloned();
+    let preflight = request.method() == Method::OPTIONS
+        && request
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+    let mut response = match &origin {
+        Some(_) if preflight => {
+            let mut response = StatusCode::NO_CONTENT.into_response();
+            let headers = response.headers_mut();
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_METHODS,
+                HeaderValue::from_static("GET, POST, DELETE"),
+            );
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                HeaderValue::from_static(CORS_ALLOW_HEADERS),
+            );
+            headers.insert(
+                header::ACCESS_CONTROL_MAX_AGE,
+                HeaderValue::from_static("600"),
+            );
+            response
+        }
+        _ => next.run(request).await,
+    };
+    let headers = response.headers_mut();
+    headers.append(header::VARY, HeaderValue::from_static("origin"));
+    if let Some(origin) = origin {
+        allow_origin(headers, origin);
+    }
+    response
+}
+
+fn allow_origin(headers: &mut HeaderMap, origin: HeaderValue) {
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.insert(
+        header::ACCESS_CONTROL_EXPOSE_HEADERS,
+        HeaderValue::from_static(CORS_EXPOSE_HEADERS),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cors, HttpEndpoint, Origin};
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn route_joins_base_path_and_path() {
+        let route = |path: Option<&str>, base_path: Option<&str>| {
+            HttpEndpoint {
+                path: path.map(String::from),
+                base_path: base_path.map(String::from),
+                ..Default::default()
+            }
+            .route()
+        };
+        assert_eq!(route(None, None).unwrap(), "/mcp");
+        assert_eq!(route(Some("/rpc/"), None).unwrap(), "/rpc");
+        assert_eq!(
+            route(None, Some("/tools/myctl/")).unwrap(),
+            "/tools/myctl/mcp"
+        );
+        assert_eq!(route(Some("/"), Some("/")).unwrap(), "/");
+        for bad in ["mcp", "/a b", "/{id}", "/a//b", "/mcp?x"] {
+            assert!(route(Some(bad), None).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn origins_match_scheme_host_and_port() {
+        let cors = Cors::new(&[
+            "https://Console.example.com".into(),
+            "http://localhost:*".into(),
+            "http://[::1]:8080".into(),
+        ])
+        .unwrap()
+        .expect("origins configured");
+        assert_eq!(
+            cors.allowed_origins(),
+            [
+                "https://console.example.com:443",
+                "http://localhost:*",
+                "http://[::1]:8080"
+            ]
+        );
+        for allowed in [
+            "https://console.example.com",
+            "https://console.example.com:443",
+            "http://localhost:5173",
+            "http://[::1]:8080",
+        ] {
+            assert!(cors.allows(&HeaderValue::from_static(allowed)), "{allowed}");
+        }
+        for refused in [
+            "http://console.example.com",
+            "https://console.example.com:8443",
+            "https://localhost:5173",
+            "http://[::1]",
+            "null",
+        ] {
+            assert!(
+                !cors.allows(&HeaderValue::from_static(refused)),
+                "{refused}"
+            );
+        }
+
+        assert!(Cors::new(&[]).unwrap().is_none());
+        assert!(Cors::new(&["console.example.com".into()]).is_err());
+        assert!(Cors::new(&["https://console.example.com/app".into()]).is_err());
+        assert_eq!(Origin::parse("https://a.example:*", false), None);
+    }
+}
@@ -0,0 +1,252 @@
+//! rustls TLS for the Streamable HTTP transport (optional `http-tls` feature).
+
+use crate::{
+    ClapMcpError, MCP_HTTP_TLS_CERT_ENV, MCP_HTTP_TLS_CLIENT_CA_ENV, MCP_HTTP_TLS_KEY_ENV,
+};
+use std::{io, net::SocketAddr, path::Path, sync::Arc, time::Duration};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    task::JoinSet,
+};
+use tokio_rustls::{
+    TlsAcceptor,
+    rustls::{
+        RootCertStore, ServerConfig,
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+        server::WebPkiClientVerifier,
+    },
+    server::TlsStream,
+};
+
+/// A connection that has not finished its TLS handshake by then is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Server certificate, key, and optional client CA for serving `--mcp-http` over HTTPS.
+///
+/// Set it with [`ClapMcpServeOptions::http_tls`](crate::ClapMcpServeOptions::http_tls). When
+/// unset, [`HttpTls::from_env`] is used. With [`require_client_cert`](Self::require_client_cert),
+/// only clients presenting a certificate issued by that CA can connect (mutual TLS).
+///
+/// # Example
+///
+/// ```no_run
+/// use clap_mcp::{ClapMcpServeOptions, HttpTls};
+///
+/// let tls = HttpTls::from_pem_files("server.crt", "server.key")?
+///     .require_client_cert_file("clients-ca.crt")?;
+/// let opts = ClapMcpServeOptions {
+///     http_tls: Some(tls),
+///     ..Default::default()
+/// };
+/// # Ok::<(), clap_mcp::ClapMcpError>(())
+/// ```
+#[derive(Clone)]
+pub struct HttpTls {
+    cert_chain: Vec<CertificateDer<'static>>,
+    key: Arc<PrivateKeyDer<'static>>,
+    client_roots: Option<Arc<RootCertStore>>,
+}
+
+impl std::fmt::Debug for HttpTls {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpTls")
+            .field("cert_chain_len", &self.cert_chain.len())
+            .field("client_cert_required", &self.client_roots.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpTls {
+    /// A PEM certificate chain (server certificate first) and PEM private key.
+    pub fn from_pem(cert_chain: &[u8], key: &[u8]) -> Result<Self, ClapMcpError> {
+        let cert_chain = parse_certs(cert_chain, "TLS certificate")?;
+        let key = PrivateKeyDer::from_pem_slice(key)
+            .map_err(|e| invalid(format!("TLS private key: {e}")))?;
+        Ok(Self {
+            cert_chain,
+            key: Arc::new(key),
+            client_roots: None,
+        })
+    }
+
+    /// [`from_pem`](Self::from_pem), reading both from files.
+    pub fn from_pem_files(
+        cert_chain: impl AsRef<Path>,
+        key: impl AsRef<Path>,
+    ) -> Result<Self, ClapMcpError> {
+        Self::from_pem(&read_pem(cert_chain.as_ref())?, &read_pem(key.as_ref())?)
+    }
+
+    /// Require clients to present a certificate issued by one of the PEM CA certificates.
+    pub fn require_client_cert(mut self, ca_certs: &[u8]) -> Result<Self, ClapMcpError> {
+        let mut roots = RootCertStore::empty();
+        for cert in parse_certs(ca_certs, "TLS client CA")? {
+            roots
+                .add(cert)
+                .map_err(|e| invalid(format!("TLS client CA: {e}")))?;
+        }
+        self.client_roots = Some(Arc::new(roots));
+        Ok(self)
+    }
+
+    /// [`require_client_cert`](Self::require_client_cert), reading the CA certificates from a file.
+    pub fn require_client_cert_file(
+        self,
+        ca_certs: impl AsRef<Path>,
+    ) -> Result<Self, ClapMcpError> {
+        let pem = read_pem(ca_certs.as_ref())?;
+        self.require_client_cert(&pem)
+    }
+
+    /// TLS settings from [`MCP_HTTP_TLS_CERT_ENV`] and [`MCP_HTTP_TLS_KEY_ENV`], plus
+    /// [`MCP_HTTP_TLS_CLIENT_CA_ENV`] for client certificates. Each holds a file path or the PEM
+    /// text itself. `Ok(None)` when none is set; setting only some of the first two is an error.
+    pub fn from_env() -> Result<Option<Self>, ClapMcpError> {
+        let cert = std::env::var_os(MCP_HTTP_TLS_CERT_ENV);
+        let key = std::env::var_os(MCP_HTTP_TLS_KEY_ENV);
+        let client_ca = std::env::var_os(MCP_HTTP_TLS_CLIENT_CA_ENV);
+        let (cert, key) = match (cert, key) {
+            (Some(cert), Some(key)) => (cert, key),
+            (None, None) if client_ca.is_none() => return Ok(None),
+            _ => {
+                return Err(invalid(format!(
+                    "set both {MCP_HTTP_TLS_CERT_ENV} and {MCP_HTTP_TLS_KEY_ENV} to serve HTTPS"
+                )));
+            }
+        };
+        let tls = Self::from_pem(&env_pem(&cert)?, &env_pem(&key)?)?;
+        match client_ca {
+            Some(client_ca) => tls.require_client_cert(&env_pem(&client_ca)?).map(Some),
+            None => Ok(Some(tls)),
+        }
+    }
+
+    /// Binds the rustls configuration; fails when the key does not match the certificate.
+    pub(crate) fn acceptor(&self) -> Result<TlsAcceptor, ClapMcpError> {
+        let provider = Arc::new(ring::default_provider());
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| invalid(format!("TLS: {e}")))?;
+        let builder = match &self.client_roots {
+            Some(roots) => {
+                let verifier = WebPkiClientVerifier::builder_with_provider(roots.clone(), provider)
+                    .build()
+                    .map_err(|e| invalid(format!("TLS client CA: {e}")))?;
+                builder.with_client_cert_verifier(verifier)
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let mut config = builder
+            .with_single_cert(self.cert_chain.clone(), self.key.clone_key())
+            .map_err(|e| invalid(format!("TLS certificate and key: {e}")))?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
+
+fn invalid(message: String) -> ClapMcpError {
+    ClapMcpError::InvalidConfig(message)
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>, ClapMcpError> {
+    std::fs::read(path).map_err(|e| invalid(format!("cannot read {}: {e}", path.display())))
+}
+
+/// An env value is PEM text when it contains a PEM header, otherwise a file path.
+fn env_pem(value: &std::ffi::OsStr) -> Result<Vec<u8>, ClapMcpError> {
+    match value.to_str() {
+        Some(text) if text.contains("-----BEGIN ") => Ok(text.as_bytes().to_vec()),
+        _ => read_pem(Path::new(value)),
+    }
+}
+
+fn parse_certs(pem: &[u8], what: &str) -> Result<Vec<CertificateDer<'static>>, ClapMcpError> {
+    let certs = CertificateDer::pem_slice_iter(pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| invalid(format!("{what}: {e}")))?;
+    if certs.is_empty() {
+        return Err(invalid(format!("{what}: no PEM certificates found")));
+    }
+    Ok(certs)
+}
+
+/// TCP listener that completes TLS handshakes off the accept path, so a slow or failing
+/// client does not hold up others. Connections that fail the handshake are dropped.
+pub(crate) struct TlsListener {
+    tcp: TcpListener,
+    acceptor: TlsAcceptor,
+    handshakes: JoinSet<Option<(TlsStream<TcpStream>, SocketAddr)>>,
+}
+
+impl TlsListener {
+    pub(crate) fn new(tcp: TcpListener, acceptor: TlsAcceptor) -> Self {
+        Self {
+            tcp,
+            acceptor,
+            handshakes: JoinSet::new(),
+        }
+    }
+}
+
+impl axum::serve::Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            tokio::select! {
+                accepted = self.tcp.accept() => match accepted {
+                    Ok((stream, addr)) => {
+                        let acceptor = self.acceptor.clone();
+                        self.handshakes.spawn(async move {
+                            let handshake = acceptor.accept(stream);
+                            let stream = tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake)
+                                .await
+                                .ok()?
+                                .ok()?;
+                            Some((stream, addr))
+                        });
+                    }
+                    // Like axum's own listener: back off on errors such as running out of fds.
+                    Err(_) => tokio::time::sleep(Duration::from_millis(50)).await,
+                },
+                Some(handshake) = self.handshakes.join_next(), if !self.handshakes.is_empty() => {
+                    if let Ok(Some(connection)) = handshake {
+                        return connection;
+                    }
+                }
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.tcp.local_addr()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HttpTls;
+
+    #[test]
+    fn from_pem_rejects_missing_certificates_and_mismatched_keys() {
+        let server = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+        let other = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+        let cert = server.cert.pem();
+        let key = server.signing_key.serialize_pem();
+
+        assert!(HttpTls::from_pem(b"", key.as_bytes()).is_err());
+        assert!(HttpTls::from_pem(cert.as_bytes(), b"").is_err());
+        HttpTls::from_pem(cert.as_bytes(), key.as_bytes())
+            .unwrap()
+            .acceptor()
+            .expect("matching key");
+        let mismatched = HttpTls::from_pem(
+            cert.as_bytes(),
+            other.signing_key.serialize_pem().as_bytes(),
+        )
+        .unwrap();
+        assert!(mismatched.acceptor().is_err());
+    }
+}
@@ -0,0 +1,92 @@
+//! Unix domain socket listener for the Streamable HTTP transport (`http` feature, Unix only).
+
+use crate::ClapMcpError;
+use std::{
+    ffi::OsString,
+    io,
+    os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+use tokio::net::UnixListener;
+
+/// Socket file permissions when [`ClapMcpServeOptions::unix_socket_mode`](crate::ClapMcpServeOptions::unix_socket_mode)
+/// is unset: only the owning user can connect.
+pub(crate) const DEFAULT_UNIX_SOCKET_MODE: u32 = 0o600;
+
+/// Removes the socket file when dropped, unless another server has since replaced it.
+pub(crate) struct UnixSocketGuard {
+    path: PathBuf,
+    dev: u64,
+    ino: u64,
+}
+
+impl Drop for UnixSocketGuard {
+    fn drop(&mut self) {
+        if let Ok(meta) = std::fs::symlink_metadata(&self.path)
+            && meta.dev() == self.dev
+            && meta.ino() == self.ino
+        {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Binds `path` with permission bits `mode`.
+///
+/// A stale socket at `path` (nothing accepting on it) is replaced; a live one or a non-socket
+/// file is an error. The socket is bound inside a new owner-only (`0o700`) directory next to
+/// `path`, so only the owning user can reach it until its mode is set; it is then renamed into
+/// place and the directory removed.
+pub(crate) fn bind_unix_socket(
+    path: &Path,
+    mode: u32,
+) -> Result<(UnixListener, UnixSocketGuard), ClapMcpError> {
+    remove_stale_socket(path)?;
+    let file_name = path.file_name().ok_or_else(|| {
+        ClapMcpError::InvalidConfig(format!("{} is not a socket file path", path.display()))
+    })?;
+    let mut tmp_dir_name = OsString::from(".");
+    tmp_dir_name.push(file_name);
+    tmp_dir_name.push(format!(".{}", std::process::id()));
+    let tmp_dir = path.with_file_name(tmp_dir_name);
+    let _ = std::fs::remove_dir_all(&tmp_dir);
+    std::fs::DirBuilder::new().mode(0o700).create(&tmp_dir)?;
+    let tmp = tmp_dir.join(file_name);
+
+    let placed = UnixListener::bind(&tmp).and_then(|listener| {
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(mode))?;
+        std::fs::rename(&tmp, path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_dir_all(&tmp_dir);
+    let listener = placed?;
+    let meta = std::fs::symlink_metadata(path)?;
+    let guard = UnixSocketGuard {
+        path: path.to_path_buf(),
+        dev: meta.dev(),
+        ino: meta.ino(),
+    };
+    Ok((listener, guard))
+}
+
+fn remove_stale_socket(path: &Path) -> Result<(), ClapMcpError> {
+    let meta = match std::fs::symlink_metadata(path) {
+        Ok(meta) => meta,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    if !meta.file_type().is_socket() {
+        return Err(ClapMcpError::InvalidConfig(format!(
+            "{} exists and is not a socket",
+            path.display()
+        )));
+    }
+    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+        return Err(ClapMcpError::InvalidConfig(format!(
+            "another server is listening on {}",
+            path.display()
+        )));
+    }
+    std::fs::remove_file(path)?;
+    Ok(())
+}
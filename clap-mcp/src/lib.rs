@@ -42,8 +42,34 @@ mod server;
 #[cfg(feature = "http")]
 mod http;
 
+#[cfg(feature = "http")]
+mod http_endpoint;
+
+#[cfg(feature = "http-tls")]
+mod http_tls;
+
+#[cfg(all(unix, feature = "http"))]
+mod http_unix;
+
 mod serve;
 
+mod completion;
+
+mod resource_updates;
+
+mod registry;
+
+mod call_context;
+
+mod pagination;
+
+mod elicitation;
+
+mod roots;
+
+#[cfg(unix)]
+mod stdio;
+
 /// Re-export of [`rmcp::model::CacheScope`] for SEP-2549 [`CacheHints`].
 pub use rmcp::model::CacheScope;
 pub use rmcp::model::ErrorData as ClapMcpErrorData;
@@ -56,10 +82,33 @@ pub mod content;
 /// MCP protocol versions clap-mcp advertises and accepts in `initialize` and discover.
 pub mod protocol;
 
+/// Opt-in persistence of shared session state across server restarts.
+pub mod state;
+
+/// Per-call tool output ([`output::ClapMcpOutput`], [`mcp_println!`]) for in-process tools.
+pub mod output;
+
+/// MCP sampling for tool code ([`sampling::ClapMcpSampler`], [`sampling::create_message`]).
+pub mod sampling;
+
+/// Status updates from task-augmented tools ([`tasks::ClapMcpTask`], [`tasks::set_progress`]).
+pub mod tasks;
+
 #[cfg(feature = "derive")]
 pub use clap_mcp_macros::ClapMcp;
+pub use registry::ClapMcpRegistry;
+pub use resource_updates::ResourceUpdateNotifier;
 pub use serve::{ServeMcp, ServeMcpBuilder};
 
+#[cfg(feature = "http")]
+pub use http::{HttpAuth, HttpPrincipal};
+
+#[cfg(feature = "http")]
+pub use http_endpoint::HttpEndpoint;
+
+#[cfg(feature = "http-tls")]
+pub use http_tls::HttpTls;
+
 /// Convenience macro for struct root + subcommand CLIs: parse root then run.
 ///
 /// Expands to: parse the root with [`ParseOrServeMcp::parse_or_serve_mcp`], then evaluate the given
@@ -115,6 +164,14 @@ pub(crate) const CLAP_MCP_STDIO_FLAG_ID_LEGACY: &str = "mcp";
 #[cfg(feature = "http")]
 pub const MCP_HTTP_FLAG_LONG: &str = "mcp-http";
 
+/// Stable clap arg id for the Unix socket MCP flag (`http` feature, Unix only).
+#[cfg(all(unix, feature = "http"))]
+pub const CLAP_MCP_UNIX_FLAG_ID: &str = "clap_mcp_unix";
+
+/// Long flag for Streamable HTTP MCP over a Unix domain socket (`http` feature, Unix only).
+#[cfg(all(unix, feature = "http"))]
+pub const MCP_UNIX_FLAG_LONG: &str = "mcp-unix";
+
 /// Environment variable for HTTP bind host when [`MCP_HTTP_LISTEN_ENV`] is unset.
 #[cfg(feature = "http")]
 pub const MCP_HTTP_BIND_ENV: &str = "CLAP_MCP_HTTP_BIND";
@@ -127,10 +184,59 @@ pub const MCP_HTTP_LISTEN_ENV: &str = "CLAP_MCP_HTTP_LISTEN";
 #[cfg(feature = "http")]
 pub const MCP_HTTP_PORT_ENV: &str = "CLAP_MCP_HTTP_PORT";
 
+/// Environment variable for the HTTP MCP endpoint route (default `/mcp`); see
+/// [`HttpEndpoint::from_env`].
+#[cfg(feature = "http")]
+pub const MCP_HTTP_PATH_ENV: &str = "CLAP_MCP_HTTP_PATH";
+
+/// Environment variable for the path prefix a reverse proxy forwards, such as `/tools/myctl`.
+#[cfg(feature = "http")]
+pub const MCP_HTTP_BASE_PATH_ENV: &str = "CLAP_MCP_HTTP_BASE_PATH";
+
+/// Environment variable listing extra accepted `Host` values, comma-separated.
+#[cfg(feature = "http")]
+pub const MCP_HTTP_ALLOWED_HOSTS_ENV: &str = "CLAP_MCP_HTTP_ALLOWED_HOSTS";
+
+/// Environment variable listing browser origins allowed to call the endpoint (with CORS
+/// headers), comma-separated.
+#[cfg(feature = "http")]
+pub const MCP_HTTP_ALLOWED_ORIGINS_ENV: &str = "CLAP_MCP_HTTP_ALLOWED_ORIGINS";
+
+/// Environment variable holding the bearer token HTTP clients must send; see [`HttpAuth::from_env`].
+#[cfg(feature = "http")]
+pub const MCP_HTTP_TOKEN_ENV: &str = "CLAP_MCP_HTTP_TOKEN";
+
+/// Environment variable naming a file that holds the bearer token, read when
+/// [`MCP_HTTP_TOKEN_ENV`] is unset.
+#[cfg(feature = "http")]
+pub const MCP_HTTP_TOKEN_FILE_ENV: &str = "CLAP_MCP_HTTP_TOKEN_FILE";
+
+/// Environment variable set for subprocess tools to the caller's [`HttpPrincipal`] name.
+///
+/// Removed when the caller has none. Subprocess tools also never inherit
+/// [`MCP_HTTP_TOKEN_ENV`] or [`MCP_HTTP_TOKEN_FILE_ENV`].
+#[cfg(feature = "http")]
+pub const MCP_HTTP_PRINCIPAL_ENV: &str = "CLAP_MCP_HTTP_PRINCIPAL";
+
+/// Environment variable holding the HTTPS certificate chain (PEM text or file path); see
+/// [`HttpTls::from_env`].
+#[cfg(feature = "http-tls")]
+pub const MCP_HTTP_TLS_CERT_ENV: &str = "CLAP_MCP_HTTP_TLS_CERT";
+
+/// Environment variable holding the HTTPS private key (PEM text or file path).
+#[cfg(feature = "http-tls")]
+pub const MCP_HTTP_TLS_KEY_ENV: &str = "CLAP_MCP_HTTP_TLS_KEY";
+
+/// Environment variable holding CA certificates (PEM text or file path) that HTTPS clients
+/// must present a certificate from.
+#[cfg(feature = "http-tls")]
+pub const MCP_HTTP_TLS_CLIENT_CA_ENV: &str = "CLAP_MCP_HTTP_TLS_CLIENT_CA";
+
 /// Long flag that triggers [Agent Skills](https://agentskills.io/specification) export (generates SKILL.md). Add via [`command_with_export_skills_flag`].
 pub const EXPORT_SKILLS_FLAG_LONG: &str = "export-skills";
 
-/// User-facing long names for clap-mcp builtin global flags (stdio, HTTP, export-skills).
+/// User-facing long names for clap-mcp builtin global flags (stdio, HTTP, Unix socket,
+/// export-skills).
 ///
 /// Override via `#[clap_mcp(mcp_flag = "...")]` on the derive or [`ClapMcpConfig::builtin_flags`]
 /// when your CLI already uses `--mcp` for something else. Values must be `'static` str literals
@@ -142,6 +248,9 @@ pub struct ClapMcpBuiltinFlags {
     /// Long name for HTTP MCP (default [`MCP_HTTP_FLAG_LONG`], `http` feature).
     #[cfg(feature = "http")]
     pub http_long: &'static str,
+    /// Long name for Unix socket MCP (default [`MCP_UNIX_FLAG_LONG`], `http` feature, Unix only).
+    #[cfg(all(unix, feature = "http"))]
+    pub unix_long: &'static str,
     /// Long name for export-skills (default [`EXPORT_SKILLS_FLAG_LONG`]).
     pub export_skills_long: &'static str,
 }
@@ -152,6 +261,8 @@ impl Default for ClapMcpBuiltinFlags {
             stdio_long: MCP_FLAG_LONG,
             #[cfg(feature = "http")]
             http_long: MCP_HTTP_FLAG_LONG,
+            #[cfg(all(unix, feature = "http"))]
+            unix_long: MCP_UNIX_FLAG_LONG,
             export_skills_long: EXPORT_SKILLS_FLAG_LONG,
         }
     }
@@ -176,11 +287,30 @@ impl ClapMcpBuiltinFlags {
         self.http_long = long;
         self
     }
+
+    /// Override the Unix socket MCP flag long name (`http` feature, Unix only).
+    #[cfg(all(unix, feature = "http"))]
+    pub const fn with_unix_long(mut self, long: &'static str) -> Self {
+        self.unix_long = long;
+        self
+    }
 }
 
 /// URI for the clap schema resource exposed by the MCP server.
 pub const MCP_RESOURCE_URI_SCHEMA: &str = "clap://schema";
 
+/// URI for the read-only session state snapshot resource, listed when
+/// [`ClapMcpServeOptions::state_persistence`] is set.
+pub const MCP_RESOURCE_URI_STATE: &str = "clap://state";
+
+/// URI for the read-only server status resource (poisoned tools after caught panics), listed
+/// when [`ClapMcpConfig::catch_in_process_panics`] is true.
+pub const MCP_RESOURCE_URI_STATUS: &str = "clap://status";
+
+/// URI for the read-only task listing resource (each task's id, tool, status, and timestamps),
+/// listed when task-augmented tools are enabled.
+pub const MCP_RESOURCE_URI_TASKS: &str = "clap://tasks";
+
 /// Provides MCP execution safety configuration from `#[clap_mcp(...)]` attributes.
 /// Implemented by the `#[derive(ClapMcp)]` macro.
 ///
@@ -514,6 +644,43 @@ pub trait ClapMcpToolExecutor {
     fn execute_for_mcp(self) -> std::result::Result<ClapMcpToolOutput, ClapMcpToolError>;
 }
 
+/// Async counterpart of [`ClapMcpToolExecutor`] for CLIs whose `run` is an `async fn`.
+///
+/// Implemented by `#[derive(ClapMcp)]` with **`#[clap_mcp_output_from_async = "run"]`**: the
+/// macro awaits `run(self)` and converts the result via [`IntoClapMcpResult`]. In-process MCP
+/// tool calls await the future directly on the server runtime (no [`run_async_tool`] bridge);
+/// the normal CLI path is driven by [`ParseOrServeMcpAsync::run_or_serve_mcp_async`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use clap::Parser;
+/// use clap_mcp::{ClapMcp, ParseOrServeMcpAsync};
+///
+/// #[derive(Parser, ClapMcp)]
+/// #[clap_mcp(reinvocation_safe, parallel_safe)]
+/// #[clap_mcp_output_from_async = "run"]
+/// enum Cli { Fetch { url: String } }
+///
+/// async fn run(cmd: Cli) -> Result<String, String> { /* ... */ }
+///
+/// fn main() {
+///     match Cli::run_or_serve_mcp_async() {
+///         Ok(output) => println!("{}", output.into_string()),
+///         Err(e) => {
+///             eprintln!("{}", e.message);
+///             std::process::exit(1);
+///         }
+///     }
+/// }
+/// ```
+pub trait ClapMcpAsyncToolExecutor: Send + Sized {
+    /// Run this parsed CLI value as an MCP tool.
+    fn execute_for_mcp_async(
+        self,
+    ) -> impl std::future::Future<Output = std::result::Result<ClapMcpToolOutput, ClapMcpToolError>> + Send;
+}
+
 /// In-process MCP tool execution with session state shared across `tools/call` invocations.
 ///
 /// Implemented by `#[derive(ClapMcp)]` when using `#[clap_mcp_output_from_with_state = "run"]`.
@@ -614,6 +781,14 @@ pub enum ClapMcpError {
     RuntimeContext(String),
     #[error("async tool thread panicked or failed: {0}")]
     ToolThread(String),
+    /// Returned by [`sampling::create_message`] when no client can sample and no fallback is
+    /// set, or by a [`sampling::ClapMcpSampler`] used after its tool call finished.
+    #[error("MCP sampling unavailable: {0}")]
+    SamplingUnavailable(String),
+    /// Returned by [`tasks::ClapMcpTask::request_input`] when the task was cancelled while
+    /// waiting, or the client's `tasks/update` answer was not a usable elicitation result.
+    #[error("task input unavailable: {0}")]
+    TaskInput(String),
 }
 
 /// Configuration for execution safety when exposing a CLI over MCP.
@@ -629,8 +804,9 @@ pub enum ClapMcpError {
 /// - **In-process (`reinvocation_safe` = true), `catch_in_process_panics` = false:** Any panic
 ///   in tool code (including from [`run_async_tool`]) crashes the server.
 /// - **In-process, `catch_in_process_panics` = true:** Panics are caught and returned as an
-///   MCP error; the server stays up. After a caught panic, the process may no longer be
-///   reinvocation_safe (global state may be corrupted); consider restarting the server.
+///   MCP error. After a caught panic, the process may no longer be reinvocation_safe (global
+///   state may be corrupted); [`poison_policy`](Self::poison_policy) decides whether the server
+///   keeps serving, disables the tool, refuses further calls, or exits.
 ///
 /// # Example
 ///
@@ -652,6 +828,8 @@ pub struct ClapMcpConfig {
     /// If true, the CLI can be invoked multiple times without tearing down the process.
     /// When false (default), each tool call spawns a fresh subprocess.
     /// When true, uses in-process execution (no subprocess).
+    /// Individual tools can override this with `#[clap_mcp(in_process)]` or
+    /// `#[clap_mcp(subprocess)]` ([`ClapMcpSchemaMetadata::tool_execution_modes`]).
     pub reinvocation_safe: bool,
 
     /// If true, tool calls may run concurrently. When false, calls are serialized.
@@ -677,6 +855,23 @@ pub struct ClapMcpConfig {
     /// For reliability, restart the MCP server after a caught panic when using in-process execution.
     pub catch_in_process_panics: bool,
 
+    /// What the server does after [`catch_in_process_panics`](Self::catch_in_process_panics)
+    /// catches a panic. Default is [`ClapMcpPoisonPolicy::Continue`].
+    ///
+    /// Panicked tools are reported in the [`MCP_RESOURCE_URI_STATUS`] resource.
+    pub poison_policy: ClapMcpPoisonPolicy,
+
+    /// Upper bound on concurrently executing tool calls across all tools. `None` (default)
+    /// means no global cap. Per-tool caps come from `#[clap_mcp(max_concurrency = N)]` on
+    /// variants ([`ClapMcpSchemaMetadata::tool_max_concurrency`]).
+    ///
+    /// Only meaningful with `parallel_safe = true`; otherwise calls are already serialized.
+    pub max_concurrency: Option<usize>,
+
+    /// How long a tool call may wait for a concurrency slot before it fails with a
+    /// "server busy" tool error. `None` (default) waits indefinitely.
+    pub queue_timeout: Option<std::time::Duration>,
+
     /// When true (default), `myapp --mcp` (or `--mcp-http`) may start the MCP server without a
     /// subcommand on the argv, by inspecting argv **before** clap runs. This preserves CLIs that
     /// use `subcommand_required = true` — you do not need `Option<Commands>` for MCP.
@@ -688,6 +883,11 @@ pub struct ClapMcpConfig {
 
     /// Long names for clap-mcp builtin global flags (`--mcp`, `--mcp-http`, `--export-skills`).
     pub builtin_flags: ClapMcpBuiltinFlags,
+
+    /// Server `instructions` returned from `initialize`, from `#[clap_mcp(instructions = "...")]`.
+    /// [`ClapMcpServeOptions::instructions`] takes precedence. When logging is enabled,
+    /// [`LOG_INTERPRETATION_INSTRUCTIONS`] is appended.
+    pub instructions: Option<String>,
 }
 
 impl Default for ClapMcpConfig {
@@ -697,8 +897,12 @@ impl Default for ClapMcpConfig {
             parallel_safe: false,
             share_runtime: false,
             catch_in_process_panics: false,
+            poison_policy: ClapMcpPoisonPolicy::Continue,
+            max_concurrency: None,
+            queue_timeout: None,
             allow_mcp_without_subcommand: true,
             builtin_flags: ClapMcpBuiltinFlags::default(),
+            instructions: None,
         }
     }
 }
@@ -715,6 +919,44 @@ impl ClapMcpConfig {
     }
 }
 
+/// Server behavior after a caught in-process panic ([`ClapMcpConfig::poison_policy`]).
+///
+/// Set with `#[clap_mcp(poison_policy = "continue" | "disable_tool" | "refuse_all" | "exit")]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClapMcpPoisonPolicy {
+    /// Keep serving every tool (the panicking call still returns an error).
+    #[default]
+    Continue,
+    /// Remove the panicking tool from `tools/list`, emit `notifications/tools/list_changed`,
+    /// and return an error for later calls to it.
+    DisableTool,
+    /// Return an error for every later tool call until the server is restarted.
+    RefuseAll,
+    /// Exit the process with [`CLAP_MCP_POISONED_EXIT_CODE`] so the client restarts the server.
+    ///
+    /// The server stops serving first, saving [`ClapMcpServeOptions::state_persistence`] and
+    /// removing a Unix socket as on SIGTERM. The panicking call may get no response; the
+    /// client sees the transport close.
+    Exit,
+}
+
+/// Process exit code used by [`ClapMcpPoisonPolicy::Exit`] (`EX_SOFTWARE`).
+pub const CLAP_MCP_POISONED_EXIT_CODE: i32 = 70;
+
+/// Per-tool override of [`ClapMcpConfig::reinvocation_safe`]
+/// ([`ClapMcpSchemaMetadata::tool_execution_modes`]).
+///
+/// Set with `#[clap_mcp(in_process)]` or `#[clap_mcp(subprocess)]` on enum variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClapMcpExecutionMode {
+    /// Run the tool in the server process (as with `reinvocation_safe = true`).
+    InProcess,
+    /// Re-invoke the executable for each call (as with `reinvocation_safe = false`).
+    Subprocess,
+}
+
 pub(crate) fn build_mcp_blocking_runtime(
     config: &ClapMcpConfig,
 ) -> Result<tokio::runtime::Runtime, ClapMcpError> {
@@ -754,13 +996,20 @@ impl From<ClapMcpConfig> for ClapMcpRunOptions {
 /// MCP transport listen target for low-level embedders.
 ///
 /// Use with [`ServeMcpBuilder`] (recommended), [`serve_mcp`], or [`serve_mcp_blocking`].
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum McpListen {
     /// Stdio MCP (default `--mcp` mode).
     Stdio,
-    /// Streamable HTTP at the given socket address (`http` feature).
+    /// Streamable HTTP at the given socket address (`http` feature); HTTPS when
+    /// [`ClapMcpServeOptions::http_tls`] or its env vars are set (`http-tls` feature).
     #[cfg(feature = "http")]
     Http(std::net::SocketAddr),
+    /// Streamable HTTP over a Unix domain socket at the given path (`http` feature, Unix only).
+    ///
+    /// A stale socket left at the path is replaced and the socket is removed on shutdown; see
+    /// [`ClapMcpServeOptions::unix_socket_mode`] for its permissions.
+    #[cfg(all(unix, feature = "http"))]
+    Unix(PathBuf),
 }
 
 /// Optional configuration for MCP serve behavior (logging, etc.).
@@ -785,8 +1034,18 @@ pub struct ClapMcpServeOptions {
     /// via `notifications/message`. Enables the logging capability and instructions.
     pub log_rx: Option<tokio::sync::mpsc::Receiver<logging::LoggingMessageNotificationParams>>,
 
+    /// Lowest level forwarded to a session until its client calls `logging/setLevel`.
+    /// `None` (default) forwards every level. Messages below the session's level are dropped
+    /// by the server and, through [`logging::is_level_forwarded`], not formatted by the bridges.
+    #[allow(deprecated)]
+    pub default_log_level: Option<rmcp::model::LoggingLevel>,
+
     /// When true and running in-process, capture stdout written during tool execution
     /// and merge it with Text output. Only has effect when `reinvocation_safe` is true.
+    /// Serving fails with [`ClapMcpError::InvalidConfig`] when combined with `parallel_safe`:
+    /// the redirect is process-wide, so use [`mcp_println!`] for per-call output instead.
+    /// Without it, stdio serving still keeps such output off the MCP stream (forwarded as
+    /// `stdout` log notifications when `log_rx` is set, otherwise to stderr).
     /// Unix only; **not available on Windows** (this field does not exist there; code
     /// setting it will fail to compile on Windows).
     #[cfg(unix)]
@@ -804,6 +1063,11 @@ pub struct ClapMcpServeOptions {
     /// Custom MCP prompts (static or async dynamic). Merged with the built-in logging guide when logging is enabled.
     pub custom_prompts: Vec<content::CustomPrompt>,
 
+    /// Answers `completion/complete` for [`custom_prompts`](Self::custom_prompts) arguments
+    /// and [`custom_resource_templates`](Self::custom_resource_templates) placeholders. Tool
+    /// arguments complete from the clap schema and `#[clap_mcp(complete_with)]` without it.
+    pub completion_provider: Option<Arc<dyn content::CompletionProvider>>,
+
     /// Extra MCP tools appended after clap-derived tools in `tools/list`.
     ///
     /// Use for raw JSON Schema vocabulary clap cannot express (`$defs`, `allOf`,
@@ -824,6 +1088,162 @@ pub struct ClapMcpServeOptions {
     /// Optional override for `resources/read` only. When `None`, uses
     /// [`cache_hints`](Self::cache_hints).
     pub resource_read_cache_hints: Option<CacheHints>,
+
+    /// Maximum items per `tools/list`, `resources/list`, `resources/templates/list`, and
+    /// `prompts/list` response; later pages are fetched with the returned `nextCursor`.
+    /// `None` (default) lists everything in one response. Must not be `Some(0)`.
+    ///
+    /// Every page carries [`cache_hints`](Self::cache_hints). Cursors name the last item of
+    /// the previous page rather than an offset, so a cached page's cursor stays valid while
+    /// that item is still listed.
+    pub page_size: Option<usize>,
+
+    /// Reject tool calls whose path args (`ValueHint::AnyPath`, `FilePath`, `DirPath`, and
+    /// `ExecutablePath` values containing a separator) resolve outside the client's roots,
+    /// including when the client exposes no `file://` roots. Default `false`.
+    ///
+    /// Relative path values are resolved against the client's first root and subprocess tools
+    /// start in it whether or not this is set.
+    pub confine_paths_to_roots: bool,
+
+    /// Snapshot shared session state to a file periodically and on graceful shutdown, and
+    /// expose it as the read-only [`MCP_RESOURCE_URI_STATE`] resource.
+    ///
+    /// Build with [`state::StatePersistence::bind`]; load the previous snapshot with
+    /// [`state::StatePersistence::load_or_default`] before creating the state `Arc`.
+    pub state_persistence: Option<state::PersistedState>,
+
+    /// Sends `notifications/resources/updated` to sessions subscribed to a URI. Clone it before
+    /// serving and call [`ResourceUpdateNotifier::notify`] when a resource changes.
+    pub resource_updates: ResourceUpdateNotifier,
+
+    /// Enables, disables, and replaces tools, prompts, and resources at runtime, emitting
+    /// `notifications/*/list_changed`. Clone it before serving; see [`ClapMcpRegistry`].
+    pub registry: ClapMcpRegistry,
+
+    /// Overrides for the `serverInfo` returned from `initialize`. Unset fields come from the
+    /// root clap command (name, version, about).
+    pub server_identity: ServerIdentity,
+
+    /// Server `instructions` returned from `initialize`; replaces
+    /// [`ClapMcpConfig::instructions`]. When logging is enabled,
+    /// [`LOG_INTERPRETATION_INSTRUCTIONS`] is appended.
+    pub instructions: Option<String>,
+
+    /// How long task-augmented calls keep their results, how many finished tasks are kept,
+    /// and the poll interval suggested to clients. Defaults keep every task until shutdown.
+    pub task_retention: TaskRetention,
+
+    /// Require a bearer token on every Streamable HTTP request (`http` feature). `None`
+    /// (default) falls back to [`HttpAuth::from_env`]; with neither, HTTP is unauthenticated.
+    #[cfg(feature = "http")]
+    pub http_auth: Option<HttpAuth>,
+
+    /// Endpoint route, reverse-proxy base path, extra allowed hosts, and CORS origins (`http`
+    /// feature). `None` (default) falls back to [`HttpEndpoint::from_env`].
+    #[cfg(feature = "http")]
+    pub http_endpoint: Option<HttpEndpoint>,
+
+    /// Serve HTTP over TLS (`http-tls` feature). `None` (default) falls back to
+    /// [`HttpTls::from_env`]; with neither, the server speaks plain HTTP.
+    #[cfg(feature = "http-tls")]
+    pub http_tls: Option<HttpTls>,
+
+    /// Permission bits for the [`McpListen::Unix`] socket file (`http` feature, Unix only).
+    /// `None` (default) uses `0o600`, so only the owning user can connect.
+    #[cfg(all(unix, feature = "http"))]
+    pub unix_socket_mode: Option<u32>,
+}
+
+/// Lifetime and polling settings for task-augmented `tools/call` (SEP-2663 tasks).
+///
+/// Finished tasks stay readable through `tasks/get` and [`MCP_RESOURCE_URI_TASKS`] until
+/// they fall out of `max_retained` or their `ttl` elapses.
+///
+/// # Example
+///
+/// ```
+/// use clap_mcp::{ClapMcpServeOptions, TaskRetention};
+/// use std::time::Duration;
+///
+/// let opts = ClapMcpServeOptions {
+///     task_retention: TaskRetention {
+///         ttl: Some(Duration::from_secs(600)),
+///         max_retained: Some(100),
+///         poll_interval: Some(Duration::from_millis(500)),
+///     },
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskRetention {
+    /// Task lifetime, sent as `ttlMs`. A task still running when it elapses is stopped and
+    /// marked `failed`; a finished task's result is freed one `ttl` after it finished.
+    /// `None` (default) keeps tasks for the life of the server. Must not be zero.
+    pub ttl: Option<std::time::Duration>,
+    /// Finished tasks kept at most; beyond it the oldest are forgotten (`tasks/get` reports
+    /// them unknown). Running tasks are never forgotten. A forgotten task's memory is
+    /// released when its `ttl` elapses, so [`ttl`](Self::ttl) must be set too. `None`
+    /// (default) keeps all. Must not be `Some(0)`.
+    pub max_retained: Option<usize>,
+    /// Suggested `tasks/get` polling interval, sent as `pollIntervalMs`. `None` (default)
+    /// leaves the choice to the client.
+    pub poll_interval: Option<std::time::Duration>,
+}
+
+/// Overrides for the MCP `serverInfo` (`Implementation`) a server reports.
+///
+/// By default the server is named after the root clap command, with its `version` (`0.0.0`
+/// when the command has none) and `about` as the description.
+///
+/// # Example
+///
+/// ```
+/// use clap_mcp::{ClapMcpServeOptions, ServerIdentity};
+///
+/// let opts = ClapMcpServeOptions {
+///     server_identity: ServerIdentity {
+///         title: Some("My App".into()),
+///         website_url: Some("https://example.com".into()),
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServerIdentity {
+    /// Replaces the root command name.
+    pub name: Option<String>,
+    /// Human-readable display name. Not set by default.
+    pub title: Option<String>,
+    /// Replaces the root command version.
+    pub version: Option<String>,
+    /// Replaces the root command `about`.
+    pub description: Option<String>,
+    /// Link to the CLI's homepage or documentation. Not set by default.
+    pub website_url: Option<String>,
+    /// Icons clients may show next to the server. Empty (default) omits `icons`.
+    pub icons: Vec<rmcp::model::Icon>,
+}
+
+impl ServerIdentity {
+    /// The `serverInfo` for a CLI whose root command is `root`.
+    pub fn implementation(&self, root: &ClapCommand) -> rmcp::model::Implementation {
+        let name = self.name.clone().unwrap_or_else(|| root.name.clone());
+        let version = self
+            .version
+            .clone()
+            .or_else(|| root.version.clone())
+            .unwrap_or_else(|| "0.0.0".into());
+        let mut info = rmcp::model::Implementation::new(name, version);
+        info.title = self.title.clone();
+        info.description = self.description.clone().or_else(|| root.about.clone());
+        info.website_url = self.website_url.clone();
+        if !self.icons.is_empty() {
+            info.icons = Some(self.icons.clone());
+        }
+        info
+    }
 }
 
 /// SEP-2549 `ttlMs` / `cacheScope` hints for list and read results.
@@ -1023,8 +1443,17 @@ pub struct ClapMcpSchemaMetadata {
     /// enum variants. When **empty**, every tool is eligible for task augmentation (when enabled
     /// in metadata). When **non-empty**, only listed tool names are eligible.
     pub task_tool_names: Vec<String>,
+    /// Tool names that ask the user for omitted required arguments via `elicitation/create`
+    /// (form mode) when the client supports it, instead of failing the call. Populated by
+    /// `#[clap_mcp(elicit_missing)]` on enum variants.
+    pub elicit_missing_tools: Vec<String>,
+    /// Tools that need the user's confirmation before running (tool name → message). Populated
+    /// by `#[clap_mcp(confirm = "Delete {name}?")]` on enum variants; `{arg}` placeholders are
+    /// replaced with the call's argument values. Calls fail closed when the client does not
+    /// support form elicitation.
+    pub confirm_tools: std::collections::HashMap<String, String>,
     /// When true, advertise MCP task support and handle task-augmented `tools/call`.
-    /// Set by `#[clap_mcp(task_augmented_tools)]` on the derive (requires `reinvocation_safe`).
+    /// Set by `#[clap_mcp(task_augmented_tools)]` on the derive.
     pub task_augmented_tools: bool,
     /// Optional JSON schema for tool output. When set (e.g. via `#[clap_mcp_output_type]` or
     /// `#[clap_mcp_output_one_of]` with the `output-schema` feature), this schema is attached
@@ -1042,13 +1471,27 @@ pub struct ClapMcpSchemaMetadata {
         String,
         std::collections::HashMap<String, SerializeTopicSegmentFn>,
     >,
+    /// Per-tool cap on concurrently executing calls (tool name → N). Populated by
+    /// `#[clap_mcp(max_concurrency = N)]` on enum variants; see also
+    /// [`ClapMcpConfig::max_concurrency`] for the global cap.
+    pub tool_max_concurrency: std::collections::HashMap<String, usize>,
+    /// Per-tool execution mode overriding [`ClapMcpConfig::reinvocation_safe`] (tool name →
+    /// mode). Populated by `#[clap_mcp(in_process)]` / `#[clap_mcp(subprocess)]` on enum
+    /// variants. Tools without an entry follow the global setting.
+    pub tool_execution_modes: std::collections::HashMap<String, ClapMcpExecutionMode>,
+    /// Per-arg `completion/complete` functions (tool name → arg id → fn). Populated by
+    /// `#[clap_mcp(complete_with = "fn")]` on fields; takes precedence over clap
+    /// `possible_values` and path value hints for that arg.
+    pub arg_completers:
+        std::collections::HashMap<String, std::collections::HashMap<String, ArgCompleterFn>>,
 }
 
 impl ClapMcpSchemaMetadata {
     /// Deep-merges `other` into `self`. Lists and per-command maps are extended; map
-    /// entries from `other` overwrite same keys in `serialize_tools` and
-    /// `serialize_topic_args`. Use when folding nested subcommand metadata into a parent
-    /// or when combining derive output with imperative overrides.
+    /// entries from `other` overwrite same keys in `serialize_tools`, `serialize_topic_args`,
+    /// `tool_max_concurrency`, `tool_execution_modes`, `confirm_tools`, and `arg_completers`. Use when folding
+    /// nested subcommand metadata into a parent or when combining derive output with
+    /// imperative overrides.
     pub fn merge_from(&mut self, other: Self) {
         self.skip_commands.extend(other.skip_commands);
         for (k, v) in other.skip_args {
@@ -1058,6 +1501,8 @@ impl ClapMcpSchemaMetadata {
             self.requires_args.entry(k).or_default().extend(v);
         }
         self.task_tool_names.extend(other.task_tool_names);
+        self.elicit_missing_tools.extend(other.elicit_missing_tools);
+        self.confirm_tools.extend(other.confirm_tools);
         self.task_augmented_tools = self.task_augmented_tools || other.task_augmented_tools;
         self.skip_root_command_when_subcommands |= other.skip_root_command_when_subcommands;
         for (k, v) in other.serialize_tools {
@@ -1069,6 +1514,11 @@ impl ClapMcpSchemaMetadata {
                 entry.insert(arg, f);
             }
         }
+        self.tool_max_concurrency.extend(other.tool_max_concurrency);
+        self.tool_execution_modes.extend(other.tool_execution_modes);
+        for (tool, args) in other.arg_completers {
+            self.arg_completers.entry(tool).or_default().extend(args);
+        }
         if other.output_schema.is_some() {
             self.output_schema = other.output_schema;
         }
@@ -1221,6 +1671,26 @@ pub const fn assert_serialized_in_any_flatten_args(arg: &str, checks: &[bool]) {
 /// Computes one arg's contribution to a topical lock key from MCP JSON.
 pub type SerializeTopicSegmentFn = fn(value: &serde_json::Value) -> Option<String>;
 
+/// Input to an argument completer registered with `#[clap_mcp(complete_with = "fn")]`.
+#[derive(Debug, Clone, Copy)]
+pub struct ArgCompletionRequest<'a> {
+    /// MCP tool name.
+    pub tool: &'a str,
+    /// Clap arg id being completed.
+    pub arg: &'a str,
+    /// Partial value typed so far.
+    pub value: &'a str,
+    /// Values the client already resolved for other args of the same tool.
+    pub context: &'a std::collections::HashMap<String, String>,
+}
+
+/// Returns `completion/complete` candidates for one tool argument.
+///
+/// Candidates are returned as-is (filter by [`ArgCompletionRequest::value`] yourself); the
+/// server sends at most 100 and reports the rest via `total` / `hasMore`. Runs on the server's
+/// runtime, so keep it quick.
+pub type ArgCompleterFn = fn(request: &ArgCompletionRequest<'_>) -> Vec<String>;
+
 /// Optional typed topical lock segments for arg-scoped serialization.
 ///
 /// Default arg-scoped serialization uses canonical MCP JSON (no `Hash` or `Eq` on your Rust types).
@@ -1297,7 +1767,9 @@ pub enum ClapMcpSerializeScope {
 }
 
 pub(crate) fn tool_task_eligible(tool_name: &str, metadata: &ClapMcpSchemaMetadata) -> bool {
-    if !metadata.task_augmented_tools {
+    if !metadata.task_augmented_tools
+        || metadata.tool_execution_modes.get(tool_name) == Some(&ClapMcpExecutionMode::Subprocess)
+    {
         return false;
     }
     if metadata.task_tool_names.is_empty() {
@@ -1307,6 +1779,19 @@ pub(crate) fn tool_task_eligible(tool_name: &str, metadata: &ClapMcpSchemaMetada
     }
 }
 
+/// Whether `tool_name` runs in-process: its [`ClapMcpSchemaMetadata::tool_execution_modes`]
+/// entry when present, otherwise [`ClapMcpConfig::reinvocation_safe`].
+pub(crate) fn tool_reinvocation_safe(
+    tool_name: &str,
+    config: &ClapMcpConfig,
+    metadata: &ClapMcpSchemaMetadata,
+) -> bool {
+    match metadata.tool_execution_modes.get(tool_name) {
+        Some(mode) => *mode == ClapMcpExecutionMode::InProcess,
+        None => config.reinvocation_safe,
+    }
+}
+
 /// Builds a JSON schema for a single type. Used by the derive macro when `#[clap_mcp_output_type = "T"]` is set.
 /// When the `output-schema` feature is enabled and `T: schemars::JsonSchema`, returns the schema; otherwise returns `None`.
 #[cfg(feature = "output-schema")]
@@ -1439,6 +1924,10 @@ pub fn argv_contains_clap_mcp_flags(args: &[String], flags: &ClapMcpBuiltinFlags
     if argv_has_long_flag(prefix, flags.http_long) {
         return true;
     }
+    #[cfg(all(unix, feature = "http"))]
+    if argv_has_long_flag(prefix, flags.unix_long) {
+        return true;
+    }
     false
 }
 
@@ -1484,7 +1973,16 @@ pub(crate) fn is_clap_mcp_builtin_arg_id(id: &str) -> bool {
     ) || {
         #[cfg(feature = "http")]
         {
-            id == CLAP_MCP_HTTP_FLAG_ID || id == MCP_HTTP_FLAG_LONG
+            id == CLAP_MCP_HTTP_FLAG_ID || id == MCP_HTTP_FLAG_LONG || {
+                #[cfg(unix)]
+                {
+                    id == CLAP_MCP_UNIX_FLAG_ID
+                }
+                #[cfg(not(unix))]
+                {
+                    false
+                }
+            }
         }
         #[cfg(not(feature = "http"))]
         {
@@ -1619,7 +2117,7 @@ pub fn tools_from_schema_with_metadata(
 }
 
 /// Args exposed for an MCP tool: leaf command args plus ancestor `#[arg(global)]` args.
-fn effective_args_for_tool(schema: &ClapSchema, command_name: &str) -> Vec<ClapArg> {
+pub(crate) fn effective_args_for_tool(schema: &ClapSchema, command_name: &str) -> Vec<ClapArg> {
     let Some(path) = command_path(schema, command_name) else {
         return Vec::new();
     };
@@ -1742,7 +2240,7 @@ fn command_to_tool_with_config(
         let mut clap_mcp = serde_json::Map::new();
         clap_mcp.insert(
             "reinvocationSafe".into(),
-            serde_json::Value::Bool(config.reinvocation_safe),
+            serde_json::Value::Bool(tool_reinvocation_safe(&cmd.name, config, metadata)),
         );
         clap_mcp.insert(
             "parallelSafe".into(),
@@ -1755,6 +2253,9 @@ fn command_to_tool_with_config(
         if tool_task_eligible(&cmd.name, metadata) {
             clap_mcp.insert("taskAugmented".into(), serde_json::Value::Bool(true));
         }
+        if let Some(max) = metadata.tool_max_concurrency.get(&cmd.name) {
+            clap_mcp.insert("maxConcurrency".into(), serde_json::Value::from(*max));
+        }
         if let Some(scope) = metadata.serialize_tools.get(&cmd.name) {
             clap_mcp.insert("serialized".into(), serde_json::Value::Bool(true));
             match scope {
@@ -1834,6 +2335,13 @@ pub struct ClapArg {
     pub action: Option<String>,
     pub value_names: Vec<String>,
     pub num_args: Option<String>,
+    /// Non-hidden clap `possible_values`, offered by `completion/complete`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub possible_values: Vec<String>,
+    /// clap `ValueHint` (e.g. `FilePath`, `DirPath`, `AnyPath`) when not `Unknown`. Path hints
+    /// complete to entries under the client's roots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_hint: Option<String>,
 }
 
 /// Returns the MCP input schema type for an argument based on its action (and num_args).
@@ -1844,13 +2352,18 @@ pub struct ClapArg {
 ///
 /// When the arg has a single value_name (e.g. VERSION), the array items schema gets a description
 /// so clients know what each element represents.
-fn mcp_type_for_arg(arg: &ClapArg) -> (serde_json::Value, Option<serde_json::Value>) {
-    let action = arg.action.as_deref().unwrap_or("Set");
-    let is_multi = matches!(action, "Append")
+/// Whether the arg accepts a list of values (`Append` or a multi-value `num_args` range).
+pub(crate) fn arg_takes_multiple_values(arg: &ClapArg) -> bool {
+    matches!(arg.action.as_deref(), Some("Append"))
         || arg
             .num_args
             .as_deref()
-            .is_some_and(|n| n.contains("..") && !n.contains("=1"));
+            .is_some_and(|n| n.contains("..") && !n.contains("=1"))
+}
+
+fn mcp_type_for_arg(arg: &ClapArg) -> (serde_json::Value, Option<serde_json::Value>) {
+    let action = arg.action.as_deref().unwrap_or("Set");
+    let is_multi = arg_takes_multiple_values(arg);
     let (json_type, items) = if matches!(action, "SetTrue" | "SetFalse") {
         (serde_json::json!("boolean"), None)
     } else if action == "Count" {
@@ -1989,6 +2502,10 @@ pub fn command_with_mcp_and_export_skills_flags_with_flags(
     {
         cmd = command_with_mcp_http_flag_with_flags(cmd, flags);
     }
+    #[cfg(all(unix, feature = "http"))]
+    {
+        cmd = command_with_mcp_unix_flag_with_flags(cmd, flags);
+    }
     command_with_export_skills_flag_with_flags(cmd, flags)
 }
 
@@ -2016,6 +2533,59 @@ pub fn command_with_mcp_http_flag_with_flags(
     cmd
 }
 
+/// Adds the `--mcp-unix PATH` flag (`http` feature, Unix only).
+#[cfg(all(unix, feature = "http"))]
+pub fn command_with_mcp_unix_flag(cmd: Command) -> Command {
+    command_with_mcp_unix_flag_with_flags(cmd, &ClapMcpBuiltinFlags::default())
+}
+
+/// Like [`command_with_mcp_unix_flag`] with custom builtin long names.
+#[cfg(all(unix, feature = "http"))]
+pub fn command_with_mcp_unix_flag_with_flags(cmd: Command, flags: &ClapMcpBuiltinFlags) -> Command {
+    if command_has_arg_id_or_long(&cmd, CLAP_MCP_UNIX_FLAG_ID, flags.unix_long) {
+        return cmd;
+    }
+    cmd.arg(
+        Arg::new(CLAP_MCP_UNIX_FLAG_ID)
+            .long(flags.unix_long)
+            .value_name("PATH")
+            .value_parser(clap::value_parser!(PathBuf))
+            .help("Run an MCP server over Streamable HTTP on a Unix domain socket at PATH")
+            .global(true),
+    )
+}
+
+/// `--mcp-unix PATH` / `--mcp-unix=PATH` before `--`; an error when the flag has no path.
+#[cfg(all(unix, feature = "http"))]
+pub(crate) fn argv_mcp_unix_path_from_args(
+    args: &[String],
+    flags: &ClapMcpBuiltinFlags,
+) -> Result<Option<PathBuf>, ClapMcpError> {
+    let prefix = argv_before_end_of_opts(args);
+    let unix_flag = format!("--{}", flags.unix_long);
+    let unix_prefix = format!("--{}=", flags.unix_long);
+    for (i, arg) in prefix.iter().enumerate() {
+        let path = if arg == &unix_flag {
+            prefix
+                .get(i + 1)
+                .map(String::as_str)
+                .filter(|s| !s.starts_with('-'))
+        } else if let Some(path) = arg.strip_prefix(&unix_prefix) {
+            Some(path).filter(|p| !p.is_empty())
+        } else {
+            continue;
+        };
+        return match path {
+            Some(path) => Ok(Some(PathBuf::from(path))),
+            None => Err(ClapMcpError::InvalidConfig(format!(
+                "`--{}` requires a socket PATH",
+                flags.unix_long
+            ))),
+        };
+    }
+    Ok(None)
+}
+
 #[cfg(feature = "http")]
 pub(crate) fn mcp_http_listen_from_env() -> Option<String> {
     if let Ok(listen) = std::env::var(MCP_HTTP_LISTEN_ENV)
@@ -2179,20 +2749,15 @@ pub fn serve_mcp_blocking(
         .serve_blocking()
 }
 
-#[cfg(feature = "http")]
 fn serve_prepared_mcp_blocking(
-    http_listen: Option<std::net::SocketAddr>,
+    listen: McpListen,
     schema_json: String,
     executable_path: Option<PathBuf>,
     config: ClapMcpConfig,
-    in_process_handler: Option<InProcessToolHandler>,
+    in_process_handler: Option<ToolHandler>,
     serve_options: ClapMcpServeOptions,
     metadata: &ClapMcpSchemaMetadata,
 ) -> Result<(), ClapMcpError> {
-    let listen = match http_listen {
-        Some(addr) => McpListen::Http(addr),
-        None => McpListen::Stdio,
-    };
     ServeMcpBuilder::new()
         .listen(listen)
         .schema_json(schema_json)
@@ -2200,28 +2765,7 @@ fn serve_prepared_mcp_blocking(
         .metadata(metadata.clone())
         .serve_options(serve_options)
         .executable_path(executable_path)
-        .in_process_handler(in_process_handler)
-        .serve_blocking()
-}
-
-#[cfg(not(feature = "http"))]
-fn serve_prepared_mcp_blocking(
-    _http_listen: Option<std::net::SocketAddr>,
-    schema_json: String,
-    executable_path: Option<PathBuf>,
-    config: ClapMcpConfig,
-    in_process_handler: Option<InProcessToolHandler>,
-    serve_options: ClapMcpServeOptions,
-    metadata: &ClapMcpSchemaMetadata,
-) -> Result<(), ClapMcpError> {
-    ServeMcpBuilder::new()
-        .listen(McpListen::Stdio)
-        .schema_json(schema_json)
-        .config(config)
-        .metadata(metadata.clone())
-        .serve_options(serve_options)
-        .executable_path(executable_path)
-        .in_process_handler(in_process_handler)
+        .tool_handler(in_process_handler)
         .serve_blocking()
 }
 
@@ -2245,12 +2789,132 @@ pub(crate) fn argv_requests_mcp_http_without_subcommand_from_args(
     has_http && !has_subcommand
 }
 
+#[cfg(all(unix, feature = "http"))]
+pub(crate) fn argv_requests_mcp_unix_without_subcommand_from_args(
+    args: &[String],
+    cmd: &Command,
+    flags: &ClapMcpBuiltinFlags,
+) -> bool {
+    let prefix = argv_before_end_of_opts(args);
+    let has_unix = argv_has_long_flag(prefix, flags.unix_long);
+    has_unix
+        && !prefix
+            .iter()
+            .any(|a| cmd.get_subcommands().any(|s| s.get_name() == a))
+}
+
 /// Returns true if argv contains the stdio MCP flag and no token before `--` is a subcommand name.
 fn argv_requests_mcp_without_subcommand(cmd: &Command, flags: &ClapMcpBuiltinFlags) -> bool {
     let args: Vec<String> = std::env::args().skip(1).collect();
     argv_requests_mcp_without_subcommand_from_args(&args, cmd, flags)
 }
 
+/// Like [`argv_requests_mcp_without_subcommand`], for any builtin MCP transport flag.
+fn argv_requests_any_mcp_without_subcommand(cmd: &Command, flags: &ClapMcpBuiltinFlags) -> bool {
+    #[cfg(feature = "http")]
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    argv_requests_mcp_without_subcommand(cmd, flags)
+        || {
+            #[cfg(feature = "http")]
+            {
+                argv_requests_mcp_http_without_subcommand_from_args(&args, cmd, flags)
+            }
+            #[cfg(not(feature = "http"))]
+            {
+                false
+            }
+        }
+        || {
+            #[cfg(all(unix, feature = "http"))]
+            {
+                argv_requests_mcp_unix_without_subcommand_from_args(&args, cmd, flags)
+            }
+            #[cfg(not(all(unix, feature = "http")))]
+            {
+                false
+            }
+        }
+}
+
+/// Transport for the no-subcommand path, where clap has not parsed argv: `--mcp-unix`, then
+/// `--mcp-http` (with env fallback), else stdio. Exits with status 2 on a missing or invalid
+/// address, or when both socket flags are given.
+fn argv_mcp_listen_or_exit(flags: &ClapMcpBuiltinFlags) -> McpListen {
+    #[cfg(feature = "http")]
+    {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let listen = (|| {
+            #[cfg(unix)]
+            if let Some(path) = argv_mcp_unix_path_from_args(&args, flags)? {
+                if argv_has_long_flag(argv_before_end_of_opts(&args), flags.http_long) {
+                    return Err(ClapMcpError::InvalidConfig(format!(
+                        "--{} and --{} are mutually exclusive",
+                        flags.http_long, flags.unix_long
+                    )));
+                }
+                return Ok(McpListen::Unix(path));
+            }
+            Ok(resolve_mcp_http_listen_from_args(&args, flags)?
+                .map_or(McpListen::Stdio, McpListen::Http))
+        })();
+        listen.unwrap_or_else(|e: ClapMcpError| {
+            eprintln!("{e}");
+            std::process::exit(2);
+        })
+    }
+    #[cfg(not(feature = "http"))]
+    {
+        let _ = flags;
+        McpListen::Stdio
+    }
+}
+
+/// Transport requested by the builtin flags in `matches`; `None` for a normal CLI run.
+/// Exits with status 2 on an invalid address or when more than one MCP flag is given.
+fn matches_mcp_listen_or_exit(
+    matches: &clap::ArgMatches,
+    flags: &ClapMcpBuiltinFlags,
+) -> Option<McpListen> {
+    let mut requested: Vec<(&str, McpListen)> = Vec::new();
+    if matches_stdio_flag(matches, flags) {
+        requested.push((flags.stdio_long, McpListen::Stdio));
+    }
+    #[cfg(feature = "http")]
+    if matches_http_flag(matches, flags) {
+        let addr = matches
+            .get_one::<String>(CLAP_MCP_HTTP_FLAG_ID)
+            .or_else(|| {
+                if flags.http_long == MCP_HTTP_FLAG_LONG {
+                    matches.get_one::<String>(MCP_HTTP_FLAG_LONG)
+                } else {
+                    None
+                }
+            })
+            .map(|s| parse_mcp_http_listen(s))
+            .transpose()
+            .unwrap_or_else(|e| {
+                eprintln!("{e}");
+                std::process::exit(2);
+            });
+        if let Some(addr) = addr {
+            requested.push((flags.http_long, McpListen::Http(addr)));
+        }
+    }
+    #[cfg(all(unix, feature = "http"))]
+    if let Some(path) = matches.get_one::<PathBuf>(CLAP_MCP_UNIX_FLAG_ID) {
+        requested.push((flags.unix_long, McpListen::Unix(path.clone())));
+    }
+    if requested.len() > 1 {
+        let longs: Vec<String> = requested
+            .iter()
+            .map(|(long, _)| format!("--{long}"))
+            .collect();
+        eprintln!("{} are mutually exclusive", longs.join(" and "));
+        std::process::exit(2);
+    }
+    requested.pop().map(|(_, listen)| listen)
+}
+
 /// Pure helper for argv_requests_mcp_without_subcommand; testable with arbitrary args.
 pub(crate) fn argv_requests_mcp_without_subcommand_from_args(
     args: &[String],
@@ -2444,18 +3108,7 @@ pub fn get_matches_or_serve_mcp_with_config_and_metadata(
         std::process::exit(0);
     }
 
-    if config.allow_mcp_without_subcommand
-        && (argv_requests_mcp_without_subcommand(&cmd, &flags) || {
-            #[cfg(feature = "http")]
-            {
-                let args: Vec<String> = std::env::args().skip(1).collect();
-                argv_requests_mcp_http_without_subcommand_from_args(&args, &cmd, &flags)
-            }
-            #[cfg(not(feature = "http"))]
-            {
-                false
-            }
-        })
+    if config.allow_mcp_without_subcommand && argv_requests_any_mcp_without_subcommand(&cmd, &flags)
     {
         let schema_json = match serde_json::to_string_pretty(&schema) {
             Ok(s) => s,
@@ -2464,19 +3117,10 @@ pub fn get_matches_or_serve_mcp_with_config_and_metadata(
                 std::process::exit(1);
             }
         };
-        #[cfg(feature = "http")]
-        let http_listen = {
-            let args: Vec<String> = std::env::args().skip(1).collect();
-            resolve_mcp_http_listen_from_args(&args, &flags).unwrap_or_else(|e| {
-                eprintln!("{e}");
-                std::process::exit(2);
-            })
-        };
-        #[cfg(not(feature = "http"))]
-        let http_listen: Option<std::net::SocketAddr> = None;
+        let listen = argv_mcp_listen_or_exit(&flags);
 
         if let Err(e) = serve_prepared_mcp_blocking(
-            http_listen,
+            listen,
             schema_json,
             None,
             config,
@@ -2491,42 +3135,7 @@ pub fn get_matches_or_serve_mcp_with_config_and_metadata(
     }
 
     let matches = cmd.get_matches();
-    let mcp_requested = matches_stdio_flag(&matches, &flags);
-    #[cfg(feature = "http")]
-    let http_listen = if matches_http_flag(&matches, &flags) {
-        matches
-            .get_one::<String>(CLAP_MCP_HTTP_FLAG_ID)
-            .or_else(|| {
-                if flags.http_long == MCP_HTTP_FLAG_LONG {
-                    matches.get_one::<String>(MCP_HTTP_FLAG_LONG)
-                } else {
-                    None
-                }
-            })
-            .map(|s| parse_mcp_http_listen(s))
-            .transpose()
-            .unwrap_or_else(|e| {
-                eprintln!("{e}");
-                std::process::exit(2);
-            })
-    } else {
-        None
-    };
-    #[cfg(not(feature = "http"))]
-    let http_listen: Option<std::net::SocketAddr> = None;
-
-    if mcp_requested && http_listen.is_some() {
-        #[cfg(feature = "http")]
-        eprintln!(
-            "--{} and --{} are mutually exclusive",
-            flags.stdio_long, flags.http_long
-        );
-        #[cfg(not(feature = "http"))]
-        eprintln!("stdio and HTTP MCP flags are mutually exclusive");
-        std::process::exit(2);
-    }
-
-    if mcp_requested || http_listen.is_some() {
+    if let Some(listen) = matches_mcp_listen_or_exit(&matches, &flags) {
         let schema_json = match serde_json::to_string_pretty(&schema) {
             Ok(s) => s,
             Err(e) => {
@@ -2535,7 +3144,7 @@ pub fn get_matches_or_serve_mcp_with_config_and_metadata(
             }
         };
         if let Err(e) = serve_prepared_mcp_blocking(
-            http_listen,
+            listen,
             schema_json,
             None,
             config,
@@ -2655,12 +3264,12 @@ where
 
 struct PreparedDeriveMcpServe {
     schema_json: String,
-    in_process_handler: Option<InProcessToolHandler>,
+    in_process_handler: Option<ToolHandler>,
     executable_path: Option<PathBuf>,
     metadata: ClapMcpSchemaMetadata,
 }
 
-fn capture_stdout_for_serve(serve_options: &ClapMcpServeOptions) -> bool {
+pub(crate) fn capture_stdout_for_serve(serve_options: &ClapMcpServeOptions) -> bool {
     #[cfg(unix)]
     {
         serve_options.capture_stdout
@@ -2672,16 +3281,32 @@ fn capture_stdout_for_serve(serve_options: &ClapMcpServeOptions) -> bool {
     }
 }
 
+/// Whether any tool runs in-process (`in_process = true`) or as a subprocess (`false`), so
+/// the matching handler or executable path must be prepared.
+fn needs_execution_mode(
+    config: &ClapMcpConfig,
+    metadata: &ClapMcpSchemaMetadata,
+    in_process: bool,
+) -> bool {
+    let wanted = if in_process {
+        ClapMcpExecutionMode::InProcess
+    } else {
+        ClapMcpExecutionMode::Subprocess
+    };
+    config.reinvocation_safe == in_process
+        || metadata.tool_execution_modes.values().any(|m| *m == wanted)
+}
+
 fn finish_prepared_derive_mcp_serve(
     config: &ClapMcpConfig,
     metadata: ClapMcpSchemaMetadata,
     schema_json: String,
-    in_process_handler: Option<InProcessToolHandler>,
+    in_process_handler: Option<ToolHandler>,
 ) -> PreparedDeriveMcpServe {
-    let executable_path = if config.reinvocation_safe {
-        None
-    } else {
+    let executable_path = if needs_execution_mode(config, &metadata, false) {
         std::env::current_exe().ok()
+    } else {
+        None
     };
     PreparedDeriveMcpServe {
         schema_json,
@@ -2707,8 +3332,33 @@ where
     let schema = schema_from_command_with_metadata(&T::command(), &metadata);
     let schema_json = serde_json::to_string_pretty(&schema).expect("schema should serialize");
     let capture_stdout = capture_stdout_for_serve(serve_options);
-    let in_process_handler = if config.reinvocation_safe {
-        Some(make_in_process_handler::<T>(schema, capture_stdout))
+    let in_process_handler = if needs_execution_mode(config, &metadata, true) {
+        Some(ToolHandler::Sync(make_in_process_handler::<T>(
+            schema,
+            capture_stdout,
+        )))
+    } else {
+        None
+    };
+    finish_prepared_derive_mcp_serve(config, metadata, schema_json, in_process_handler)
+}
+
+/// Like [`prepare_derive_mcp_serve`], but awaits an async `run` on the server runtime.
+pub(crate) fn prepare_derive_mcp_serve_async<T>(config: &ClapMcpConfig) -> PreparedDeriveMcpServe
+where
+    T: ClapMcpAsyncToolExecutor
+        + ClapMcpSchemaMetadataProvider
+        + clap::CommandFactory
+        + clap::FromArgMatches
+        + 'static,
+{
+    let metadata = T::clap_mcp_schema_metadata();
+    let schema = schema_from_command_with_metadata(&T::command(), &metadata);
+    let schema_json = serde_json::to_string_pretty(&schema).expect("schema should serialize");
+    let in_process_handler = if needs_execution_mode(config, &metadata, true) {
+        Some(ToolHandler::Async(async_in_process_tool_handler_for::<T>(
+            schema,
+        )))
     } else {
         None
     };
@@ -2732,12 +3382,12 @@ where
     let schema = schema_from_command_with_metadata(&T::command(), &metadata);
     let schema_json = serde_json::to_string_pretty(&schema).expect("schema should serialize");
     let capture_stdout = capture_stdout_for_serve(serve_options);
-    let in_process_handler = if config.reinvocation_safe {
-        Some(make_in_process_handler_with_state::<T>(
+    let in_process_handler = if needs_execution_mode(config, &metadata, true) {
+        Some(ToolHandler::Sync(make_in_process_handler_with_state::<T>(
             schema,
             state,
             capture_stdout,
-        ))
+        )))
     } else {
         None
     };
@@ -2746,12 +3396,12 @@ where
 
 fn run_prepared_derive_mcp_serve(
     prepared: PreparedDeriveMcpServe,
-    http_listen: Option<std::net::SocketAddr>,
+    listen: McpListen,
     config: ClapMcpConfig,
     serve_options: ClapMcpServeOptions,
 ) -> Result<(), ClapMcpError> {
     serve_prepared_mcp_blocking(
-        http_listen,
+        listen,
         prepared.schema_json,
         prepared.executable_path,
         config,
@@ -2769,17 +3419,6 @@ fn exit_on_mcp_serve_error(result: Result<(), ClapMcpError>) -> ! {
     std::process::exit(0);
 }
 
-#[cfg(feature = "http")]
-fn resolve_http_listen_from_env_or_exit(
-    flags: &ClapMcpBuiltinFlags,
-) -> Option<std::net::SocketAddr> {
-    let args: Vec<String> = std::env::args().skip(1).collect();
-    resolve_mcp_http_listen_from_args(&args, flags).unwrap_or_else(|e| {
-        eprintln!("{e}");
-        std::process::exit(2);
-    })
-}
-
 fn parse_or_serve_mcp_common<T>(
     options: ClapMcpRunOptions,
     prepare: impl FnOnce(&ClapMcpConfig, &ClapMcpServeOptions) -> PreparedDeriveMcpServe,
@@ -2817,76 +3456,24 @@ where
         std::process::exit(0);
     }
 
-    if config.allow_mcp_without_subcommand
-        && (argv_requests_mcp_without_subcommand(&cmd, &flags) || {
-            #[cfg(feature = "http")]
-            {
-                let args: Vec<String> = std::env::args().skip(1).collect();
-                argv_requests_mcp_http_without_subcommand_from_args(&args, &cmd, &flags)
-            }
-            #[cfg(not(feature = "http"))]
-            {
-                false
-            }
-        })
+    if config.allow_mcp_without_subcommand && argv_requests_any_mcp_without_subcommand(&cmd, &flags)
     {
-        #[cfg(feature = "http")]
-        let http_listen = resolve_http_listen_from_env_or_exit(&flags);
-        #[cfg(not(feature = "http"))]
-        let http_listen: Option<std::net::SocketAddr> = None;
+        let listen = argv_mcp_listen_or_exit(&flags);
         let prepared = prepare(&config, &serve_options);
         exit_on_mcp_serve_error(run_prepared_derive_mcp_serve(
             prepared,
-            http_listen,
+            listen,
             config,
             serve_options,
         ));
     }
 
     let matches = cmd.get_matches();
-    let mcp_requested = matches_stdio_flag(&matches, &flags);
-    #[cfg(feature = "http")]
-    let http_listen = if matches_http_flag(&matches, &flags) {
-        match matches
-            .get_one::<String>(CLAP_MCP_HTTP_FLAG_ID)
-            .or_else(|| {
-                if flags.http_long == MCP_HTTP_FLAG_LONG {
-                    matches.get_one::<String>(MCP_HTTP_FLAG_LONG)
-                } else {
-                    None
-                }
-            })
-            .map(|s| parse_mcp_http_listen(s))
-            .transpose()
-        {
-            Ok(v) => v,
-            Err(e) => {
-                eprintln!("{e}");
-                std::process::exit(2);
-            }
-        }
-    } else {
-        None
-    };
-    #[cfg(not(feature = "http"))]
-    let http_listen: Option<std::net::SocketAddr> = None;
-
-    if mcp_requested && http_listen.is_some() {
-        #[cfg(feature = "http")]
-        eprintln!(
-            "--{} and --{} are mutually exclusive",
-            flags.stdio_long, flags.http_long
-        );
-        #[cfg(not(feature = "http"))]
-        eprintln!("stdio and HTTP MCP flags are mutually exclusive");
-        std::process::exit(2);
-    }
-
-    if mcp_requested || http_listen.is_some() {
+    if let Some(listen) = matches_mcp_listen_or_exit(&matches, &flags) {
         let prepared = prepare(&config, &serve_options);
         exit_on_mcp_serve_error(run_prepared_derive_mcp_serve(
             prepared,
-            http_listen,
+            listen,
             config,
             serve_options,
         ));
@@ -3006,11 +3593,103 @@ where
     }
 }
 
+/// Async derive entrypoint: like [`parse_or_serve_mcp_with`] for [`ClapMcpAsyncToolExecutor`]
+/// types. In-process MCP tool calls await `run` on the server runtime.
+///
+/// Returns the parsed CLI value on the normal CLI path; use [`run_or_serve_mcp_async_with`] to
+/// also await `run`.
+pub fn parse_or_serve_mcp_async_with<T>(options: ClapMcpRunOptions) -> T
+where
+    T: ClapMcpSchemaMetadataProvider
+        + ClapMcpAsyncToolExecutor
+        + clap::Parser
+        + clap::CommandFactory
+        + clap::FromArgMatches
+        + 'static,
+{
+    parse_or_serve_mcp_common::<T>(options, |config, _serve_options| {
+        prepare_derive_mcp_serve_async::<T>(config)
+    })
+}
+
+/// Parse argv (or serve MCP and exit), then await the async `run` on a tokio runtime built from
+/// `options.config` and return its output.
+///
+/// Subprocess tool calls (`reinvocation_safe = false` or `#[clap_mcp(subprocess)]`) re-invoke
+/// the executable, so they reach `run` through this same path.
+pub fn run_or_serve_mcp_async_with<T>(
+    options: ClapMcpRunOptions,
+) -> Result<ClapMcpToolOutput, ClapMcpToolError>
+where
+    T: ClapMcpSchemaMetadataProvider
+        + ClapMcpAsyncToolExecutor
+        + clap::Parser
+        + clap::CommandFactory
+        + clap::FromArgMatches
+        + 'static,
+{
+    let config = options.config.clone();
+    let cli = parse_or_serve_mcp_async_with::<T>(options);
+    build_mcp_blocking_runtime(&config)
+        .map_err(|e| ClapMcpToolError::text(e.to_string()))?
+        .block_on(cli.execute_for_mcp_async())
+}
+
+/// Parse CLI or serve MCP for [`ClapMcpAsyncToolExecutor`] types
+/// (`#[clap_mcp_output_from_async = "run"]`).
+pub trait ParseOrServeMcpAsync: ClapMcpAsyncToolExecutor {
+    /// Parse argv or start MCP; returns the parsed CLI value without running it.
+    fn parse_or_serve_mcp_async() -> Self;
+
+    /// Parse argv or start MCP; on the CLI path, await `run` and return its output.
+    fn run_or_serve_mcp_async() -> Result<ClapMcpToolOutput, ClapMcpToolError>;
+}
+
+impl<T> ParseOrServeMcpAsync for T
+where
+    T: ClapMcpConfigProvider
+        + ClapMcpSchemaMetadataProvider
+        + ClapMcpAsyncToolExecutor
+        + clap::Parser
+        + clap::CommandFactory
+        + clap::FromArgMatches
+        + 'static,
+{
+    fn parse_or_serve_mcp_async() -> Self {
+        parse_or_serve_mcp_async_with(ClapMcpRunOptions {
+            config: T::clap_mcp_config(),
+            serve: ClapMcpServeOptions::default(),
+        })
+    }
+
+    fn run_or_serve_mcp_async() -> Result<ClapMcpToolOutput, ClapMcpToolError> {
+        run_or_serve_mcp_async_with::<T>(ClapMcpRunOptions {
+            config: T::clap_mcp_config(),
+            serve: ClapMcpServeOptions::default(),
+        })
+    }
+}
+
 fn arg_to_schema(arg: &clap::Arg) -> ClapArg {
     let value_names = arg
         .get_value_names()
         .map(|names| names.iter().map(|n| n.to_string()).collect())
         .unwrap_or_default();
+    let takes_values = arg.get_action().takes_values();
+    let possible_values = if takes_values {
+        arg.get_possible_values()
+            .iter()
+            .filter(|v| !v.is_hide_set())
+            .map(|v| v.get_name().to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let value_hint = match arg.get_value_hint() {
+        clap::ValueHint::Unknown => None,
+        _ if !takes_values => None,
+        hint => Some(format!("{hint:?}")),
+    };
 
     ClapArg {
         id: arg.get_id().to_string(),
@@ -3024,22 +3703,23 @@ fn arg_to_schema(arg: &clap::Arg) -> ClapArg {
         action: Some(format!("{:?}", arg.get_action())),
         value_names,
         num_args: arg.get_num_args().map(|r| format!("{r:?}")),
+        possible_values,
+        value_hint,
     }
 }
 
-/// Validates that all required args for the command are present in the arguments map.
-/// Returns Err with a clear message if any required arg is missing.
-pub(crate) fn validate_required_args(
+/// Required args of `command_name` (including MCP-required ones) that `arguments` does not
+/// provide a usable value for.
+pub(crate) fn missing_required_args(
     schema: &ClapSchema,
     command_name: &str,
     arguments: &serde_json::Map<String, serde_json::Value>,
-) -> Result<(), String> {
+) -> Vec<ClapArg> {
     if command_path(schema, command_name).is_none() {
-        return Ok(());
+        return Vec::new();
     }
-    let effective_args = effective_args_for_tool(schema, command_name);
-    let missing: Vec<_> = effective_args
-        .iter()
+    effective_args_for_tool(schema, command_name)
+        .into_iter()
         .filter(|a| {
             if !a.required {
                 return false;
@@ -3057,7 +3737,19 @@ pub(crate) fn validate_required_args(
             });
             !has_value.unwrap_or(false)
         })
-        .map(|a| a.id.clone())
+        .collect()
+}
+
+/// Validates that all required args for the command are present in the arguments map.
+/// Returns Err with a clear message if any required arg is missing.
+pub(crate) fn validate_required_args(
+    schema: &ClapSchema,
+    command_name: &str,
+    arguments: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    let missing: Vec<_> = missing_required_args(schema, command_name, arguments)
+        .into_iter()
+        .map(|a| a.id)
         .collect();
     if missing.is_empty() {
         Ok(())
@@ -3223,6 +3915,25 @@ pub type InProcessToolHandler = Arc<
         + Sync,
 >;
 
+/// Future returned by an [`AsyncInProcessToolHandler`].
+pub type InProcessToolFuture = std::pin::Pin<
+    Box<dyn std::future::Future<Output = Result<ClapMcpToolOutput, ClapMcpToolError>> + Send>,
+>;
+
+/// Async counterpart of [`InProcessToolHandler`]: the returned future is awaited on the MCP
+/// server runtime. Built for [`ClapMcpAsyncToolExecutor`] types by
+/// [`async_in_process_tool_handler_for`].
+pub type AsyncInProcessToolHandler = Arc<
+    dyn Fn(&str, serde_json::Map<String, serde_json::Value>) -> InProcessToolFuture + Send + Sync,
+>;
+
+/// In-process handler for a sync or async `run`.
+#[derive(Clone)]
+pub(crate) enum ToolHandler {
+    Sync(InProcessToolHandler),
+    Async(AsyncInProcessToolHandler),
+}
+
 fn merge_captured_stdout(
     result: Result<ClapMcpToolOutput, ClapMcpToolError>,
     captured: String,
@@ -3272,12 +3983,14 @@ where
     T: clap::CommandFactory + clap::FromArgMatches,
 {
     let cli = parse_cli_from_tool_args::<T>(schema, command_name, arguments)?;
-    if capture_stdout {
-        let (result, captured) = run_with_stdout_capture(|| execute(cli));
+    let output = output::ClapMcpOutput::new();
+    let result = if capture_stdout {
+        let (result, captured) = run_with_stdout_capture(|| output.scope_sync(|| execute(cli)));
         merge_captured_stdout(result, captured)
     } else {
-        execute(cli)
-    }
+        output.scope_sync(|| execute(cli))
+    };
+    merge_captured_stdout(result, output.take())
 }
 
 fn execute_in_process_command_stateless<T>(
@@ -3336,6 +4049,29 @@ where
     ) as InProcessToolHandler
 }
 
+/// Builds an [`AsyncInProcessToolHandler`] for a [`ClapMcpAsyncToolExecutor`] type. Arguments
+/// are parsed before the future is created; `run` is awaited when the server polls it.
+///
+/// `capture_stdout` does not apply: other tasks may write to stdout while `run` is suspended.
+/// Use [`mcp_println!`] instead; its output is merged into the call's Text result.
+pub fn async_in_process_tool_handler_for<T>(schema: ClapSchema) -> AsyncInProcessToolHandler
+where
+    T: ClapMcpAsyncToolExecutor + clap::CommandFactory + clap::FromArgMatches + 'static,
+{
+    Arc::new(
+        move |cmd: &str, args: serde_json::Map<String, serde_json::Value>| {
+            match parse_cli_from_tool_args::<T>(&schema, cmd, args) {
+                Ok(cli) => Box::pin(async move {
+                    let output = output::ClapMcpOutput::new();
+                    let result = output.scope(cli.execute_for_mcp_async()).await;
+                    merge_captured_stdout(result, output.take())
+                }) as InProcessToolFuture,
+                Err(error) => Box::pin(std::future::ready(Err(error))),
+            }
+        },
+    ) as AsyncInProcessToolHandler
+}
+
 pub(crate) fn make_in_process_handler_with_state<T>(
     schema: ClapSchema,
     state: Arc<T::State>,
@@ -3527,24 +4263,30 @@ where
             // `parallel_safe` load). Re-install via `run_with_mcp_task_id`, mirroring the
             // dedicated-thread `McpTaskIdGuard` path below.
             let task_id = crate::logging::current_mcp_task_id();
-            Ok(handle.block_on(async move {
-                match task_id {
-                    Some(id) => crate::logging::run_with_mcp_task_id(id, f()).await,
-                    None => f().await,
-                }
-            }))
+            let output = output::ClapMcpOutput::current();
+            let handles = call_context::CallHandles::current();
+            Ok(
+                handle.block_on(with_tool_output(output, handles, async move {
+                    match task_id {
+                        Some(id) => crate::logging::run_with_mcp_task_id(id, f()).await,
+                        None => f().await,
+                    }
+                })),
+            )
         })
     } else {
         let catch_panics = config.catch_in_process_panics;
         let run_on_dedicated_thread = || {
             let task_id = crate::logging::current_mcp_task_id();
+            let output = output::ClapMcpOutput::current();
+            let handles = call_context::CallHandles::current();
             std::thread::scope(|s| {
                 let join_handle = s.spawn(move || {
                     let _task_id_guard = task_id.map(crate::logging::McpTaskIdGuard::new);
                     let rt = tokio::runtime::Builder::new_current_thread()
                         .enable_all()
                         .build()?;
-                    Ok(rt.block_on(f()))
+                    Ok(rt.block_on(with_tool_output(output, handles, f())))
                 });
                 match join_handle.join() {
                     Ok(inner) => inner,
@@ -3564,6 +4306,26 @@ where
     }
 }
 
+/// Carries the calling tool's [`output::ClapMcpOutput`] and server handles
+/// ([`ResourceUpdateNotifier::current`], [`ClapMcpRegistry::current`],
+/// [`sampling::ClapMcpSampler::current`]) into a nested runtime.
+async fn with_tool_output<F: std::future::Future>(
+    output: Option<output::ClapMcpOutput>,
+    handles: Option<call_context::CallHandles>,
+    fut: F,
+) -> F::Output {
+    let fut = async move {
+        match output {
+            Some(output) => output.scope(fut).await,
+            None => fut.await,
+        }
+    };
+    match handles {
+        Some(handles) => handles.scope(fut).await,
+        None => fut.await,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3765,6 +4527,8 @@ mod tests {
             action: Some("SetTrue".to_string()),
             value_names: vec![],
             num_args: None,
+            possible_values: vec![],
+            value_hint: None,
         };
         let (json_type, items) = mcp_type_for_arg(&boolean_arg);
         assert_eq!(json_type, json!("boolean"));
@@ -3822,6 +4586,8 @@ mod tests {
             action: Some("Set".to_string()),
             value_names: vec!["NAME".to_string()],
             num_args: Some("1..".to_string()),
+            possible_values: vec![],
+            value_hint: None,
         };
         let (json_type, items) = mcp_type_for_arg(&multi_value_arg);
         assert_eq!(json_type, json!("array"));
@@ -4791,6 +5557,61 @@ mod tests {
         ));
     }
 
+    #[cfg(all(unix, feature = "http"))]
+    #[test]
+    fn test_unix_flag_helpers_cover_command_and_argv_shapes() {
+        let flags = ClapMcpBuiltinFlags::default();
+        let cmd = command_with_mcp_unix_flag(Command::new("app").subcommand(Command::new("run")));
+        assert!(
+            cmd.get_arguments()
+                .any(|a| a.get_long() == Some(MCP_UNIX_FLAG_LONG))
+        );
+        assert!(is_builtin_arg(CLAP_MCP_UNIX_FLAG_ID));
+
+        let matches = cmd
+            .clone()
+            .get_matches_from(["app", "--mcp-unix", "/tmp/app.sock"]);
+        assert_eq!(
+            matches.get_one::<PathBuf>(CLAP_MCP_UNIX_FLAG_ID),
+            Some(&PathBuf::from("/tmp/app.sock"))
+        );
+
+        for args in [
+            vec!["--mcp-unix".to_string(), "/tmp/app.sock".to_string()],
+            vec!["--mcp-unix=/tmp/app.sock".to_string()],
+        ] {
+            assert_eq!(
+                argv_mcp_unix_path_from_args(&args, &flags).expect("path should parse"),
+                Some(PathBuf::from("/tmp/app.sock"))
+            );
+            assert!(argv_requests_mcp_unix_without_subcommand_from_args(
+                &args, &cmd, &flags
+            ));
+        }
+        assert!(
+            argv_mcp_unix_path_from_args(&["--help".to_string()], &flags)
+                .expect("no unix flag")
+                .is_none()
+        );
+        assert!(matches!(
+            argv_mcp_unix_path_from_args(&["--mcp-unix".to_string()], &flags),
+            Err(ClapMcpError::InvalidConfig(message)) if message.contains("--mcp-unix")
+        ));
+        assert!(!argv_requests_mcp_unix_without_subcommand_from_args(
+            &[
+                "run".to_string(),
+                "--mcp-unix".to_string(),
+                "/tmp/app.sock".to_string()
+            ],
+            &cmd,
+            &flags
+        ));
+        assert!(argv_contains_clap_mcp_flags(
+            &["--mcp-unix=/tmp/app.sock".to_string()],
+            &flags
+        ));
+    }
+
     #[tokio::test]
     async fn test_serve_mcp_fails_fast_on_invalid_schema_json() {
         let err = serve_mcp(
@@ -5449,7 +6270,7 @@ mod tests {
             schema_json,
             tools,
             executable_path,
-            handler,
+            handler.map(ToolHandler::Sync),
             schema.root.name.clone(),
             &config,
             &serve_options,
@@ -5459,7 +6280,7 @@ mod tests {
     }
 
     #[test]
-    fn test_build_clap_mcp_server_rejects_task_augmented_without_reinvocation_safe() {
+    fn test_build_clap_mcp_server_allows_task_augmented_subprocess_tools() {
         let config = ClapMcpConfig {
             reinvocation_safe: false,
             ..Default::default()
@@ -5471,47 +6292,62 @@ mod tests {
         let schema = nested_schema();
         let schema_json = serde_json::to_string(&schema).expect("schema json");
         let tools = tools_from_schema_with_metadata(&schema, &config, &metadata);
-        assert!(matches!(
+        let build = |executable_path: Option<PathBuf>| {
             build_clap_mcp_server(
-                schema_json,
-                tools,
+                schema_json.clone(),
+                tools.clone(),
+                executable_path,
                 None,
-                Some(Arc::new(|_, _| Ok(ClapMcpToolOutput::Text("ok".into())))),
-                schema.root.name,
+                schema.root.name.clone(),
                 &config,
                 &ClapMcpServeOptions::default(),
                 &metadata,
-            ),
-            Err(ClapMcpError::InvalidConfig(_))
-        ));
+            )
+            .expect("server should build")
+        };
+        let subprocess = build(Some(PathBuf::from("/bin/true")));
+        assert!(subprocess.inner.allows_task_tool("echo"));
+        // Nothing executes the tool, so there is no task to run it under.
+        let placeholder = build(None);
+        assert!(!placeholder.inner.allows_task_tool("echo"));
     }
 
     #[test]
-    fn test_build_clap_mcp_server_rejects_task_augmented_without_in_process_handler() {
+    fn test_build_clap_mcp_server_rejects_subprocess_tools_without_executable() {
         let config = ClapMcpConfig {
             reinvocation_safe: true,
             ..Default::default()
         };
         let metadata = ClapMcpSchemaMetadata {
-            task_augmented_tools: true,
+            tool_execution_modes: std::collections::HashMap::from([(
+                "echo".to_string(),
+                ClapMcpExecutionMode::Subprocess,
+            )]),
             ..Default::default()
         };
         let schema = nested_schema();
         let schema_json = serde_json::to_string(&schema).expect("schema json");
         let tools = tools_from_schema_with_metadata(&schema, &config, &metadata);
-        assert!(matches!(
+        let handler: InProcessToolHandler =
+            Arc::new(|_, _| Ok(ClapMcpToolOutput::Text("ok".into())));
+        let build = |executable_path: Option<PathBuf>| {
             build_clap_mcp_server(
-                schema_json,
-                tools,
-                None,
-                None,
-                schema.root.name,
+                schema_json.clone(),
+                tools.clone(),
+                executable_path,
+                Some(ToolHandler::Sync(handler.clone())),
+                schema.root.name.clone(),
                 &config,
                 &ClapMcpServeOptions::default(),
                 &metadata,
-            ),
-            Err(ClapMcpError::InvalidConfig(_))
-        ));
+            )
+        };
+        let Err(ClapMcpError::InvalidConfig(message)) = build(None) else {
+            panic!("subprocess tool without an executable should be rejected");
+        };
+        assert!(message.contains("echo"), "{message}");
+        let server = build(Some(PathBuf::from("/bin/true"))).expect("server should build");
+        assert!(!server.inner.runs_in_process("echo"));
     }
 
     #[test]
@@ -5623,6 +6459,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_info_server_identity_and_custom_instructions() {
+        let handler: InProcessToolHandler =
+            Arc::new(|_, _| Ok(ClapMcpToolOutput::Text("ok".into())));
+        let config = ClapMcpConfig {
+            reinvocation_safe: true,
+            instructions: Some("from attribute".into()),
+            ..Default::default()
+        };
+
+        let info = build_test_server(
+            config.clone(),
+            ClapMcpSchemaMetadata::default(),
+            ClapMcpServeOptions::default(),
+            Some(handler.clone()),
+            None,
+        )
+        .get_info();
+        assert_eq!(info.server_info.name, "sample");
+        assert_eq!(info.server_info.version, "0.0.0");
+        assert!(info.server_info.title.is_none());
+        assert_eq!(info.instructions.as_deref(), Some("from attribute"));
+
+        let info = build_test_server(
+            config,
+            ClapMcpSchemaMetadata::default(),
+            ClapMcpServeOptions {
+                log_rx: Some(logging::log_channel(4).1),
+                server_identity: ServerIdentity {
+                    name: Some("renamed".into()),
+                    title: Some("Renamed CLI".into()),
+                    website_url: Some("https://example.com".into()),
+                    icons: vec![rmcp::model::Icon::new("https://example.com/icon.png")],
+                    ..Default::default()
+                },
+                instructions: Some("from options".into()),
+                ..Default::default()
+            },
+            Some(handler),
+            None,
+        )
+        .get_info();
+        assert_eq!(info.server_info.name, "renamed");
+        assert_eq!(info.server_info.title.as_deref(), Some("Renamed CLI"));
+        assert_eq!(
+            info.server_info.website_url.as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(info.server_info.icons.map(|icons| icons.len()), Some(1));
+        assert_eq!(
+            info.instructions,
+            Some(format!("from options\n\n{LOG_INTERPRETATION_INSTRUCTIONS}"))
+        );
+    }
+
+    #[test]
+    fn test_server_identity_defaults_to_root_command() {
+        let schema =
+            schema_from_command(&Command::new("mytool").version("1.2.3").about("Does things"));
+        let info = ServerIdentity::default().implementation(&schema.root);
+        assert_eq!(info.name, "mytool");
+        assert_eq!(info.version, "1.2.3");
+        assert_eq!(info.description.as_deref(), Some("Does things"));
+        assert!(info.icons.is_none());
+
+        let info = ServerIdentity {
+            version: Some("2.0.0".into()),
+            description: Some("Overridden".into()),
+            ..Default::default()
+        }
+        .implementation(&schema.root);
+        assert_eq!(info.version, "2.0.0");
+        assert_eq!(info.description.as_deref(), Some("Overridden"));
+    }
+
     #[test]
     fn test_supported_protocol_versions_matches_conformance_set() {
         let handler: InProcessToolHandler =
@@ -32,6 +32,7 @@ use rmcp::model::LoggingLevel;
 use serde_json::Value;
 use std::cell::RefCell;
 use std::future::Future;
+use std::sync::atomic::{AtomicU8, Ordering};
 use tokio::sync::mpsc;
 
 tokio::task_local! {
@@ -42,6 +43,9 @@ thread_local! {
     static THREAD_MCP_TASK_ID: RefCell<Option<String>> = const { RefCell::new(None) };
 }
 
+/// [`level_rank`] of the lowest level the MCP server currently forwards; read by the bridges.
+static FORWARDED_LEVEL: AtomicU8 = AtomicU8::new(0);
+
 /// Parameters for a logging notification forwarded through [`log_channel`].
 ///
 /// Includes optional `meta` (e.g. `taskId` during task-augmented tool bodies). The MCP server
@@ -151,6 +155,34 @@ pub fn level_to_mcp(level: &str) -> LoggingLevel {
     }
 }
 
+/// Severity order of `level`, `Debug` lowest.
+pub(crate) fn level_rank(level: LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
+
+/// Whether the MCP server forwards messages at `level` to the client receiving logs, per its
+/// `logging/setLevel` (or [`crate::ClapMcpServeOptions::default_log_level`]).
+///
+/// `ClapMcpTracingLayer` and `ClapMcpLogBridge` check this before formatting a message.
+/// Everything is forwarded until a server starts.
+pub fn is_level_forwarded(level: LoggingLevel) -> bool {
+    level_rank(level) >= FORWARDED_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Set the level [`is_level_forwarded`] checks against.
+pub(crate) fn set_forwarded_level(level: LoggingLevel) {
+    FORWARDED_LEVEL.store(level_rank(level), Ordering::Relaxed);
+}
+
 /// Creates a channel for forwarding log messages to the MCP server.
 ///
 /// Returns `(sender, receiver)`. Pass the receiver to `ClapMcpServeOptions::log_rx`.
@@ -251,9 +283,6 @@ mod tracing_layer {
         S: Subscriber,
     {
         fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
-            let mut visitor = LogVisitor::default();
-            event.record(&mut visitor);
-            let message = visitor.message.unwrap_or_else(|| format!("{:?}", event));
             let level = level_to_mcp(match *event.metadata().level() {
                 tracing::Level::TRACE => "trace",
                 tracing::Level::DEBUG => "debug",
@@ -261,6 +290,12 @@ mod tracing_layer {
                 tracing::Level::WARN => "warn",
                 tracing::Level::ERROR => "error",
             });
+            if !is_level_forwarded(level) {
+                return;
+            }
+            let mut visitor = LogVisitor::default();
+            event.record(&mut visitor);
+            let message = visitor.message.unwrap_or_else(|| format!("{:?}", event));
             let params = log_params(level, Some(self.logger_name.clone()), message);
             let _ = self.tx.try_send(params);
         }
@@ -332,18 +367,15 @@ mod log_bridge {
     }
 
     impl Log for ClapMcpLogBridge {
-        fn enabled(&self, _metadata: &log::Metadata) -> bool {
-            true
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            is_level_forwarded(mcp_level(metadata.level()))
         }
 
         fn log(&self, record: &log::Record) {
-            let level = level_to_mcp(match record.level() {
-                log::Level::Trace => "trace",
-                log::Level::Debug => "debug",
-                log::Level::Info => "info",
-                log::Level::Warn => "warn",
-                log::Level::Error => "error",
-            });
+            let level = mcp_level(record.level());
+            if !is_level_forwarded(level) {
+                return;
+            }
             let message = record.args().to_string();
             let params = log_params(level, Some(self.logger_name.clone()), message);
             let _ = self.tx.try_send(params);
@@ -351,6 +383,16 @@ mod log_bridge {
 
         fn flush(&self) {}
     }
+
+    fn mcp_level(level: log::Level) -> LoggingLevel {
+        level_to_mcp(match level {
+            log::Level::Trace => "trace",
+            log::Level::Debug => "debug",
+            log::Level::Info => "info",
+            log::Level::Warn => "warn",
+            log::Level::Error => "error",
+        })
+    }
 }
 
 #[cfg(feature = "log")]
@@ -0,0 +1,145 @@
+//! Per-call tool output that stays correct under `parallel_safe`.
+//!
+//! [`crate::ClapMcpServeOptions::capture_stdout`] redirects the process-wide stdout fd, so
+//! overlapping in-process calls would see each other's output. Instead, write through the
+//! [`ClapMcpOutput`](crate::output::ClapMcpOutput) for the current call — usually via
+//! [`mcp_print!`](crate::mcp_print) / [`mcp_println!`](crate::mcp_println). In-process tool
+//! handlers install a fresh handle for every call and merge what was written into that call's
+//! Text result (like captured stdout). Outside an MCP call (normal CLI runs, subprocess tools)
+//! the macros print to stdout.
+//!
+//! # Example
+//!
+//! ```
+//! use clap_mcp::{mcp_println, output::ClapMcpOutput};
+//!
+//! let output = ClapMcpOutput::new();
+//! output.scope_sync(|| mcp_println!("processed {} files", 3));
+//! assert_eq!(output.contents(), "processed 3 files\n");
+//! ```
+
+use std::{
+    cell::RefCell,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+tokio::task_local! {
+    static TASK_OUTPUT: ClapMcpOutput;
+}
+
+thread_local! {
+    static THREAD_OUTPUT: RefCell<Option<ClapMcpOutput>> = const { RefCell::new(None) };
+}
+
+/// Output buffer for one tool call. Cloning shares the buffer.
+#[derive(Debug, Clone, Default)]
+pub struct ClapMcpOutput {
+    buf: Arc<Mutex<String>>,
+}
+
+impl ClapMcpOutput {
+    /// Empty output buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The output for the current tool call, if any.
+    ///
+    /// Prefers the current async task's output, then the current thread's.
+    pub fn current() -> Option<Self> {
+        TASK_OUTPUT
+            .try_with(Clone::clone)
+            .ok()
+            .or_else(|| THREAD_OUTPUT.with(|slot| slot.borrow().clone()))
+    }
+
+    /// Append `s` to the buffer.
+    pub fn write_str(&self, s: &str) {
+        self.lock().push_str(s);
+    }
+
+    /// Everything written so far.
+    pub fn contents(&self) -> String {
+        self.lock().clone()
+    }
+
+    /// Run `f` with this output as [`Self::current`] on the current thread.
+    pub fn scope_sync<R>(&self, f: impl FnOnce() -> R) -> R {
+        let previous = THREAD_OUTPUT.with(|slot| slot.replace(Some(self.clone())));
+        let _restore = RestoreThreadOutput(previous);
+        f()
+    }
+
+    /// Run `fut` with this output as [`Self::current`] in the current async task.
+    pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        TASK_OUTPUT.scope(self.clone(), fut).await
+    }
+
+    pub(crate) fn take(&self) -> String {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, String> {
+        self.buf.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Restores the previous thread output even if the scoped closure panics.
+struct RestoreThreadOutput(Option<ClapMcpOutput>);
+
+impl Drop for RestoreThreadOutput {
+    fn drop(&mut self) {
+        THREAD_OUTPUT.with(|slot| slot.replace(self.0.take()));
+    }
+}
+
+impl std::fmt::Write for ClapMcpOutput {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        ClapMcpOutput::write_str(self, s);
+        Ok(())
+    }
+}
+
+impl std::io::Write for ClapMcpOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        ClapMcpOutput::write_str(self, &String::from_utf8_lossy(buf));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Backend for [`mcp_print!`](crate::mcp_print); not public API.
+#[doc(hidden)]
+pub fn _print(args: std::fmt::Arguments<'_>) {
+    match ClapMcpOutput::current() {
+        Some(mut output) => {
+            let _ = std::fmt::Write::write_fmt(&mut output, args);
+        }
+        None => print!("{args}"),
+    }
+}
+
+/// Like `print!`, but writes to the current tool call's
+/// [`ClapMcpOutput`](crate::output::ClapMcpOutput) when there is one.
+#[macro_export]
+macro_rules! mcp_print {
+    ($($arg:tt)*) => {
+        $crate::output::_print(::std::format_args!($($arg)*))
+    };
+}
+
+/// Like `println!`, but writes to the current tool call's
+/// [`ClapMcpOutput`](crate::output::ClapMcpOutput) when there is one.
+#[macro_export]
+macro_rules! mcp_println {
+    () => {
+        $crate::mcp_print!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::output::_print(::std::format_args!("{}\n", ::std::format_args!($($arg)*)))
+    };
+}
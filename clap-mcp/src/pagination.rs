@@ -0,0 +1,132 @@
+//! Cursor pagination for `tools/list`, `resources/list`, `resources/templates/list`, and
+//! `prompts/list` ([`crate::ClapMcpServeOptions::page_size`]).
+//!
+//! A cursor names the list and the key (tool or prompt name, resource URI, template) of the
+//! last item on the previous page, hex-encoded so clients treat it as opaque. Resuming after a
+//! key instead of an offset keeps cursors valid when earlier entries are hidden or added (see
+//! [`crate::ClapMcpRegistry`]); a cursor whose item no longer exists is rejected with
+//! `-32602`.
+
+use rmcp::ErrorData as McpError;
+use std::fmt::Write as _;
+
+/// One page of `items`, starting after the item named by `cursor`, and the cursor for the
+/// next page (`None` on the last page). Without `page_size` every remaining item is returned.
+pub(crate) fn paginate<T>(
+    items: Vec<T>,
+    key: impl Fn(&T) -> &str,
+    list: &str,
+    page_size: Option<usize>,
+    cursor: Option<&str>,
+) -> Result<(Vec<T>, Option<String>), McpError> {
+    let start = match cursor {
+        None => 0,
+        Some(cursor) => {
+            let after = decode_cursor(list, cursor).ok_or_else(|| invalid_cursor(cursor))?;
+            items
+                .iter()
+                .position(|item| key(item) == after)
+                .ok_or_else(|| invalid_cursor(cursor))?
+                + 1
+        }
+    };
+    let mut page: Vec<T> = items.into_iter().skip(start).collect();
+    let next = match page_size {
+        Some(size) if page.len() > size => {
+            page.truncate(size);
+            page.last().map(|last| encode_cursor(list, key(last)))
+        }
+        _ => None,
+    };
+    Ok((page, next))
+}
+
+fn encode_cursor(list: &str, key: &str) -> String {
+    let mut cursor = format!("{list}.");
+    for byte in key.bytes() {
+        let _ = write!(cursor, "{byte:02x}");
+    }
+    cursor
+}
+
+fn decode_cursor(list: &str, cursor: &str) -> Option<String> {
+    let hex = cursor.strip_prefix(list)?.strip_prefix('.')?;
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+fn invalid_cursor(cursor: &str) -> McpError {
+    McpError::invalid_params(
+        "invalid or expired cursor",
+        Some(serde_json::json!({ "cursor": cursor })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("tool-{i}")).collect()
+    }
+
+    #[test]
+    fn pages_follow_cursors_to_the_end() {
+        let (page, next) = paginate(names(5), String::as_str, "tools", Some(2), None).unwrap();
+        assert_eq!(page, ["tool-0", "tool-1"]);
+        let (page, next) =
+            paginate(names(5), String::as_str, "tools", Some(2), next.as_deref()).unwrap();
+        assert_eq!(page, ["tool-2", "tool-3"]);
+        let (page, next) =
+            paginate(names(5), String::as_str, "tools", Some(2), next.as_deref()).unwrap();
+        assert_eq!(page, ["tool-4"]);
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn exact_multiple_and_no_page_size_have_no_next_cursor() {
+        let (page, next) = paginate(names(4), String::as_str, "tools", Some(4), None).unwrap();
+        assert_eq!(page.len(), 4);
+        assert_eq!(next, None);
+        let (page, next) = paginate(names(4), String::as_str, "tools", None, None).unwrap();
+        assert_eq!(page.len(), 4);
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn cursor_survives_earlier_removals_but_not_its_own() {
+        let (_, next) = paginate(names(5), String::as_str, "tools", Some(2), None).unwrap();
+        let mut fewer = names(5);
+        fewer.remove(0);
+        let (page, _) = paginate(fewer, String::as_str, "tools", Some(2), next.as_deref()).unwrap();
+        assert_eq!(page, ["tool-2", "tool-3"]);
+
+        let mut without_key = names(5);
+        without_key.remove(1);
+        assert!(
+            paginate(
+                without_key,
+                String::as_str,
+                "tools",
+                Some(2),
+                next.as_deref()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn cursor_is_bound_to_its_list() {
+        let (_, next) = paginate(names(3), String::as_str, "tools", Some(1), None).unwrap();
+        let next = next.unwrap();
+        assert!(paginate(names(3), String::as_str, "prompts", Some(1), Some(&next)).is_err());
+        assert!(paginate(names(3), String::as_str, "tools", Some(1), Some("tools.zz")).is_err());
+        assert!(paginate(names(3), String::as_str, "tools", Some(1), Some("garbage")).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! Runtime changes to what a running server advertises.
+//!
+//! `tools/list`, `prompts/list`, `resources/list`, and `resources/templates/list` are built
+//! when the server starts. A [`ClapMcpRegistry`] hides or re-shows individual entries (feature
+//! flags, license checks, tools that only make sense after `login`) and replaces the clap
+//! schema tools are derived from. Every change that alters a list sends the matching
+//! `notifications/*/list_changed` to connected sessions.
+
+use crate::resource_updates::ListKind;
+use crate::server::ServeHandlerInner;
+use crate::{ClapMcpError, ClapSchema};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, Weak};
+
+/// Enables, disables, and replaces tools, prompts, and resources while serving.
+///
+/// Get one from [`crate::ClapMcpServeOptions::registry`] or [`crate::ServeMcpBuilder::registry`]
+/// before serving, or call [`Self::current`] from an in-process tool. Cloning shares the state;
+/// changes made before serving apply from the first `tools/list`.
+///
+/// Disabled entries are dropped from their list and rejected like unknown names: calls to a
+/// disabled tool, `prompts/get` for a disabled prompt, and `resources/read` for a disabled URI
+/// (or a URI matching a disabled template) return errors.
+#[derive(Clone, Default)]
+pub struct ClapMcpRegistry {
+    inner: Arc<RegistryInner>,
+}
+
+#[derive(Default)]
+struct RegistryInner {
+    disabled: Mutex<Disabled>,
+    /// Servers built with this registry; rebuilt on schema replacement and notified on changes.
+    servers: Mutex<Vec<Weak<ServeHandlerInner>>>,
+}
+
+#[derive(Default)]
+struct Disabled {
+    tools: HashSet<String>,
+    prompts: HashSet<String>,
+    resources: HashSet<String>,
+}
+
+impl std::fmt::Debug for ClapMcpRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let disabled = self.disabled();
+        f.debug_struct("ClapMcpRegistry")
+            .field("disabled_tools", &disabled.tools)
+            .field("disabled_prompts", &disabled.prompts)
+            .field("disabled_resources", &disabled.resources)
+            .finish()
+    }
+}
+
+impl ClapMcpRegistry {
+    /// The registry of the server running the current in-process tool call, if any.
+    pub fn current() -> Option<Self> {
+        crate::call_context::CallHandles::current().map(|handles| handles.registry)
+    }
+
+    /// Show or hide tool `name` (clap subcommand path or custom tool name). Returns whether
+    /// the state changed; `notifications/tools/list_changed` is sent only then.
+    pub fn set_tool_enabled(&self, name: &str, enabled: bool) -> bool {
+        self.set_enabled(ListKind::Tools, name, enabled)
+    }
+
+    /// Show or hide prompt `name`, including the built-in logging guide.
+    pub fn set_prompt_enabled(&self, name: &str, enabled: bool) -> bool {
+        self.set_enabled(ListKind::Prompts, name, enabled)
+    }
+
+    /// Show or hide a resource by URI (including `clap://schema`), or a resource template by
+    /// its `uriTemplate`.
+    pub fn set_resource_enabled(&self, uri: &str, enabled: bool) -> bool {
+        self.set_enabled(ListKind::Resources, uri, enabled)
+    }
+
+    /// Whether tool `name` is listed, callable, and completes its arguments.
+    pub fn is_tool_enabled(&self, name: &str) -> bool {
+        !self.disabled().tools.contains(name)
+    }
+
+    /// Whether prompt `name` is listed, retrievable, and completes its arguments.
+    pub fn is_prompt_enabled(&self, name: &str) -> bool {
+        !self.disabled().prompts.contains(name)
+    }
+
+    /// Whether the resource or template `uri` is listed and readable.
+    pub fn is_resource_enabled(&self, uri: &str) -> bool {
+        !self.disabled().resources.contains(uri)
+    }
+
+    /// Replace the clap schema of every server using this registry: tools are re-derived with
+    /// the server's config and metadata, `clap://schema` serves the new schema, and
+    /// `notifications/tools/list_changed` is sent.
+    ///
+    /// In-process tools still parse arguments into the CLI type the server was built for, so
+    /// the new schema should be a subset of (or match) that type; subprocess tools run the
+    /// executable with the new schema's argument layout.
+    pub fn replace_schema(&self, schema: &ClapSchema) -> Result<(), ClapMcpError> {
+        let servers = self.servers();
+        for server in &servers {
+            server.replace_schema(schema)?;
+        }
+        notify(&servers, ListKind::Tools);
+        Ok(())
+    }
+
+    pub(crate) fn attach(&self, server: &Arc<ServeHandlerInner>) {
+        if let Ok(mut servers) = self.inner.servers.lock() {
+            servers.retain(|s| s.strong_count() > 0);
+            servers.push(Arc::downgrade(server));
+        }
+    }
+
+    fn set_enabled(&self, kind: ListKind, key: &str, enabled: bool) -> bool {
+        let changed = {
+            let mut disabled = self.disabled();
+            let set = match kind {
+                ListKind::Tools => &mut disabled.tools,
+                ListKind::Prompts => &mut disabled.prompts,
+                ListKind::Resources => &mut disabled.resources,
+            };
+            if enabled {
+                set.remove(key)
+            } else {
+                set.insert(key.to_string())
+            }
+        };
+        if changed {
+            notify(&self.servers(), kind);
+        }
+        changed
+    }
+
+    fn disabled(&self) -> std::sync::MutexGuard<'_, Disabled> {
+        self.inner
+            .disabled
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn servers(&self) -> Vec<Arc<ServeHandlerInner>> {
+        match self.inner.servers.lock() {
+            Ok(mut servers) => {
+                servers.retain(|s| s.strong_count() > 0);
+                servers.iter().filter_map(Weak::upgrade).collect()
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+fn notify(servers: &[Arc<ServeHandlerInner>], kind: ListKind) {
+    for server in servers {
+        server.resource_updates.notify_list_changed(kind);
+    }
+}
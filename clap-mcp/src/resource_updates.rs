@@ -0,0 +1,298 @@
+//! `notifications/resources/updated` for subscribed resources, and the session list that
+//! `notifications/*/list_changed` from [`crate::ClapMcpRegistry`] goes to.
+//!
+//! Each MCP session records the URIs it subscribed to with legacy `resources/subscribe`, and
+//! each open `subscriptions/listen` stream (protocol 2026-07-28) records its accepted
+//! `resourceSubscriptions`. A [`ResourceUpdateNotifier`] is shared by every session of one
+//! server; [`notify`] sends the notification to the subscribers of that URI. Legacy
+//! subscriptions may also name a URI template (`myapp://item/{id}`) that matches it; listen
+//! streams only accept the exact URIs they requested.
+//!
+//! [`notify`]: ResourceUpdateNotifier::notify
+
+use crate::content::match_uri_template;
+use rmcp::model::{ResourceUpdatedNotificationParam, SubscriptionFilter};
+use rmcp::service::SubscriptionSink;
+use rmcp::{Peer, RoleServer};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime};
+
+/// Sends `notifications/resources/updated` to subscribed sessions.
+///
+/// Get one from [`crate::ClapMcpServeOptions::resource_updates`] or
+/// [`crate::ServeMcpBuilder::resource_update_notifier`] before serving and keep a clone wherever
+/// resources change (a background task, a `static`), or call [`Self::current`] from an
+/// in-process tool. Cloning shares the sessions.
+#[derive(Clone, Default)]
+pub struct ResourceUpdateNotifier {
+    sessions: Arc<Mutex<Vec<Weak<SessionSubscriptions>>>>,
+}
+
+impl std::fmt::Debug for ResourceUpdateNotifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResourceUpdateNotifier")
+            .field("sessions", &self.live_sessions().len())
+            .finish()
+    }
+}
+
+impl ResourceUpdateNotifier {
+    /// The notifier of the server running the current in-process tool call, if any.
+    pub fn current() -> Option<Self> {
+        crate::call_context::CallHandles::current().map(|handles| handles.resource_updates)
+    }
+
+    /// Notify every session subscribed to `uri` (or to a template matching it) that the
+    /// resource changed. Returns the number of sessions notified.
+    ///
+    /// Sends are spawned on the server's runtime, so this can be called from any thread,
+    /// including synchronous tool code.
+    pub fn notify(&self, uri: &str) -> usize {
+        let mut notified = 0;
+        for session in self.live_sessions() {
+            let Some((target, runtime)) = session.target_if_subscribed(uri) else {
+                continue;
+            };
+            let uri = uri.to_string();
+            runtime.spawn(async move {
+                match target {
+                    UpdateTarget::Session(peer) => {
+                        let _ = peer
+                            .notify_resource_updated(ResourceUpdatedNotificationParam::new(uri))
+                            .await;
+                    }
+                    UpdateTarget::Listen(sink) => {
+                        let _ = sink.notify_resource_updated(uri).await;
+                    }
+                }
+            });
+            notified += 1;
+        }
+        notified
+    }
+
+    /// Poll `path` every `interval` and [`notify`](Self::notify) `uri` when its modification
+    /// time or size changes (including the file appearing or disappearing). Use for
+    /// [`crate::content::CustomResource`]s whose content is read from a file.
+    ///
+    /// Must be called within a tokio runtime; abort the returned handle to stop watching.
+    pub fn watch_file(
+        &self,
+        uri: impl Into<String>,
+        path: impl Into<PathBuf>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let notifier = self.clone();
+        let uri = uri.into();
+        let path = path.into();
+        tokio::spawn(async move {
+            let mut last = file_stamp(&path).await;
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let current = file_stamp(&path).await;
+                if current != last {
+                    last = current;
+                    notifier.notify(&uri);
+                }
+            }
+        })
+    }
+
+    /// Send `notifications/{kind}/list_changed` to every session, and to listen streams that
+    /// opted in to it. Returns the number of recipients.
+    pub(crate) fn notify_list_changed(&self, kind: ListKind) -> usize {
+        let mut notified = 0;
+        for session in self.live_sessions() {
+            let Some((target, runtime)) = session.target_if_listening(kind) else {
+                continue;
+            };
+            runtime.spawn(async move {
+                match (target, kind) {
+                    (UpdateTarget::Session(peer), ListKind::Tools) => {
+                        let _ = peer.notify_tool_list_changed().await;
+                    }
+                    (UpdateTarget::Session(peer), ListKind::Prompts) => {
+                        let _ = peer.notify_prompt_list_changed().await;
+                    }
+                    (UpdateTarget::Session(peer), ListKind::Resources) => {
+                        let _ = peer.notify_resource_list_changed().await;
+                    }
+                    (UpdateTarget::Listen(sink), ListKind::Tools) => {
+                        let _ = sink.notify_tool_list_changed().await;
+                    }
+                    (UpdateTarget::Listen(sink), ListKind::Prompts) => {
+                        let _ = sink.notify_prompt_list_changed().await;
+                    }
+                    (UpdateTarget::Listen(sink), ListKind::Resources) => {
+                        let _ = sink.notify_resource_list_changed().await;
+                    }
+                }
+            });
+            notified += 1;
+        }
+        notified
+    }
+
+    /// Subscription state for a new session, registered with this notifier.
+    pub(crate) fn new_session(&self) -> Arc<SessionSubscriptions> {
+        let session = Arc::new(SessionSubscriptions::default());
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.retain(|s| s.strong_count() > 0);
+            sessions.push(Arc::downgrade(&session));
+        }
+        session
+    }
+
+    fn live_sessions(&self) -> Vec<Arc<SessionSubscriptions>> {
+        match self.sessions.lock() {
+            Ok(mut sessions) => {
+                sessions.retain(|s| s.strong_count() > 0);
+                sessions.iter().filter_map(Weak::upgrade).collect()
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+async fn file_stamp(path: &std::path::Path) -> Option<(Option<SystemTime>, u64)> {
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    Some((metadata.modified().ok(), metadata.len()))
+}
+
+/// Which `notifications/*/list_changed` to send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListKind {
+    Tools,
+    Prompts,
+    Resources,
+}
+
+/// Where a subscriber's notifications go.
+#[derive(Clone)]
+enum UpdateTarget {
+    /// Legacy `resources/subscribe` on this session.
+    Session(Peer<RoleServer>),
+    /// An open `subscriptions/listen` stream.
+    Listen(SubscriptionSink),
+}
+
+/// One subscriber's URIs and where to notify it: a session (legacy subscribe) or one listen
+/// stream.
+#[derive(Default)]
+pub(crate) struct SessionSubscriptions {
+    state: Mutex<SessionState>,
+}
+
+#[derive(Default)]
+struct SessionState {
+    uris: HashSet<String>,
+    /// List kinds a listen stream opted in to; sessions receive every kind.
+    lists: Vec<ListKind>,
+    target: Option<(UpdateTarget, tokio::runtime::Handle)>,
+}
+
+impl SessionSubscriptions {
+    /// Remember where to send this session's list changes and legacy subscription
+    /// notifications.
+    pub(crate) fn set_peer(&self, peer: Peer<RoleServer>) {
+        if let Ok(mut state) = self.state.lock() {
+            state.lists = vec![ListKind::Tools, ListKind::Prompts, ListKind::Resources];
+        }
+        self.set_target(UpdateTarget::Session(peer));
+    }
+
+    /// Route the notifications `accepted` by a `subscriptions/listen` stream to its sink.
+    pub(crate) fn set_listen_sink(&self, sink: SubscriptionSink, accepted: &SubscriptionFilter) {
+        if let Ok(mut state) = self.state.lock() {
+            state
+                .uris
+                .extend(accepted.resource_subscriptions.iter().flatten().cloned());
+            for (kind, flag) in [
+                (ListKind::Tools, accepted.tools_list_changed),
+                (ListKind::Prompts, accepted.prompts_list_changed),
+                (ListKind::Resources, accepted.resources_list_changed),
+            ] {
+                if flag == Some(true) {
+                    state.lists.push(kind);
+                }
+            }
+        }
+        self.set_target(UpdateTarget::Listen(sink));
+    }
+
+    fn set_target(&self, target: UpdateTarget) {
+        if let (Ok(mut state), Ok(runtime)) =
+            (self.state.lock(), tokio::runtime::Handle::try_current())
+        {
+            state.target = Some((target, runtime));
+        }
+    }
+
+    pub(crate) fn subscribe(&self, uri: String) {
+        if let Ok(mut state) = self.state.lock() {
+            state.uris.insert(uri);
+        }
+    }
+
+    /// Unknown URIs are ignored.
+    pub(crate) fn unsubscribe(&self, uri: &str) {
+        if let Ok(mut state) = self.state.lock() {
+            state.uris.remove(uri);
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn uris(&self) -> HashSet<String> {
+        self.state
+            .lock()
+            .map(|state| state.uris.clone())
+            .unwrap_or_default()
+    }
+
+    fn target_if_subscribed(&self, uri: &str) -> Option<(UpdateTarget, tokio::runtime::Handle)> {
+        let state = self.state.lock().ok()?;
+        let target = state.target.clone()?;
+        // Listen streams reject URIs outside their accepted filter, so no template matching.
+        let templates = matches!(target.0, UpdateTarget::Session(_));
+        let subscribed = state.uris.iter().any(|subscribed| {
+            subscribed == uri
+                || (templates
+                    && subscribed.contains('{')
+                    && match_uri_template(subscribed, uri).is_some())
+        });
+        subscribed.then_some(target)
+    }
+
+    fn target_if_listening(
+        &self,
+        kind: ListKind,
+    ) -> Option<(UpdateTarget, tokio::runtime::Handle)> {
+        let state = self.state.lock().ok()?;
+        state
+            .lists
+            .contains(&kind)
+            .then(|| state.target.clone())
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dropped_sessions_are_pruned() {
+        let notifier = ResourceUpdateNotifier::default();
+        let kept = notifier.new_session();
+        drop(notifier.new_session());
+        assert_eq!(notifier.live_sessions().len(), 1);
+        kept.subscribe("test://a".into());
+        // No peer yet: subscribed but nothing to notify.
+        assert_eq!(notifier.notify("test://a"), 0);
+    }
+}
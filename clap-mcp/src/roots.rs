@@ -0,0 +1,304 @@
+//! Client roots (`roots/list`) for path arguments and the subprocess working directory.
+//!
+//! When the client declares the `roots` capability, a session fetches its `file://` roots the
+//! first time a tool call (or path completion) needs them and again after
+//! `notifications/roots/list_changed`. Relative values of path args (`ValueHint::AnyPath`,
+//! `FilePath`, `DirPath`, `ExecutablePath`) resolve against the primary (first) root, and
+//! subprocess tools start in it. With [`crate::ClapMcpServeOptions::confine_paths_to_roots`],
+//! values outside every root are rejected before the tool's argv is built.
+
+// Roots remain functional in rmcp 3.x but are deprecated by SEP-2577.
+#![allow(deprecated)]
+
+use crate::{ClapSchema, completion::file_uri_to_path};
+use rmcp::{Peer, RoleServer, model::Tool};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// A path-valued argument of one tool.
+#[derive(Debug, Clone)]
+pub(crate) struct PathArg {
+    pub id: String,
+    /// `ValueHint::ExecutablePath`: bare names (no separator) are looked up on `PATH`, so they
+    /// are neither resolved nor confined.
+    pub executable: bool,
+}
+
+/// Path-valued arguments per clap tool (tools without any are omitted).
+pub(crate) fn tool_path_args(schema: &ClapSchema, tools: &[Tool]) -> HashMap<String, Vec<PathArg>> {
+    tools
+        .iter()
+        .filter_map(|tool| {
+            let args: Vec<PathArg> = crate::effective_args_for_tool(schema, &tool.name)
+                .into_iter()
+                .filter_map(|arg| match arg.value_hint.as_deref()? {
+                    "AnyPath" | "FilePath" | "DirPath" => Some(PathArg {
+                        id: arg.id,
+                        executable: false,
+                    }),
+                    "ExecutablePath" => Some(PathArg {
+                        id: arg.id,
+                        executable: true,
+                    }),
+                    _ => None,
+                })
+                .collect();
+            (!args.is_empty()).then(|| (tool.name.to_string(), args))
+        })
+        .collect()
+}
+
+/// One session's client roots, cached until the client reports a change.
+#[derive(Debug, Default)]
+pub(crate) struct SessionRoots {
+    cached: tokio::sync::Mutex<Option<Vec<PathBuf>>>,
+}
+
+impl SessionRoots {
+    /// Local directories the client exposed as `file://` roots. Empty when the client does not
+    /// declare the roots capability or the request fails; failures are not cached.
+    pub async fn get(&self, peer: &Peer<RoleServer>) -> Vec<PathBuf> {
+        let mut cached = self.cached.lock().await;
+        if let Some(roots) = cached.as_ref() {
+            return roots.clone();
+        }
+        let declares_roots = peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.roots.is_some());
+        if !declares_roots {
+            return Vec::new();
+        }
+        match peer.list_roots().await {
+            Ok(result) => {
+                let roots: Vec<PathBuf> = result
+                    .roots
+                    .iter()
+                    .filter_map(|root| file_uri_to_path(&root.uri))
+                    .collect();
+                *cached = Some(roots.clone());
+                roots
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Forget the cached roots (`notifications/roots/list_changed`).
+    pub async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+}
+
+/// Rewrite relative values of `path_args` in `arguments` against the primary root and, when
+/// `confine` is set, reject values outside every root. Returns the tool error message.
+pub(crate) fn resolve_path_args(
+    path_args: &[PathArg],
+    roots: &[PathBuf],
+    confine: bool,
+    arguments: &mut serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    for arg in path_args {
+        let Some(value) = arguments.get_mut(&arg.id) else {
+            continue;
+        };
+        match value {
+            serde_json::Value::String(path) => resolve_one(arg, path, roots, confine)?,
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    if let serde_json::Value::String(path) = item {
+                        resolve_one(arg, path, roots, confine)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn resolve_one(
+    arg: &PathArg,
+    value: &mut String,
+    roots: &[PathBuf],
+    confine: bool,
+) -> Result<(), String> {
+    if value.is_empty() || (arg.executable && !value.contains(std::path::is_separator)) {
+        return Ok(());
+    }
+    let path = Path::new(value.as_str());
+    let resolved = match roots.first() {
+        Some(primary) if path.is_relative() => primary.join(path),
+        _ => path.to_path_buf(),
+    };
+    if confine {
+        if roots.is_empty() {
+            return Err(format!(
+                "Argument '{}': cannot check path '{value}' because the client exposed no \
+                 file:// roots",
+                arg.id
+            ));
+        }
+        let real = real_path(&resolved);
+        if !roots.iter().any(|root| real.starts_with(real_path(root))) {
+            let roots: Vec<String> = roots.iter().map(|r| r.display().to_string()).collect();
+            return Err(format!(
+                "Argument '{}': path '{value}' is outside the client's roots ({})",
+                arg.id,
+                roots.join(", ")
+            ));
+        }
+        // The tool gets the path that was checked, not one the OS could resolve differently.
+        *value = real.to_string_lossy().into_owned();
+        return Ok(());
+    }
+    *value = resolved.to_string_lossy().into_owned();
+    Ok(())
+}
+
+/// Where `path` actually lands, for paths that do not exist yet too.
+///
+/// Components are applied in order and every existing prefix is canonicalized before the
+/// next one, so a `..` after a symlink steps out of the link's target, as the OS does.
+fn real_path(path: &Path) -> PathBuf {
+    let mut real = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                real.pop();
+            }
+            other => {
+                real.push(other);
+                if let Ok(canonical) = real.canonicalize() {
+                    real = canonical;
+                }
+            }
+        }
+    }
+    real
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_arg(id: &str) -> PathArg {
+        PathArg {
+            id: id.into(),
+            executable: false,
+        }
+    }
+
+    fn resolve(
+        path_args: &[PathArg],
+        roots: &[PathBuf],
+        confine: bool,
+        arguments: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let mut arguments = arguments.as_object().cloned().expect("object");
+        resolve_path_args(path_args, roots, confine, &mut arguments)?;
+        Ok(serde_json::Value::Object(arguments))
+    }
+
+    #[test]
+    fn relative_values_resolve_against_primary_root() {
+        let roots = [PathBuf::from("/ws/app"), PathBuf::from("/ws/lib")];
+        let args = [
+            file_arg("input"),
+            file_arg("include"),
+            PathArg {
+                id: "tool".into(),
+                executable: true,
+            },
+        ];
+        let resolved = resolve(
+            &args,
+            &roots,
+            false,
+            serde_json::json!({
+                "input": "src/main.rs",
+                "include": ["a", "/abs/b"],
+                "tool": "cargo",
+                "other": "x",
+            }),
+        );
+        assert_eq!(
+            resolved,
+            Ok(serde_json::json!({
+                "input": "/ws/app/src/main.rs",
+                "include": ["/ws/app/a", "/abs/b"],
+                "tool": "cargo",
+                "other": "x",
+            }))
+        );
+        assert_eq!(
+            resolve(&args, &[], false, serde_json::json!({ "input": "a" })),
+            Ok(serde_json::json!({ "input": "a" }))
+        );
+    }
+
+    #[test]
+    fn confinement_rejects_paths_outside_roots() {
+        let roots = [PathBuf::from("/ws/app")];
+        let args = [file_arg("input")];
+        assert!(resolve(&args, &roots, true, serde_json::json!({ "input": "src/x" })).is_ok());
+        assert!(
+            resolve(
+                &args,
+                &roots,
+                true,
+                serde_json::json!({ "input": "/ws/app/x" })
+            )
+            .is_ok()
+        );
+        for outside in [
+            "/etc/passwd",
+            "../other/x",
+            "/ws/app-other",
+            "/ws/app/../lib",
+        ] {
+            let err = resolve(&args, &roots, true, serde_json::json!({ "input": outside }))
+                .expect_err(outside);
+            assert!(err.contains("outside the client's roots"), "{err}");
+        }
+        let err = resolve(&args, &[], true, serde_json::json!({ "input": "x" })).expect_err("x");
+        assert!(err.contains("no file:// roots"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn confinement_follows_symlinks_before_parent_components() {
+        let base = std::env::temp_dir().join(format!("clap-mcp-roots-{}", std::process::id()));
+        let root = base.join("ws/app");
+        let outside = base.join("elsewhere/inner");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(base.join("elsewhere/secret"), "s").unwrap();
+        let _ = std::fs::remove_file(root.join("link"));
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+        let roots = [root.clone()];
+        let args = [file_arg("input")];
+
+        // Text-wise `app/secret`, but the OS resolves it to `elsewhere/secret`.
+        let err = resolve(
+            &args,
+            &roots,
+            true,
+            serde_json::json!({ "input": "link/../secret" }),
+        )
+        .expect_err("escapes through the symlink");
+        assert!(err.contains("outside the client's roots"), "{err}");
+
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        let resolved = resolve(
+            &args,
+            &roots,
+            true,
+            serde_json::json!({ "input": "sub/../new.txt" }),
+        )
+        .expect("stays inside the root");
+        let checked = root.canonicalize().unwrap().join("new.txt");
+        assert_eq!(resolved["input"], checked.to_string_lossy().as_ref());
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
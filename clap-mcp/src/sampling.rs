@@ -0,0 +1,188 @@
+//! MCP sampling (`sampling/createMessage`): ask the connected client's model for a completion
+//! from tool code instead of shipping a model integration.
+//!
+//! During an in-process `tools/call` whose client declared the `sampling` capability,
+//! [`ClapMcpSampler::current`](crate::sampling::ClapMcpSampler::current) returns a sampler for that call. [`create_message`](crate::sampling::create_message) wraps it for
+//! code shared with the normal CLI: outside MCP (or when the client cannot sample) it calls the
+//! hook registered with [`set_fallback`](crate::sampling::set_fallback), so the same `run` works in both modes.
+//!
+//! Requests are sent from the server task handling the `tools/call`, so they stay associated
+//! with it (SEP-2260) even when the tool body runs on another thread or runtime — e.g. inside
+//! [`crate::run_async_tool`]. Sync tools that sample need a multi-thread server runtime.
+//! Subprocess tools cannot sample. Sampling is deprecated by SEP-2577 alongside logging.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rmcp::model::{CreateMessageRequestParams, SamplingMessage};
+//!
+//! async fn summarize(text: String) -> Result<String, clap_mcp::ClapMcpError> {
+//!     let params = CreateMessageRequestParams::new(
+//!         vec![SamplingMessage::user_text(format!("Summarize:\n{text}"))],
+//!         400,
+//!     );
+//!     let result = clap_mcp::sampling::create_message(params).await?;
+//!     Ok(result.message.content.first().and_then(|c| c.as_text()).map(|t| t.text.clone()).unwrap_or_default())
+//! }
+//! ```
+
+// Sampling types remain functional in rmcp 3.x but are deprecated by SEP-2577.
+#![allow(deprecated)]
+
+use crate::{ClapMcpError, call_context::CallHandles};
+use rmcp::{
+    Peer,
+    model::{CallToolResult, CreateMessageRequestParams, CreateMessageResult},
+    service::RoleServer,
+    task_manager::{TaskManager, TaskOptions},
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, RwLock},
+};
+use tokio::sync::{mpsc, oneshot};
+
+/// Future returned by a sampling fallback registered with [`set_fallback`].
+pub type SamplingFuture =
+    Pin<Box<dyn Future<Output = Result<CreateMessageResult, ClapMcpError>> + Send>>;
+
+type SamplingFallback = Arc<dyn Fn(CreateMessageRequestParams) -> SamplingFuture + Send + Sync>;
+
+static FALLBACK: RwLock<Option<SamplingFallback>> = RwLock::new(None);
+
+type SamplingRequest = (
+    CreateMessageRequestParams,
+    oneshot::Sender<Result<CreateMessageResult, rmcp::ServiceError>>,
+);
+
+/// Sends `sampling/createMessage` to the client of the current tool call. Cloning shares the
+/// call; a sampler kept after the call finishes returns [`ClapMcpError::SamplingUnavailable`].
+#[derive(Debug, Clone)]
+pub struct ClapMcpSampler {
+    tx: mpsc::UnboundedSender<SamplingRequest>,
+}
+
+/// Keeps the call's sampling requests flowing; dropping it when the call ends stops them.
+pub(crate) struct SamplingPump {
+    _done: oneshot::Sender<()>,
+}
+
+impl ClapMcpSampler {
+    /// The sampler for the current in-process tool call, if its client supports sampling.
+    pub fn current() -> Option<Self> {
+        CallHandles::current().and_then(|handles| handles.sampler)
+    }
+
+    /// Ask the client's model for a completion.
+    pub async fn create_message(
+        &self,
+        params: CreateMessageRequestParams,
+    ) -> Result<CreateMessageResult, ClapMcpError> {
+        let finished =
+            || ClapMcpError::SamplingUnavailable("the tool call has already finished".into());
+        let (reply, response) = oneshot::channel();
+        self.tx.send((params, reply)).map_err(|_| finished())?;
+        Ok(response.await.map_err(|_| finished())??)
+    }
+
+    /// Start forwarding requests for one tool call when `peer` declared sampling.
+    pub(crate) fn start(peer: &Peer<RoleServer>) -> Option<(Self, SamplingPump)> {
+        let supported = peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.sampling.is_some());
+        if !supported {
+            return None;
+        }
+        let (tx, mut requests) = mpsc::unbounded_channel::<SamplingRequest>();
+        let (done, mut finished) = oneshot::channel::<()>();
+        let peer = peer.clone();
+        // A private TaskManager (never listed to clients) spawns the forwarder because it
+        // carries the originating `tools/call` into the new task; 2026-07-28 clients reject
+        // sampling requests that are not associated with one.
+        TaskManager::new().spawn(TaskOptions::new(), move |_| {
+            Box::pin(async move {
+                loop {
+                    tokio::select! {
+                        _ = &mut finished => break,
+                        request = requests.recv() => match request {
+                            Some((params, reply)) => {
+                                let _ = reply.send(peer.create_message(params).await);
+                            }
+                            None => break,
+                        },
+                    }
+                }
+                Ok(CallToolResult::success(Vec::new()))
+            })
+        });
+        Some((Self { tx }, SamplingPump { _done: done }))
+    }
+}
+
+/// Ask for a completion from the current tool call's client, or from the [`set_fallback`] hook
+/// when there is no sampler (normal CLI runs, subprocess tools, clients without sampling).
+pub async fn create_message(
+    params: CreateMessageRequestParams,
+) -> Result<CreateMessageResult, ClapMcpError> {
+    if let Some(sampler) = ClapMcpSampler::current() {
+        return sampler.create_message(params).await;
+    }
+    let fallback = FALLBACK.read().unwrap_or_else(|e| e.into_inner()).clone();
+    match fallback {
+        Some(fallback) => fallback(params).await,
+        None => Err(ClapMcpError::SamplingUnavailable(
+            "not in an MCP tool call whose client supports sampling, and no fallback is set".into(),
+        )),
+    }
+}
+
+/// Register the process-wide hook [`create_message`] uses when no client can sample, e.g. a
+/// direct model API call for CLI runs. Replaces any previous hook.
+pub fn set_fallback<F, Fut>(fallback: F)
+where
+    F: Fn(CreateMessageRequestParams) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<CreateMessageResult, ClapMcpError>> + Send + 'static,
+{
+    let fallback: SamplingFallback = Arc::new(move |params| Box::pin(fallback(params)));
+    *FALLBACK.write().unwrap_or_else(|e| e.into_inner()) = Some(fallback);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::SamplingMessage;
+
+    #[tokio::test]
+    async fn create_message_uses_fallback_outside_mcp() {
+        let params = CreateMessageRequestParams::new(vec![SamplingMessage::user_text("hi")], 10);
+        assert!(matches!(
+            create_message(params.clone()).await,
+            Err(ClapMcpError::SamplingUnavailable(_))
+        ));
+
+        set_fallback(|params: CreateMessageRequestParams| async move {
+            let prompt = params.messages[0]
+                .content
+                .first()
+                .and_then(|c| c.as_text())
+                .map(|t| t.text.clone())
+                .unwrap_or_default();
+            Ok(CreateMessageResult::new(
+                SamplingMessage::assistant_text(format!("echo: {prompt}")),
+                "local".into(),
+            ))
+        });
+        let result = create_message(params).await.expect("fallback result");
+        assert_eq!(result.model, "local");
+        assert_eq!(
+            result
+                .message
+                .content
+                .first()
+                .and_then(|c| c.as_text())
+                .map(|t| t.text.as_str()),
+            Some("echo: hi")
+        );
+    }
+}
@@ -3,10 +3,12 @@
 #[cfg(feature = "http")]
 use crate::http;
 use crate::{
-    ClapMcpConfig, ClapMcpConfigProvider, ClapMcpError, ClapMcpSchemaMetadata,
-    ClapMcpSchemaMetadataProvider, ClapMcpServeOptions, ClapMcpToolExecutor,
-    ClapMcpToolExecutorWithState, InProcessToolHandler, McpListen, build_mcp_blocking_runtime,
-    prepare_derive_mcp_serve, prepare_derive_mcp_serve_with_state, server,
+    AsyncInProcessToolHandler, ClapMcpAsyncToolExecutor, ClapMcpConfig, ClapMcpConfigProvider,
+    ClapMcpError, ClapMcpRegistry, ClapMcpSchemaMetadata, ClapMcpSchemaMetadataProvider,
+    ClapMcpServeOptions, ClapMcpToolExecutor, ClapMcpToolExecutorWithState, InProcessToolHandler,
+    McpListen, ResourceUpdateNotifier, ToolHandler, build_mcp_blocking_runtime,
+    prepare_derive_mcp_serve, prepare_derive_mcp_serve_async, prepare_derive_mcp_serve_with_state,
+    server,
 };
 use clap::CommandFactory;
 use std::{path::PathBuf, pin::Pin, sync::Arc};
@@ -34,7 +36,7 @@ pub struct ServeMcp {
     schema_json: String,
     executable_path: Option<PathBuf>,
     config: ClapMcpConfig,
-    in_process_handler: Option<InProcessToolHandler>,
+    in_process_handler: Option<ToolHandler>,
     serve_options: ClapMcpServeOptions,
     metadata: ClapMcpSchemaMetadata,
     stdio_io: McpStdioIo,
@@ -42,8 +44,36 @@ pub struct ServeMcp {
 
 impl ServeMcp {
     /// Run MCP on the caller's tokio runtime (stdio or HTTP).
+    ///
+    /// With [`ClapMcpServeOptions::state_persistence`] set, session state is also saved when the
+    /// transport closes or the process receives SIGINT / SIGTERM.
+    ///
+    /// Under [`ClapMcpPoisonPolicy::Exit`](crate::ClapMcpPoisonPolicy::Exit), a caught panic stops
+    /// the transport; once state is saved and the Unix socket removed, the process exits with
+    /// [`CLAP_MCP_POISONED_EXIT_CODE`](crate::CLAP_MCP_POISONED_EXIT_CODE).
     pub async fn serve(self) -> Result<(), ClapMcpError> {
         validate_embedder_runtime(&self.config)?;
+        let persisted = self.serve_options.state_persistence.clone();
+        let serve = async {
+            tokio::select! {
+                served = self.serve_transport() => served,
+                () = server::poison_exit() => Ok(()),
+            }
+        };
+        let result = match persisted {
+            Some(persisted) => persisted.run_with_snapshots(serve).await,
+            None => serve.await,
+        };
+        if server::poison_exit_requested() {
+            if let Err(e) = result {
+                eprintln!("MCP server error: {e}");
+            }
+            std::process::exit(crate::CLAP_MCP_POISONED_EXIT_CODE);
+        }
+        result
+    }
+
+    async fn serve_transport(self) -> Result<(), ClapMcpError> {
         match self.listen {
             McpListen::Stdio => {
                 server::serve_schema_json_over_stdio(
@@ -70,6 +100,19 @@ impl ServeMcp {
                 )
                 .await
             }
+            #[cfg(all(unix, feature = "http"))]
+            McpListen::Unix(path) => {
+                http::serve_schema_json_over_unix(
+                    path,
+                    self.schema_json,
+                    self.executable_path,
+                    self.config,
+                    self.in_process_handler,
+                    self.serve_options,
+                    &self.metadata,
+                )
+                .await
+            }
         }
     }
 
@@ -121,7 +164,7 @@ pub struct ServeMcpBuilder {
     metadata: Option<ClapMcpSchemaMetadata>,
     serve_options: ClapMcpServeOptions,
     executable_path: Option<PathBuf>,
-    in_process_handler: Option<InProcessToolHandler>,
+    in_process_handler: Option<ToolHandler>,
     stdio_io: McpStdioIo,
 }
 
@@ -191,6 +234,32 @@ impl ServeMcpBuilder {
         }
     }
 
+    /// Like [`Self::for_cli`], for [`ClapMcpAsyncToolExecutor`] types
+    /// (`#[clap_mcp_output_from_async = "run"]`): in-process tool calls await `run` on the
+    /// runtime that drives [`Self::serve`].
+    pub fn for_cli_async<T>(listen: McpListen) -> Self
+    where
+        T: ClapMcpAsyncToolExecutor
+            + ClapMcpSchemaMetadataProvider
+            + ClapMcpConfigProvider
+            + CommandFactory
+            + clap::FromArgMatches
+            + 'static,
+    {
+        let config = T::clap_mcp_config();
+        let prepared = prepare_derive_mcp_serve_async::<T>(&config);
+        Self {
+            listen: Some(listen),
+            schema_json: Some(prepared.schema_json),
+            config: Some(config),
+            metadata: Some(prepared.metadata),
+            serve_options: ClapMcpServeOptions::default(),
+            executable_path: prepared.executable_path,
+            in_process_handler: prepared.in_process_handler,
+            stdio_io: McpStdioIo::default(),
+        }
+    }
+
     /// MCP transport (stdio or HTTP).
     pub fn listen(mut self, listen: McpListen) -> Self {
         self.listen = Some(listen);
@@ -221,6 +290,14 @@ impl ServeMcpBuilder {
         self
     }
 
+    /// Lowest log level forwarded before the client calls `logging/setLevel`; see
+    /// [`ClapMcpServeOptions::default_log_level`].
+    #[allow(deprecated)]
+    pub fn default_log_level(mut self, level: rmcp::model::LoggingLevel) -> Self {
+        self.serve_options.default_log_level = Some(level);
+        self
+    }
+
     /// SEP-2549 cache hints for list results (and `resources/read` when no read override).
     pub fn cache_hints(mut self, cache_hints: crate::CacheHints) -> Self {
         self.serve_options.cache_hints = cache_hints;
@@ -236,6 +313,75 @@ impl ServeMcpBuilder {
         self
     }
 
+    /// Maximum items per list response; see [`ClapMcpServeOptions::page_size`].
+    pub fn page_size(mut self, page_size: Option<usize>) -> Self {
+        self.serve_options.page_size = page_size;
+        self
+    }
+
+    /// Reject path args outside the client's roots; see
+    /// [`ClapMcpServeOptions::confine_paths_to_roots`].
+    pub fn confine_paths_to_roots(mut self, confine: bool) -> Self {
+        self.serve_options.confine_paths_to_roots = confine;
+        self
+    }
+
+    /// Overrides for the reported `serverInfo`; see [`ClapMcpServeOptions::server_identity`].
+    pub fn server_identity(mut self, server_identity: crate::ServerIdentity) -> Self {
+        self.serve_options.server_identity = server_identity;
+        self
+    }
+
+    /// Server `instructions`; see [`ClapMcpServeOptions::instructions`].
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.serve_options.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Task result lifetime, retention, and poll interval; see
+    /// [`ClapMcpServeOptions::task_retention`].
+    pub fn task_retention(mut self, task_retention: crate::TaskRetention) -> Self {
+        self.serve_options.task_retention = task_retention;
+        self
+    }
+
+    /// Require a bearer token on HTTP requests; see [`ClapMcpServeOptions::http_auth`].
+    #[cfg(feature = "http")]
+    pub fn http_auth(mut self, http_auth: crate::HttpAuth) -> Self {
+        self.serve_options.http_auth = Some(http_auth);
+        self
+    }
+
+    /// Endpoint route, allowed hosts, and CORS origins; see [`ClapMcpServeOptions::http_endpoint`].
+    #[cfg(feature = "http")]
+    pub fn http_endpoint(mut self, http_endpoint: crate::HttpEndpoint) -> Self {
+        self.serve_options.http_endpoint = Some(http_endpoint);
+        self
+    }
+
+    /// Serve HTTP over TLS; see [`ClapMcpServeOptions::http_tls`].
+    #[cfg(feature = "http-tls")]
+    pub fn http_tls(mut self, http_tls: crate::HttpTls) -> Self {
+        self.serve_options.http_tls = Some(http_tls);
+        self
+    }
+
+    /// Permission bits for the Unix socket file; see [`ClapMcpServeOptions::unix_socket_mode`].
+    #[cfg(all(unix, feature = "http"))]
+    pub fn unix_socket_mode(mut self, mode: u32) -> Self {
+        self.serve_options.unix_socket_mode = Some(mode);
+        self
+    }
+
+    /// Snapshot shared session state to a file and expose it as `clap://state`.
+    ///
+    /// Bind the same `Arc` passed to [`Self::for_cli_with_state`] with
+    /// [`crate::state::StatePersistence::bind`].
+    pub fn state_persistence(mut self, state_persistence: crate::state::PersistedState) -> Self {
+        self.serve_options.state_persistence = Some(state_persistence);
+        self
+    }
+
     /// Subprocess executable for tool calls when not in-process.
     pub fn executable_path(mut self, executable_path: Option<PathBuf>) -> Self {
         self.executable_path = executable_path;
@@ -244,10 +390,41 @@ impl ServeMcpBuilder {
 
     /// In-process tool handler when `reinvocation_safe`.
     pub fn in_process_handler(mut self, in_process_handler: Option<InProcessToolHandler>) -> Self {
+        self.in_process_handler = in_process_handler.map(ToolHandler::Sync);
+        self
+    }
+
+    /// Async in-process tool handler when `reinvocation_safe`; replaces
+    /// [`Self::in_process_handler`].
+    pub fn async_in_process_handler(
+        mut self,
+        in_process_handler: Option<AsyncInProcessToolHandler>,
+    ) -> Self {
+        self.in_process_handler = in_process_handler.map(ToolHandler::Async);
+        self
+    }
+
+    pub(crate) fn tool_handler(mut self, in_process_handler: Option<ToolHandler>) -> Self {
         self.in_process_handler = in_process_handler;
         self
     }
 
+    /// Handle for sending `notifications/resources/updated` to subscribed sessions.
+    ///
+    /// Shares [`ClapMcpServeOptions::resource_updates`]; call after [`Self::serve_options`],
+    /// which replaces it.
+    pub fn resource_update_notifier(&self) -> ResourceUpdateNotifier {
+        self.serve_options.resource_updates.clone()
+    }
+
+    /// Handle for enabling, disabling, and replacing tools, prompts, and resources at runtime.
+    ///
+    /// Shares [`ClapMcpServeOptions::registry`]; call after [`Self::serve_options`], which
+    /// replaces it.
+    pub fn registry(&self) -> ClapMcpRegistry {
+        self.serve_options.registry.clone()
+    }
+
     /// Use custom async I/O for stdio MCP instead of process stdin/stdout.
     ///
     /// Only valid with [`McpListen::Stdio`]. Default is process stdio. Use when
@@ -268,8 +445,7 @@ impl ServeMcpBuilder {
     pub fn build(self) -> Result<ServeMcp, ClapMcpError> {
         let listen = self.listen.ok_or_else(|| missing_field("listen"))?;
         #[cfg(feature = "http")]
-        if matches!(listen, McpListen::Http(_))
-            && matches!(self.stdio_io, McpStdioIo::Custom { .. })
+        if !matches!(listen, McpListen::Stdio) && matches!(self.stdio_io, McpStdioIo::Custom { .. })
         {
             return Err(ClapMcpError::InvalidConfig(
                 "ServeMcpBuilder::stdio_io is only valid with McpListen::Stdio".into(),
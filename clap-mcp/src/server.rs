@@ -4,45 +4,131 @@
 #![allow(deprecated)]
 
 use crate::{
-    CacheHints, ClapMcpConfig, ClapMcpError, ClapMcpSchemaMetadata, ClapMcpSerializeScope,
-    ClapMcpServeOptions, ClapMcpToolError, ClapMcpToolOutput, InProcessToolHandler,
-    LOG_INTERPRETATION_INSTRUCTIONS, LOGGING_GUIDE_CONTENT, MCP_RESOURCE_URI_SCHEMA,
-    PROMPT_LOGGING_GUIDE, content,
+    CLAP_MCP_POISONED_EXIT_CODE, CacheHints, ClapMcpConfig, ClapMcpError, ClapMcpPoisonPolicy,
+    ClapMcpRegistry, ClapMcpSchemaMetadata, ClapMcpSerializeScope, ClapMcpServeOptions,
+    ClapMcpToolError, ClapMcpToolOutput, ClapSchema, LOG_INTERPRETATION_INSTRUCTIONS,
+    LOGGING_GUIDE_CONTENT, MCP_RESOURCE_URI_SCHEMA, MCP_RESOURCE_URI_STATE,
+    MCP_RESOURCE_URI_STATUS, MCP_RESOURCE_URI_TASKS, PROMPT_LOGGING_GUIDE, ResourceUpdateNotifier,
+    TaskRetention, ToolHandler,
+    call_context::CallHandles,
+    completion::ToolArgCompletion,
+    content,
     logging::LoggingMessageNotificationParams,
+    pagination::paginate,
     protocol::{PROTOCOL_VERSION_STABLE, SUPPORTED_PROTOCOL_VERSIONS, negotiate_protocol_version},
+    resource_updates::SessionSubscriptions,
+    roots::{PathArg, SessionRoots},
+    sampling::ClapMcpSampler,
     serialize_lock_key,
+    tasks::{ClapMcpTask, TaskIndex},
 };
 use rmcp::{
     ErrorData as McpError, Peer, ServerHandler,
     model::{
-        CallToolRequestParams, CallToolResponse, CallToolResult, CancelTaskParams, ContentBlock,
-        CreateTaskResult, GetPromptRequestParams, GetPromptResponse, GetPromptResult,
-        GetTaskParams, GetTaskResult, Implementation, InitializeRequestParams, ListPromptsResult,
-        ListResourceTemplatesResult, ListResourcesResult, ListToolsResult, LoggingLevel,
-        LoggingMessageNotification, LoggingMessageNotificationParam, NotificationMetaObject,
-        PaginatedRequestParams, PromptMessage, ProtocolVersion, ReadResourceRequestParams,
-        ReadResourceResponse, ReadResourceResult, Resource, ResourceContents, Role,
-        ServerCapabilities, SetLevelRequestParams, SubscribeRequestParams, Tool,
+        CallToolRequestParams, CallToolResponse, CallToolResult, CancelTaskParams,
+        CompleteRequestParams, CompleteResult, ContentBlock, CreateTaskResult,
+        GetPromptRequestParams, GetPromptResponse, GetPromptResult, GetTaskParams, GetTaskResult,
+        Implementation, InitializeRequestParams, ListPromptsResult, ListResourceTemplatesResult,
+        ListResourcesResult, ListToolsResult, LoggingLevel, LoggingMessageNotification,
+        LoggingMessageNotificationParam, NotificationMetaObject, PaginatedRequestParams,
+        PromptMessage, ProtocolVersion, ReadResourceRequestParams, ReadResourceResponse,
+        ReadResourceResult, Reference, Resource, ResourceContents, Role, ServerCapabilities,
+        SetLevelRequestParams, SubscribeRequestParams, SubscriptionFilter, Tool,
         UnsubscribeRequestParams, UpdateTaskParams,
     },
-    service::{RequestContext, RoleServer, serve_directly},
+    service::{
+        NotificationContext, RequestContext, RoleServer, SubscriptionContext, serve_directly,
+    },
     task_manager::{TaskExit, TaskManager, TaskOptions},
 };
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
     path::PathBuf,
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
-/// Shared handler state: tool execution, resources, prompts, and task policy.
-pub(crate) struct ServeHandlerInner {
+/// Tools derived from the clap schema; swapped by [`ClapMcpRegistry::replace_schema`].
+pub(crate) struct ToolSet {
     pub schema_json: String,
+    /// Clap tools followed by [`ClapMcpServeOptions::custom_tools`].
     pub tools: Vec<Tool>,
+    /// Tools routed to `executable_path` even though `in_process_handler` is set
+    /// (`#[clap_mcp(subprocess)]`, or the global mode when it is subprocess).
+    pub subprocess_tools: HashSet<String>,
+    /// `completion/complete` sources per clap tool argument.
+    pub tool_arg_completions: HashMap<String, HashMap<String, ToolArgCompletion>>,
+    /// Path-valued arguments per clap tool, resolved against the client's roots.
+    pub path_args: HashMap<String, Vec<PathArg>>,
+}
+
+impl ToolSet {
+    fn new(
+        schema_json: String,
+        schema: &ClapSchema,
+        clap_tools: Vec<Tool>,
+        custom_tools: &[Tool],
+        config: &ClapMcpConfig,
+        metadata: &ClapMcpSchemaMetadata,
+    ) -> Self {
+        let subprocess_tools = clap_tools
+            .iter()
+            .filter(|t| !crate::tool_reinvocation_safe(&t.name, config, metadata))
+            .map(|t| t.name.to_string())
+            .collect();
+        let tool_arg_completions =
+            crate::completion::tool_arg_completions(schema, &clap_tools, metadata);
+        let path_args = crate::roots::tool_path_args(schema, &clap_tools);
+        let mut tools = clap_tools;
+        tools.extend(custom_tools.iter().cloned());
+        Self {
+            schema_json,
+            tools,
+            subprocess_tools,
+            tool_arg_completions,
+            path_args,
+        }
+    }
+
+    /// Subprocess tools never fall back to the in-process handler, so serving them in process
+    /// needs an executable to launch.
+    fn check_subprocess_tools(
+        &self,
+        executable_path: Option<&std::path::Path>,
+        has_in_process_handler: bool,
+    ) -> Result<(), ClapMcpError> {
+        if executable_path.is_some() || !has_in_process_handler {
+            return Ok(());
+        }
+        let mut names: Vec<&str> = self.subprocess_tools.iter().map(String::as_str).collect();
+        if names.is_empty() {
+            return Ok(());
+        }
+        names.sort_unstable();
+        Err(ClapMcpError::InvalidConfig(format!(
+            "subprocess tools ({}) need an executable path to run isolated, but none is set",
+            names.join(", ")
+        )))
+    }
+}
+
+/// Shared handler state: tool execution, resources, prompts, and task policy.
+pub(crate) struct ServeHandlerInner {
+    pub tool_set: RwLock<Arc<ToolSet>>,
+    /// Inputs for rebuilding [`Self::tool_set`] from a replacement schema.
+    pub config: ClapMcpConfig,
+    pub metadata: ClapMcpSchemaMetadata,
+    pub custom_tools: Vec<Tool>,
     pub executable_path: Option<PathBuf>,
-    pub in_process_handler: Option<InProcessToolHandler>,
+    pub in_process_handler: Option<ToolHandler>,
     pub root_name: String,
     pub catch_in_process_panics: bool,
+    pub poison_policy: ClapMcpPoisonPolicy,
+    /// Tools whose panics were caught; drives [`ClapMcpPoisonPolicy`] and `clap://status`.
+    pub poisoned_tools: Mutex<Vec<PoisonedTool>>,
     pub custom_resources: Vec<content::CustomResource>,
     pub custom_resource_templates: Vec<content::CustomResourceTemplate>,
     pub custom_prompts: Vec<content::CustomPrompt>,
@@ -50,16 +136,190 @@ pub(crate) struct ServeHandlerInner {
     pub custom_tool_names: HashSet<String>,
     pub cache_hints: CacheHints,
     pub resource_read_cache_hints: Option<CacheHints>,
+    /// Items per list page; `None` lists everything in one response.
+    pub page_size: Option<usize>,
+    /// Reject path args outside the client's roots.
+    pub confine_paths_to_roots: bool,
+    /// Session state exposed read-only as `clap://state` when persistence is configured.
+    pub state_persistence: Option<crate::state::PersistedState>,
     pub logging_enabled: bool,
+    /// `serverInfo` from [`crate::ServerIdentity`] and the root command.
+    pub server_info: Implementation,
+    /// Custom instructions merged with [`LOG_INTERPRETATION_INSTRUCTIONS`].
+    pub instructions: Option<String>,
+    /// Level for sessions whose client has not called `logging/setLevel`.
+    pub default_log_level: LoggingLevel,
     pub task_augmented_tools: bool,
     pub task_tool_filter: Option<HashSet<String>>,
+    /// TTL and poll interval for new tasks; `max_retained` lives in the server's task index.
+    pub task_retention: TaskRetention,
     pub serialize_tools: HashMap<String, ClapMcpSerializeScope>,
     pub serialize_topic_args: HashMap<String, HashMap<String, crate::SerializeTopicSegmentFn>>,
+    pub completion_provider: Option<Arc<dyn content::CompletionProvider>>,
+    /// Installed as [`ResourceUpdateNotifier::current`] while in-process tools run.
+    pub resource_updates: ResourceUpdateNotifier,
+    /// Entries hidden at runtime; installed as [`ClapMcpRegistry::current`].
+    pub registry: ClapMcpRegistry,
+}
+
+/// A tool call that panicked under `catch_in_process_panics`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct PoisonedTool {
+    pub tool: String,
+    pub message: String,
 }
 
 impl ServeHandlerInner {
+    pub fn tool_set(&self) -> Arc<ToolSet> {
+        self.tool_set
+            .read()
+            .map(|set| set.clone())
+            .unwrap_or_else(|e| e.into_inner().clone())
+    }
+
+    /// Re-derive tools from `schema` (see [`ClapMcpRegistry::replace_schema`]).
+    pub fn replace_schema(&self, schema: &ClapSchema) -> Result<(), ClapMcpError> {
+        let schema_json = serde_json::to_string(schema)?;
+        let clap_tools =
+            crate::tools_from_schema_with_metadata(schema, &self.config, &self.metadata);
+        let tool_set = ToolSet::new(
+            schema_json,
+            schema,
+            clap_tools,
+            &self.custom_tools,
+            &self.config,
+            &self.metadata,
+        );
+        tool_set.check_subprocess_tools(
+            self.executable_path.as_deref(),
+            self.in_process_handler.is_some(),
+        )?;
+        *self.tool_set.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(tool_set);
+        Ok(())
+    }
+
+    /// Tools advertised in `tools/list`: drops tools disabled in [`Self::registry`] and, under
+    /// [`ClapMcpPoisonPolicy::DisableTool`], tools that panicked.
+    pub fn listed_tools(&self) -> Vec<Tool> {
+        let poisoned = if self.poison_policy == ClapMcpPoisonPolicy::DisableTool {
+            self.poisoned_tools
+                .lock()
+                .map(|g| g.clone())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        self.tool_set()
+            .tools
+            .iter()
+            .filter(|t| {
+                self.registry.is_tool_enabled(&t.name) && !poisoned.iter().any(|p| p.tool == t.name)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Handles installed for in-process tool calls ([`CallHandles::current`]).
+    fn call_handles(
+        &self,
+        context: &RequestContext<RoleServer>,
+        sampler: Option<ClapMcpSampler>,
+        task: Option<ClapMcpTask>,
+    ) -> CallHandles {
+        #[cfg(not(feature = "http"))]
+        let _ = context;
+        CallHandles {
+            resource_updates: self.resource_updates.clone(),
+            registry: self.registry.clone(),
+            sampler,
+            task,
+            #[cfg(feature = "http")]
+            principal: crate::HttpPrincipal::from_context(context),
+        }
+    }
+
+    /// Error result for calls refused by the poison policy, if any.
+    pub fn poisoned_call_result(&self, name: &str) -> Option<CallToolResult> {
+        let poisoned = self.poisoned_tools.lock().ok()?;
+        match self.poison_policy {
+            ClapMcpPoisonPolicy::DisableTool if poisoned.iter().any(|p| p.tool == name) => {
+                Some(CallToolResult::error(vec![ContentBlock::text(format!(
+                    "Tool '{name}' is disabled after a caught panic; restart the MCP server"
+                ))]))
+            }
+            ClapMcpPoisonPolicy::RefuseAll => poisoned.first().map(|first| {
+                CallToolResult::error(vec![ContentBlock::text(format!(
+                    "MCP server is poisoned after tool '{}' panicked; restart the MCP server",
+                    first.tool
+                ))])
+            }),
+            _ => None,
+        }
+    }
+
+    /// Record a caught panic, apply the poison policy, and return the tool error result.
+    pub async fn handle_caught_panic(
+        &self,
+        name: &str,
+        panic_payload: Box<dyn std::any::Any + Send>,
+        peer: &Peer<RoleServer>,
+    ) -> CallToolResult {
+        let result = call_tool_result_from_panic(panic_payload.as_ref());
+        let message = crate::format_panic_payload(panic_payload.as_ref());
+        let newly_poisoned = match self.poisoned_tools.lock() {
+            Ok(mut guard) => {
+                let new = !guard.iter().any(|p| p.tool == name);
+                guard.push(PoisonedTool {
+                    tool: name.to_string(),
+                    message: message.clone(),
+                });
+                new
+            }
+            Err(_) => false,
+        };
+        match self.poison_policy {
+            ClapMcpPoisonPolicy::Continue | ClapMcpPoisonPolicy::RefuseAll => {}
+            ClapMcpPoisonPolicy::DisableTool => {
+                if newly_poisoned {
+                    let _ = peer.notify_tool_list_changed().await;
+                }
+            }
+            ClapMcpPoisonPolicy::Exit => {
+                eprintln!(
+                    "clap-mcp: tool '{name}' panicked ({message}); exiting with code \
+                     {CLAP_MCP_POISONED_EXIT_CODE}"
+                );
+                request_poison_exit();
+            }
+        }
+        result
+    }
+
+    /// JSON body for the `clap://status` resource.
+    pub fn status_json(&self) -> serde_json::Value {
+        let poisoned = self
+            .poisoned_tools
+            .lock()
+            .map(|g| g.clone())
+            .unwrap_or_default();
+        serde_json::json!({
+            "poisoned": !poisoned.is_empty(),
+            "poisonPolicy": self.poison_policy,
+            "refusingCalls": self.poison_policy == ClapMcpPoisonPolicy::RefuseAll
+                && !poisoned.is_empty(),
+            "panics": poisoned,
+        })
+    }
+
+    /// Whether `name` runs through `in_process_handler` rather than `executable_path`.
+    pub(crate) fn runs_in_process(&self, name: &str) -> bool {
+        self.in_process_handler.is_some() && !self.tool_set().subprocess_tools.contains(name)
+    }
+
     pub fn allows_task_tool(&self, name: &str) -> bool {
-        if !self.task_augmented_tools {
+        if !self.task_augmented_tools
+            || !(self.runs_in_process(name) || self.executable_path.is_some())
+        {
             return false;
         }
         match &self.task_tool_filter {
@@ -68,12 +328,22 @@ impl ServeHandlerInner {
         }
     }
 
-    pub async fn call_tool(
+    /// Checks a call and collects what it still needs from the client: missing arguments, the
+    /// roots that path arguments resolve against, and the user's confirmation.
+    ///
+    /// Runs before [`with_execution_guard`], so a client that is slow to answer does not
+    /// hold the global lock, topic locks, or concurrency permits.
+    pub async fn prepare_call(
         &self,
         params: &CallToolRequestParams,
         context: &RequestContext<RoleServer>,
-    ) -> Result<CallToolResult, McpError> {
-        let tool = self.tools.iter().find(|t| t.name == params.name);
+        roots: &SessionRoots,
+    ) -> Result<PreparedCall, McpError> {
+        let tool_set = self.tool_set();
+        let tool = tool_set
+            .tools
+            .iter()
+            .find(|t| t.name == params.name && self.registry.is_tool_enabled(&t.name));
         let Some(tool) = tool else {
             return Err(McpError::invalid_params(
                 format!("unknown tool: {}", params.name),
@@ -81,32 +351,150 @@ impl ServeHandlerInner {
             ));
         };
 
-        let args_map = params.arguments.clone().unwrap_or_default();
-        validate_tool_argument_names(tool, &params.name, &args_map)?;
+        let mut args = params.arguments.clone().unwrap_or_default();
+        validate_tool_argument_names(tool, &params.name, &args)?;
+
+        if let Some(refused) = self.poisoned_call_result(&params.name) {
+            return Ok(PreparedCall::Done(refused));
+        }
 
         if self.custom_tool_names.contains(params.name.as_ref()) {
-            return Ok(CallToolResult::success(vec![ContentBlock::text(
-                "Custom tool acknowledged",
-            )]));
+            return Ok(PreparedCall::Done(CallToolResult::success(vec![
+                ContentBlock::text("Custom tool acknowledged"),
+            ])));
         }
 
-        if let Some(ref handler) = self.in_process_handler {
+        if self
+            .metadata
+            .elicit_missing_tools
+            .iter()
+            .any(|t| t == params.name.as_ref())
+            && let Ok(schema) = serde_json::from_str::<ClapSchema>(&tool_set.schema_json)
+            && let Some(refused) = crate::elicitation::elicit_missing_args(
+                &context.peer,
+                &schema,
+                &params.name,
+                &mut args,
+            )
+            .await
+        {
+            return Ok(PreparedCall::Done(refused));
+        }
+
+        // Roots are only fetched when they change the call: path args or a subprocess cwd.
+        let path_args = tool_set.path_args.get(params.name.as_ref());
+        let roots = if path_args.is_some()
+            || (!self.runs_in_process(&params.name) && self.executable_path.is_some())
+        {
+            roots.get(&context.peer).await
+        } else {
+            Vec::new()
+        };
+        if let Some(path_args) = path_args
+            && let Err(e) = crate::roots::resolve_path_args(
+                path_args,
+                &roots,
+                self.confine_paths_to_roots,
+                &mut args,
+            )
+        {
+            return Ok(PreparedCall::Done(call_tool_result_from_tool_error(
+                ClapMcpToolError::text(e),
+            )));
+        }
+
+        if let Some(template) = self.metadata.confirm_tools.get(params.name.as_ref())
+            && let Some(refused) =
+                crate::elicitation::confirm_call(&context.peer, &params.name, template, &args).await
+        {
+            return Ok(PreparedCall::Done(refused));
+        }
+
+        Ok(PreparedCall::Run { args, roots })
+    }
+
+    /// Runs a call [`prepare_call`](Self::prepare_call) accepted; callers hold the execution
+    /// guard around it.
+    pub async fn run_call(
+        &self,
+        params: &CallToolRequestParams,
+        args: &serde_json::Map<String, serde_json::Value>,
+        context: &RequestContext<RoleServer>,
+        roots: &[PathBuf],
+        task: Option<ClapMcpTask>,
+    ) -> Result<CallToolResult, McpError> {
+        // Checked again: a call queued behind the guard may follow one that panicked.
+        if let Some(refused) = self.poisoned_call_result(&params.name) {
+            return Ok(refused);
+        }
+        let tool_set = self.tool_set();
+        let args_map = args.clone();
+
+        let runs_in_process = self.runs_in_process(&params.name);
+
+        if let Some(handler) = self.in_process_handler.as_ref().filter(|_| runs_in_process) {
             let name = params.name.to_string();
             let args = args_map;
-            let result = if self.catch_in_process_panics {
-                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| handler(&name, args)))
-            } else {
-                Ok(handler(&name, args))
+            // Held until the call returns; dropping it stops forwarding sampling requests.
+            let (sampler, _sampling_pump) = ClapMcpSampler::start(&context.peer).unzip();
+            // A sync tool blocks this worker, and forwarders it spawns or wakes sit in its
+            // non-stealable LIFO slot; hand the worker off so sampling requests and task
+            // status notifications still go out.
+            let hand_off = (sampler.is_some() || task.is_some())
+                && tokio::runtime::Handle::current().runtime_flavor()
+                    == tokio::runtime::RuntimeFlavor::MultiThread;
+            let handles = self.call_handles(context, sampler, task);
+            let result = match handler {
+                ToolHandler::Sync(handler) => {
+                    let run = || {
+                        if self.catch_in_process_panics {
+                            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                                handles.scope_sync(|| handler(&name, args))
+                            }))
+                        } else {
+                            Ok(handles.scope_sync(|| handler(&name, args)))
+                        }
+                    };
+                    if hand_off {
+                        tokio::task::block_in_place(run)
+                    } else {
+                        run()
+                    }
+                }
+                ToolHandler::Async(handler) if self.catch_in_process_panics => {
+                    // Spawn so a panic surfaces as a JoinError; re-scope the task id and
+                    // call handles because task-locals do not cross `tokio::spawn`.
+                    let future = handler(&name, args);
+                    let task_id = crate::logging::current_mcp_task_id();
+                    let joined = tokio::spawn(async move {
+                        let future = handles.scope(future);
+                        match task_id {
+                            Some(id) => crate::logging::run_with_mcp_task_id(id, future).await,
+                            None => future.await,
+                        }
+                    })
+                    .await;
+                    match joined {
+                        Ok(output) => Ok(output),
+                        Err(join_err) if join_err.is_panic() => Err(join_err.into_panic()),
+                        Err(join_err) => {
+                            return Err(McpError::internal_error(join_err.to_string(), None));
+                        }
+                    }
+                }
+                ToolHandler::Async(handler) => Ok(handles.scope(handler(&name, args)).await),
             };
             return match result {
                 Ok(Ok(output)) => Ok(call_tool_result_from_output(output)),
                 Ok(Err(error)) => Ok(call_tool_result_from_tool_error(error)),
-                Err(panic_payload) => Ok(call_tool_result_from_panic(panic_payload.as_ref())),
+                Err(panic_payload) => Ok(self
+                    .handle_caught_panic(&name, panic_payload, &context.peer)
+                    .await),
             };
         }
 
         if let Some(ref exe) = self.executable_path {
-            let schema: crate::ClapSchema = match serde_json::from_str(&self.schema_json) {
+            let schema: crate::ClapSchema = match serde_json::from_str(&tool_set.schema_json) {
                 Ok(schema) => schema,
                 Err(_) => return Ok(schema_parse_failure_result()),
             };
@@ -115,12 +503,40 @@ impl ServeHandlerInner {
             }
             let mut cmd =
                 build_execution_command(exe, &schema, &self.root_name, &params.name, &args_map);
-            match cmd.output() {
+            if let Some(primary) = roots.first().filter(|root| root.is_dir()) {
+                cmd.current_dir(primary);
+            }
+            // Tools never see the server's token, nor a principal the caller does not have.
+            #[cfg(feature = "http")]
+            {
+                cmd.env_remove(crate::MCP_HTTP_TOKEN_ENV)
+                    .env_remove(crate::MCP_HTTP_TOKEN_FILE_ENV);
+                match crate::HttpPrincipal::from_context(context) {
+                    Some(principal) => cmd.env(crate::MCP_HTTP_PRINCIPAL_ENV, principal.name()),
+                    None => cmd.env_remove(crate::MCP_HTTP_PRINCIPAL_ENV),
+                };
+            }
+            let output = match &task {
+                Some(task) => match run_subprocess_task(cmd, task).await {
+                    Some(output) => output,
+                    None => {
+                        task.mark_stopped();
+                        return Ok(CallToolResult::error(vec![ContentBlock::text(
+                            "Tool process killed: task cancelled",
+                        )]));
+                    }
+                },
+                None => cmd.output(),
+            };
+            match output {
                 Ok(output) => {
+                    // The calling session's level: `call_tool` made it the log target.
                     if let Some(log_params) = subprocess_stderr_log_params(
                         &params.name,
                         &String::from_utf8_lossy(&output.stderr),
-                    ) {
+                    )
+                    .filter(|p| crate::logging::is_level_forwarded(p.level))
+                    {
                         let _ = notify_log(&context.peer, log_params).await;
                     }
                     return Ok(call_tool_result_from_subprocess_output(&output));
@@ -133,6 +549,43 @@ impl ServeHandlerInner {
     }
 }
 
+static POISON_EXIT_REQUESTED: AtomicBool = AtomicBool::new(false);
+static POISON_EXIT: tokio::sync::Notify = tokio::sync::Notify::const_new();
+
+/// Asks [`ServeMcp::serve`](crate::ServeMcp::serve) to stop serving and exit with
+/// [`CLAP_MCP_POISONED_EXIT_CODE`] once state is saved and the transport is cleaned up.
+fn request_poison_exit() {
+    POISON_EXIT_REQUESTED.store(true, Ordering::SeqCst);
+    POISON_EXIT.notify_waiters();
+}
+
+/// Whether a tool panicked under [`ClapMcpPoisonPolicy::Exit`].
+pub(crate) fn poison_exit_requested() -> bool {
+    POISON_EXIT_REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Resolves once [`poison_exit_requested`] is true.
+pub(crate) async fn poison_exit() {
+    let notified = POISON_EXIT.notified();
+    tokio::pin!(notified);
+    notified.as_mut().enable();
+    if poison_exit_requested() {
+        return;
+    }
+    notified.await;
+}
+
+/// Outcome of [`ServeHandlerInner::prepare_call`].
+pub(crate) enum PreparedCall {
+    /// Run the tool with these arguments; `roots` are the client roots, primary first.
+    Run {
+        args: serde_json::Map<String, serde_json::Value>,
+        roots: Vec<PathBuf>,
+    },
+    /// The call already finished (refused, declined, or acknowledged) without running.
+    Done(CallToolResult),
+}
+
 /// Per-topic mutexes for topical serialization when `parallel_safe` is true.
 struct TopicLockRegistry {
     locks: tokio::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
@@ -156,24 +609,92 @@ impl TopicLockRegistry {
     }
 }
 
+/// Semaphores for `max_concurrency` caps (global and per tool) with an optional queue timeout.
+struct ConcurrencyLimits {
+    global: Option<(usize, Arc<tokio::sync::Semaphore>)>,
+    per_tool: HashMap<String, (usize, Arc<tokio::sync::Semaphore>)>,
+    queue_timeout: Option<std::time::Duration>,
+}
+
+/// Permits held for the duration of one tool call.
+struct ConcurrencyPermits {
+    _global: Option<tokio::sync::OwnedSemaphorePermit>,
+    _tool: Option<tokio::sync::OwnedSemaphorePermit>,
+}
+
+impl ConcurrencyLimits {
+    fn new(config: &ClapMcpConfig, metadata: &ClapMcpSchemaMetadata) -> Self {
+        let semaphore = |n: usize| (n, Arc::new(tokio::sync::Semaphore::new(n)));
+        Self {
+            global: config.max_concurrency.map(semaphore),
+            per_tool: metadata
+                .tool_max_concurrency
+                .iter()
+                .map(|(name, n)| (name.clone(), semaphore(*n)))
+                .collect(),
+            queue_timeout: config.queue_timeout,
+        }
+    }
+
+    /// Wait for the global and per-tool slots; on queue timeout, return a "server busy"
+    /// tool error.
+    async fn acquire(&self, tool_name: &str) -> Result<ConcurrencyPermits, CallToolResult> {
+        let tool = self.per_tool.get(tool_name);
+        // Per-tool slot first so calls queued on a busy tool do not hold global slots.
+        let acquire = async {
+            let tool = match tool {
+                Some((_, sem)) => sem.clone().acquire_owned().await.ok(),
+                None => None,
+            };
+            let global = match &self.global {
+                Some((_, sem)) => sem.clone().acquire_owned().await.ok(),
+                None => None,
+            };
+            ConcurrencyPermits {
+                _global: global,
+                _tool: tool,
+            }
+        };
+        let Some(timeout) = self.queue_timeout else {
+            return Ok(acquire.await);
+        };
+        tokio::time::timeout(timeout, acquire).await.map_err(|_| {
+            let limit = match (tool, &self.global) {
+                (Some((n, _)), _) => format!("max_concurrency = {n} for '{tool_name}'"),
+                (None, Some((n, _))) => format!("global max_concurrency = {n}"),
+                (None, None) => "max_concurrency".to_string(),
+            };
+            CallToolResult::error(vec![ContentBlock::text(format!(
+                "Server busy: tool '{tool_name}' waited more than {}ms for a free slot ({limit})",
+                timeout.as_millis()
+            ))])
+        })
+    }
+}
+
 struct ExecutionGuardContext<'a> {
     parallel_safe: bool,
     global_lock: &'a Option<Arc<tokio::sync::Mutex<()>>>,
     topic_registry: &'a TopicLockRegistry,
+    concurrency_limits: &'a ConcurrencyLimits,
     serialize_tools: &'a HashMap<String, ClapMcpSerializeScope>,
     serialize_topic_args: &'a HashMap<String, HashMap<String, crate::SerializeTopicSegmentFn>>,
 }
 
-async fn with_execution_guard<F, Fut, T>(
+async fn with_execution_guard<F, Fut>(
     ctx: &ExecutionGuardContext<'_>,
     tool_name: &str,
     args: &serde_json::Map<String, serde_json::Value>,
     f: F,
-) -> T
+) -> Result<CallToolResult, McpError>
 where
     F: FnOnce() -> Fut,
-    Fut: std::future::Future<Output = T>,
+    Fut: std::future::Future<Output = Result<CallToolResult, McpError>>,
 {
+    let _permits = match ctx.concurrency_limits.acquire(tool_name).await {
+        Ok(permits) => permits,
+        Err(busy) => return Ok(busy),
+    };
     if !ctx.parallel_safe {
         if let Some(lock) = ctx.global_lock {
             let _guard = lock.lock().await;
@@ -195,41 +716,83 @@ pub(crate) struct ClapMcpServer {
     parallel_safe: bool,
     tool_execution_lock: Option<Arc<tokio::sync::Mutex<()>>>,
     topic_lock_registry: Arc<TopicLockRegistry>,
-    log_peer: Arc<Mutex<Option<Peer<RoleServer>>>>,
+    concurrency_limits: Arc<ConcurrencyLimits>,
+    /// The session that most recently sent a request; forwarded log messages go to it.
+    log_peer: Arc<Mutex<Option<LogTarget>>>,
+    /// This session's `logging/setLevel` level.
+    log_level: Arc<Mutex<LoggingLevel>>,
     task_manager: TaskManager,
-    /// URIs accepted via `resources/subscribe`. Bookkeeping only; update
-    /// notifications are not emitted.
-    subscribed_uris: Arc<Mutex<HashSet<String>>>,
+    /// Tasks served by `tasks/get` and listed in `clap://tasks`.
+    task_index: TaskIndex,
+    /// URIs this session accepted via `resources/subscribe`.
+    subscriptions: Arc<SessionSubscriptions>,
+    /// This session's client roots (`roots/list`).
+    roots: Arc<SessionRoots>,
+}
+
+/// Receiver of forwarded log messages and the lowest level it asked for.
+#[derive(Clone)]
+struct LogTarget {
+    peer: Peer<RoleServer>,
+    level: Arc<Mutex<LoggingLevel>>,
+}
+
+impl LogTarget {
+    fn forwards(&self, level: LoggingLevel) -> bool {
+        let min = self
+            .level
+            .lock()
+            .map(|l| *l)
+            .unwrap_or_else(|e| *e.into_inner());
+        crate::logging::level_rank(level) >= crate::logging::level_rank(min)
+    }
+}
+
+/// Current log target of `log_peer`, if a client has sent a request.
+fn log_target(log_peer: &Mutex<Option<LogTarget>>) -> Option<LogTarget> {
+    log_peer.lock().ok().and_then(|g| g.clone())
 }
 
 impl ClapMcpServer {
     fn capture_peer(&self, context: &RequestContext<RoleServer>) {
         if let Ok(mut guard) = self.log_peer.lock() {
-            *guard = Some(context.peer.clone());
+            *guard = Some(LogTarget {
+                peer: context.peer.clone(),
+                level: self.log_level.clone(),
+            });
+        }
+        if self.inner.logging_enabled
+            && let Ok(level) = self.log_level.lock()
+        {
+            crate::logging::set_forwarded_level(*level);
         }
     }
 
-    /// Record a resource subscription (no update notifications are sent).
-    pub(crate) fn track_resource_subscribe(&self, uri: impl Into<String>) {
-        if let Ok(mut guard) = self.subscribed_uris.lock() {
-            guard.insert(uri.into());
+    /// A handler for a new HTTP session: shared state, but its own resource subscriptions.
+    #[cfg(feature = "http")]
+    pub(crate) fn for_new_session(&self) -> Self {
+        Self {
+            subscriptions: self.inner.resource_updates.new_session(),
+            roots: Arc::default(),
+            log_level: Arc::new(Mutex::new(self.inner.default_log_level)),
+            ..self.clone()
         }
     }
 
+    /// Record a resource subscription for [`ResourceUpdateNotifier::notify`].
+    pub(crate) fn track_resource_subscribe(&self, uri: impl Into<String>) {
+        self.subscriptions.subscribe(uri.into());
+    }
+
     /// Remove a resource subscription. Unknown URIs are ignored.
     pub(crate) fn track_resource_unsubscribe(&self, uri: &str) {
-        if let Ok(mut guard) = self.subscribed_uris.lock() {
-            guard.remove(uri);
-        }
+        self.subscriptions.unsubscribe(uri);
     }
 
     /// Snapshot of currently subscribed resource URIs (test helper).
     #[cfg(test)]
     pub(crate) fn subscribed_resource_uris(&self) -> HashSet<String> {
-        self.subscribed_uris
-            .lock()
-            .map(|guard| guard.clone())
-            .unwrap_or_default()
+        self.subscriptions.uris()
     }
 }
 
@@ -237,8 +800,8 @@ impl ServerHandler for ClapMcpServer {
     fn get_info(&self) -> rmcp::model::ServerInfo {
         let logging_enabled = self.inner.logging_enabled;
         let task_augmented = self.inner.task_augmented_tools;
-        // resources.subscribe is advertised; handlers accept subscribe/unsubscribe
-        // but do not emit `notifications/resources/updated`.
+        // resources.subscribe is advertised; `notifications/resources/updated` is sent through
+        // ClapMcpServeOptions::resource_updates.
         let capabilities = match (logging_enabled, task_augmented) {
             (true, true) => ServerCapabilities::builder()
                 .enable_tools()
@@ -269,14 +832,24 @@ impl ServerHandler for ClapMcpServer {
                 .enable_prompts()
                 .build(),
         };
-        let server_info = Implementation::new("clap-mcp", env!("CARGO_PKG_VERSION"))
-            .with_title("clap-mcp")
-            .with_description("Expose clap CLI schema over MCP (stdio)");
+        let mut capabilities = capabilities;
+        // Tool arguments always complete from the clap schema (possible values, path hints).
+        capabilities.completions = Some(Default::default());
+        // Lists change through ClapMcpRegistry (and DisableTool poisoning).
+        if let Some(tools) = capabilities.tools.as_mut() {
+            tools.list_changed = Some(true);
+        }
+        if let Some(prompts) = capabilities.prompts.as_mut() {
+            prompts.list_changed = Some(true);
+        }
+        if let Some(resources) = capabilities.resources.as_mut() {
+            resources.list_changed = Some(true);
+        }
         let mut info = rmcp::model::ServerInfo::new(capabilities)
-            .with_server_info(server_info)
+            .with_server_info(self.inner.server_info.clone())
             .with_protocol_version(PROTOCOL_VERSION_STABLE);
-        if logging_enabled {
-            info = info.with_instructions(LOG_INTERPRETATION_INSTRUCTIONS);
+        if let Some(instructions) = &self.inner.instructions {
+            info = info.with_instructions(instructions.clone());
         }
         info
     }
@@ -298,6 +871,7 @@ impl ServerHandler for ClapMcpServer {
         let negotiated = negotiate_protocol_version(&request.protocol_version);
         request.protocol_version = negotiated.clone();
         context.peer.set_peer_info(request);
+        self.subscriptions.set_peer(context.peer.clone());
         let mut info = self.get_info();
         info.protocol_version = negotiated;
         std::future::ready(Ok(info))
@@ -305,13 +879,17 @@ impl ServerHandler for ClapMcpServer {
 
     fn set_level(
         &self,
-        _request: SetLevelRequestParams,
+        request: SetLevelRequestParams,
         context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<(), McpError>> + Send + '_ {
-        self.capture_peer(&context);
         if self.inner.logging_enabled {
+            if let Ok(mut level) = self.log_level.lock() {
+                *level = request.level;
+            }
+            self.capture_peer(&context);
             std::future::ready(Ok(()))
         } else {
+            self.capture_peer(&context);
             std::future::ready(Err(McpError::method_not_found::<
                 rmcp::model::SetLevelRequestMethod,
             >()))
@@ -320,15 +898,37 @@ impl ServerHandler for ClapMcpServer {
 
     fn list_resources(
         &self,
-        _request: Option<PaginatedRequestParams>,
+        request: Option<PaginatedRequestParams>,
         context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListResourcesResult, McpError>> + Send + '_ {
         self.capture_peer(&context);
         async move {
-            Ok(list_resources_result(
-                &self.inner.custom_resources,
-                self.inner.cache_hints,
-            ))
+            let mut result =
+                list_resources_result(&self.inner.custom_resources, self.inner.cache_hints);
+            let mut builtin = Vec::new();
+            if self.inner.state_persistence.is_some() {
+                builtin.push(clap_state_resource());
+            }
+            if self.inner.catch_in_process_panics {
+                builtin.push(clap_status_resource());
+            }
+            if self.inner.task_augmented_tools {
+                builtin.push(clap_tasks_resource());
+            }
+            result.resources.splice(1..1, builtin);
+            result
+                .resources
+                .retain(|r| self.inner.registry.is_resource_enabled(&r.uri));
+            let (resources, next_cursor) = paginate(
+                std::mem::take(&mut result.resources),
+                |r| r.uri.as_str(),
+                "resources",
+                self.inner.page_size,
+                page_cursor(&request),
+            )?;
+            result.resources = resources;
+            result.next_cursor = next_cursor;
+            Ok(result)
         }
     }
 
@@ -339,12 +939,52 @@ impl ServerHandler for ClapMcpServer {
     ) -> impl std::future::Future<Output = Result<ReadResourceResponse, McpError>> + Send + '_ {
         self.capture_peer(&context);
         let inner = self.inner.clone();
+        let task_manager = self.task_manager.clone();
+        let task_index = self.task_index.clone();
         async move {
+            let registry = &inner.registry;
+            if !registry.is_resource_enabled(&params.uri) {
+                return Err(McpError::resource_not_found(
+                    "Resource not found",
+                    Some(serde_json::json!({ "uri": params.uri })),
+                ));
+            }
+            let cache_hints = inner.resource_read_cache_hints.unwrap_or(inner.cache_hints);
+            if let Some(persisted) = &inner.state_persistence
+                && params.uri == MCP_RESOURCE_URI_STATE
+            {
+                return read_state_resource_result(persisted, cache_hints, params).map(Into::into);
+            }
+            if inner.catch_in_process_panics && params.uri == MCP_RESOURCE_URI_STATUS {
+                let text = serde_json::to_string_pretty(&inner.status_json())
+                    .unwrap_or_else(|_| inner.status_json().to_string());
+                return Ok(cache_hints
+                    .apply_to_read(ReadResourceResult::new(vec![
+                        ResourceContents::text(text, params.uri).with_mime_type("application/json"),
+                    ]))
+                    .into());
+            }
+            if inner.task_augmented_tools && params.uri == MCP_RESOURCE_URI_TASKS {
+                let tasks = task_index.list_json(&task_manager);
+                let text =
+                    serde_json::to_string_pretty(&tasks).unwrap_or_else(|_| tasks.to_string());
+                return Ok(cache_hints
+                    .apply_to_read(ReadResourceResult::new(vec![
+                        ResourceContents::text(text, params.uri).with_mime_type("application/json"),
+                    ]))
+                    .into());
+            }
+            let templates: Vec<_> = inner
+                .custom_resource_templates
+                .iter()
+                .filter(|t| registry.is_resource_enabled(&t.uri_template))
+                .cloned()
+                .collect();
             read_resource_result(
-                &inner.schema_json,
+                &inner.tool_set().schema_json,
                 &inner.custom_resources,
-                &inner.custom_resource_templates,
-                inner.resource_read_cache_hints.unwrap_or(inner.cache_hints),
+                &templates,
+                cache_hints,
                 params,
             )
             .await
@@ -354,25 +994,56 @@ impl ServerHandler for ClapMcpServer {
 
     fn list_resource_templates(
         &self,
-        _request: Option<PaginatedRequestParams>,
+        request: Option<PaginatedRequestParams>,
         context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListResourceTemplatesResult, McpError>> + Send + '_
     {
         self.capture_peer(&context);
         async move {
-            Ok(list_resource_templates_result(
+            let mut result = list_resource_templates_result(
                 &self.inner.custom_resource_templates,
                 self.inner.cache_hints,
-            ))
+            );
+            result
+                .resource_templates
+                .retain(|t| self.inner.registry.is_resource_enabled(&t.uri_template));
+            let (templates, next_cursor) = paginate(
+                std::mem::take(&mut result.resource_templates),
+                |t| t.uri_template.as_str(),
+                "resource-templates",
+                self.inner.page_size,
+                page_cursor(&request),
+            )?;
+            result.resource_templates = templates;
+            result.next_cursor = next_cursor;
+            Ok(result)
         }
     }
 
+    fn accepted_subscription_filter(
+        &self,
+        requested: &SubscriptionFilter,
+    ) -> Option<SubscriptionFilter> {
+        // Every list can change through ClapMcpRegistry, and any URI can be notified.
+        Some(requested.clone())
+    }
+
+    async fn listen(&self, context: SubscriptionContext) -> Result<(), McpError> {
+        // Registered with the notifier only while the stream stays open.
+        let subscriber = self.inner.resource_updates.new_session();
+        subscriber.set_listen_sink(context.sink().clone(), context.accepted());
+        context.cancelled().await;
+        drop(subscriber);
+        Ok(())
+    }
+
     fn subscribe(
         &self,
         request: SubscribeRequestParams,
         context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<(), McpError>> + Send + '_ {
         self.capture_peer(&context);
+        self.subscriptions.set_peer(context.peer.clone());
         self.track_resource_subscribe(request.uri);
         std::future::ready(Ok(()))
     }
@@ -389,30 +1060,53 @@ impl ServerHandler for ClapMcpServer {
 
     fn list_tools(
         &self,
-        _request: Option<PaginatedRequestParams>,
+        request: Option<PaginatedRequestParams>,
         context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListToolsResult, McpError>> + Send + '_ {
         self.capture_peer(&context);
         async move {
-            Ok(self
+            // Custom tools follow clap tools, so later pages carry them.
+            let (tools, next_cursor) = paginate(
+                self.inner.listed_tools(),
+                |t| t.name.as_ref(),
+                "tools",
+                self.inner.page_size,
+                page_cursor(&request),
+            )?;
+            let mut result = self
                 .inner
                 .cache_hints
-                .apply_to_tools(ListToolsResult::with_all_items(self.inner.tools.clone())))
+                .apply_to_tools(ListToolsResult::with_all_items(tools));
+            result.next_cursor = next_cursor;
+            Ok(result)
         }
     }
 
     fn list_prompts(
         &self,
-        _request: Option<PaginatedRequestParams>,
+        request: Option<PaginatedRequestParams>,
         context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListPromptsResult, McpError>> + Send + '_ {
         self.capture_peer(&context);
         async move {
-            Ok(list_prompts_result(
+            let mut result = list_prompts_result(
                 self.inner.logging_enabled,
                 &self.inner.custom_prompts,
                 self.inner.cache_hints,
-            ))
+            );
+            result
+                .prompts
+                .retain(|p| self.inner.registry.is_prompt_enabled(&p.name));
+            let (prompts, next_cursor) = paginate(
+                std::mem::take(&mut result.prompts),
+                |p| p.name.as_str(),
+                "prompts",
+                self.inner.page_size,
+                page_cursor(&request),
+            )?;
+            result.prompts = prompts;
+            result.next_cursor = next_cursor;
+            Ok(result)
         }
     }
 
@@ -424,13 +1118,33 @@ impl ServerHandler for ClapMcpServer {
         self.capture_peer(&context);
         let logging_enabled = self.inner.logging_enabled;
         let custom_prompts = self.inner.custom_prompts.clone();
+        let enabled = self.inner.registry.is_prompt_enabled(&params.name);
         async move {
+            if !enabled {
+                return Err(McpError::invalid_params(
+                    format!("unknown prompt: {}", params.name),
+                    None,
+                ));
+            }
             get_prompt_result(logging_enabled, &custom_prompts, params)
                 .await
                 .map(Into::into)
         }
     }
 
+    fn complete(
+        &self,
+        request: CompleteRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<CompleteResult, McpError>> + Send + '_ {
+        self.capture_peer(&context);
+        async move { complete_result(&self.inner, request, &self.roots, &context.peer).await }
+    }
+
+    async fn on_roots_list_changed(&self, _context: NotificationContext<RoleServer>) {
+        self.roots.invalidate().await;
+    }
+
     fn call_tool(
         &self,
         params: CallToolRequestParams,
@@ -441,9 +1155,12 @@ impl ServerHandler for ClapMcpServer {
         let lock = self.tool_execution_lock.clone();
         let parallel_safe = self.parallel_safe;
         let topic_registry = self.topic_lock_registry.clone();
+        let concurrency_limits = self.concurrency_limits.clone();
         let serialize_tools = self.inner.serialize_tools.clone();
         let serialize_topic_args = self.inner.serialize_topic_args.clone();
         let task_manager = self.task_manager.clone();
+        let task_index = self.task_index.clone();
+        let roots = self.roots.clone();
         async move {
             let client_tasks = context
                 .client_capabilities()
@@ -454,79 +1171,119 @@ impl ServerHandler for ClapMcpServer {
                 let future_request = params.clone();
                 let future_context = context.clone();
                 let future_inner = inner.clone();
+                let future_roots = roots.clone();
                 let future_lock = lock.clone();
                 let future_parallel_safe = parallel_safe;
                 let future_topic_registry = topic_registry.clone();
+                let future_concurrency_limits = concurrency_limits.clone();
                 let future_serialize_tools = serialize_tools.clone();
                 let future_serialize_topic_args = serialize_topic_args.clone();
-                let task_args = params.arguments.clone().unwrap_or_default();
                 let catch_panics = future_inner.catch_in_process_panics;
+                let panic_inner = future_inner.clone();
+                let panic_peer = context.peer.clone();
+                let panic_tool_name = future_request.name.to_string();
                 let tool_name = future_request.name.clone();
+                let status_manager = task_manager.clone();
+                let status_peer = context.peer.clone();
 
-                let task = task_manager.spawn(
-                    TaskOptions::new().with_status_message("Task accepted"),
-                    move |task_ctx| {
-                        let task_id = task_ctx.task_id().to_string();
-                        Box::pin(async move {
-                            let guard_ctx = ExecutionGuardContext {
-                                parallel_safe: future_parallel_safe,
-                                global_lock: &future_lock,
-                                topic_registry: &future_topic_registry,
-                                serialize_tools: &future_serialize_tools,
-                                serialize_topic_args: &future_serialize_topic_args,
-                            };
-                            let result = with_execution_guard(
-                                &guard_ctx,
-                                &tool_name,
-                                &task_args,
-                                || async move {
-                                    let run_body = async move {
-                                        crate::logging::run_with_mcp_task_id(task_id, async move {
-                                            future_inner
-                                                .call_tool(&future_request, &future_context)
-                                                .await
-                                        })
-                                        .await
-                                    };
-                                    if catch_panics {
-                                        match tokio::task::spawn(run_body).await {
-                                            Ok(r) => r,
-                                            Err(join_err) if join_err.is_panic() => {
-                                                Ok(call_tool_result_from_panic(
-                                                    join_err.into_panic().as_ref(),
-                                                ))
-                                            }
-                                            Err(join_err) => Err(McpError::internal_error(
-                                                format!("task body join error: {join_err}"),
-                                                None,
-                                            )),
-                                        }
-                                    } else {
-                                        run_body.await
-                                    }
-                                },
-                            )
-                            .await;
-                            match result {
-                                Ok(call_tool) => Ok(call_tool),
-                                Err(err) => Err(TaskExit::Error(err)),
+                let retention = inner.task_retention;
+                let mut options = TaskOptions::new().with_status_message("Task accepted");
+                if let Some(ttl) = retention.ttl {
+                    options = options.with_ttl_ms(duration_ms(ttl));
+                }
+                if let Some(poll_interval) = retention.poll_interval {
+                    options = options.with_poll_interval_ms(duration_ms(poll_interval));
+                }
+                let task = task_manager.spawn(options, move |task_ctx| {
+                    let task_id = task_ctx.task_id().to_string();
+                    let task_handle = ClapMcpTask::start(task_ctx, status_manager, status_peer);
+                    let stopped_handle = task_handle.clone();
+                    Box::pin(async move {
+                        let (task_args, call_roots) = match future_inner
+                            .prepare_call(&future_request, &future_context, &future_roots)
+                            .await
+                        {
+                            Ok(PreparedCall::Run { args, roots }) => (args, roots),
+                            Ok(PreparedCall::Done(result)) if !stopped_handle.was_stopped() => {
+                                return Ok(result);
                             }
-                        })
-                    },
-                );
+                            Ok(PreparedCall::Done(_)) => return Err(TaskExit::Cancelled),
+                            Err(err) => return Err(TaskExit::Error(err)),
+                        };
+                        let run_args = task_args.clone();
+                        let guard_ctx = ExecutionGuardContext {
+                            parallel_safe: future_parallel_safe,
+                            global_lock: &future_lock,
+                            topic_registry: &future_topic_registry,
+                            concurrency_limits: &future_concurrency_limits,
+                            serialize_tools: &future_serialize_tools,
+                            serialize_topic_args: &future_serialize_topic_args,
+                        };
+                        let result = with_execution_guard(
+                            &guard_ctx,
+                            &tool_name,
+                            &task_args,
+                            || async move {
+                                let run_body = async move {
+                                    crate::logging::run_with_mcp_task_id(task_id, async move {
+                                        future_inner
+                                            .run_call(
+                                                &future_request,
+                                                &run_args,
+                                                &future_context,
+                                                &call_roots,
+                                                Some(task_handle),
+                                            )
+                                            .await
+                                    })
+                                    .await
+                                };
+                                if catch_panics {
+                                    match tokio::task::spawn(run_body).await {
+                                        Ok(r) => r,
+                                        Err(join_err) if join_err.is_panic() => Ok(panic_inner
+                                            .handle_caught_panic(
+                                                &panic_tool_name,
+                                                join_err.into_panic(),
+                                                &panic_peer,
+                                            )
+                                            .await),
+                                        Err(join_err) => Err(McpError::internal_error(
+                                            format!("task body join error: {join_err}"),
+                                            None,
+                                        )),
+                                    }
+                                } else {
+                                    run_body.await
+                                }
+                            },
+                        )
+                        .await;
+                        match result {
+                            _ if stopped_handle.was_stopped() => Err(TaskExit::Cancelled),
+                            Ok(call_tool) => Ok(call_tool),
+                            Err(err) => Err(TaskExit::Error(err)),
+                        }
+                    })
+                });
+                task_index.insert(&task_manager, task.task_id.clone(), params.name.to_string());
                 return Ok(CreateTaskResult::new(task).into());
             }
 
-            let args = params.arguments.clone().unwrap_or_default();
+            let (args, call_roots) = match inner.prepare_call(&params, &context, &roots).await? {
+                PreparedCall::Run { args, roots } => (args, roots),
+                PreparedCall::Done(result) => return Ok(result.into()),
+            };
             let guard_ctx = ExecutionGuardContext {
                 parallel_safe,
                 global_lock: &lock,
                 topic_registry: &topic_registry,
+                concurrency_limits: &concurrency_limits,
                 serialize_tools: &serialize_tools,
                 serialize_topic_args: &serialize_topic_args,
             };
             with_execution_guard(&guard_ctx, &params.name, &args, || {
-                inner.call_tool(&params, &context)
+                inner.run_call(&params, &args, &context, &call_roots, None)
             })
             .await
             .map(Into::into)
@@ -539,7 +1296,9 @@ impl ServerHandler for ClapMcpServer {
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<GetTaskResult, McpError>> + Send + '_ {
         let task_manager = self.task_manager.clone();
+        let task_index = self.task_index.clone();
         async move {
+            ensure_task_retained(&task_index, &request.task_id)?;
             let detailed = task_manager.get_task(&request.task_id)?;
             Ok(GetTaskResult::new(detailed))
         }
@@ -551,7 +1310,9 @@ impl ServerHandler for ClapMcpServer {
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<(), McpError>> + Send + '_ {
         let task_manager = self.task_manager.clone();
+        let task_index = self.task_index.clone();
         async move {
+            ensure_task_retained(&task_index, &request.task_id)?;
             task_manager.update_task(&request.task_id, request.input_responses)?;
             Ok(())
         }
@@ -563,36 +1324,73 @@ impl ServerHandler for ClapMcpServer {
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<(), McpError>> + Send + '_ {
         let task_manager = self.task_manager.clone();
+        let task_index = self.task_index.clone();
         async move {
+            ensure_task_retained(&task_index, &request.task_id)?;
             task_manager.cancel_task(&request.task_id)?;
             Ok(())
         }
     }
 }
 
+/// Tasks forgotten under `max_retained` answer like the SDK's unknown (evicted) tasks.
+fn ensure_task_retained(task_index: &TaskIndex, task_id: &str) -> Result<(), McpError> {
+    if task_index.contains(task_id) {
+        Ok(())
+    } else {
+        Err(McpError::invalid_params(
+            format!("unknown task: {task_id}"),
+            None,
+        ))
+    }
+}
+
+fn duration_ms(duration: std::time::Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+fn page_cursor(request: &Option<PaginatedRequestParams>) -> Option<&str> {
+    request.as_ref().and_then(|r| r.cursor.as_deref())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn build_clap_mcp_server(
     schema_json: String,
     tools: Vec<Tool>,
     executable_path: Option<PathBuf>,
-    in_process_handler: Option<InProcessToolHandler>,
+    in_process_handler: Option<ToolHandler>,
     root_name: String,
     config: &ClapMcpConfig,
     serve_options: &ClapMcpServeOptions,
     metadata: &ClapMcpSchemaMetadata,
 ) -> Result<ClapMcpServer, ClapMcpError> {
-    if metadata.task_augmented_tools {
-        if !config.reinvocation_safe {
-            return Err(ClapMcpError::InvalidConfig(
-                "task_augmented_tools requires reinvocation_safe (in-process execution)".into(),
-            ));
-        }
-        if in_process_handler.is_none() {
-            return Err(ClapMcpError::InvalidConfig(
-                "task_augmented_tools requires in-process execution (derive with reinvocation_safe and #[clap_mcp_output_from = \"run\"] or equivalent)"
-                    .into(),
-            ));
-        }
+    if config.parallel_safe
+        && in_process_handler.is_some()
+        && crate::capture_stdout_for_serve(serve_options)
+    {
+        return Err(ClapMcpError::InvalidConfig(
+            "capture_stdout redirects process-wide stdout and cannot be combined with parallel_safe; write tool output with clap_mcp::mcp_println! (ClapMcpOutput) instead"
+                .into(),
+        ));
+    }
+
+    let retention = &serve_options.task_retention;
+    if retention.ttl.is_some_and(|ttl| ttl.is_zero()) || retention.max_retained == Some(0) {
+        return Err(ClapMcpError::InvalidConfig(
+            "task_retention ttl and max_retained must be non-zero (or None for no limit)".into(),
+        ));
+    }
+    if retention.max_retained.is_some() && retention.ttl.is_none() {
+        return Err(ClapMcpError::InvalidConfig(
+            "task_retention max_retained requires a ttl: forgotten tasks are freed when it elapses"
+                .into(),
+        ));
+    }
+
+    if serve_options.page_size == Some(0) {
+        return Err(ClapMcpError::InvalidConfig(
+            "page_size must be at least 1 (or None to list everything in one page)".into(),
+        ));
     }
 
     let tool_execution_lock = if config.parallel_safe {
@@ -602,6 +1400,12 @@ pub(crate) fn build_clap_mcp_server(
     };
 
     let logging_enabled = serve_options.log_rx.is_some();
+    let default_log_level = serve_options
+        .default_log_level
+        .unwrap_or(LoggingLevel::Debug);
+    if logging_enabled {
+        crate::logging::set_forwarded_level(default_log_level);
+    }
     let task_tool_filter = if metadata.task_augmented_tools && !metadata.task_tool_names.is_empty()
     {
         Some(
@@ -615,42 +1419,85 @@ pub(crate) fn build_clap_mcp_server(
         None
     };
 
+    // Custom instructions come first; the log guide applies to every server that forwards logs.
+    let instructions = match (
+        serve_options
+            .instructions
+            .as_ref()
+            .or(config.instructions.as_ref()),
+        logging_enabled,
+    ) {
+        (Some(custom), true) => Some(format!("{custom}\n\n{LOG_INTERPRETATION_INSTRUCTIONS}")),
+        (Some(custom), false) => Some(custom.clone()),
+        (None, true) => Some(LOG_INTERPRETATION_INSTRUCTIONS.to_string()),
+        (None, false) => None,
+    };
+
     let custom_tool_names: HashSet<String> = serve_options
         .custom_tools
         .iter()
         .map(|t| t.name.to_string())
         .collect();
-    let mut tools = tools;
-    tools.extend(serve_options.custom_tools.iter().cloned());
-
-    let inner = Arc::new(ServeHandlerInner {
+    let schema: crate::ClapSchema = serde_json::from_str(&schema_json)?;
+    let server_info = serve_options.server_identity.implementation(&schema.root);
+    let tool_set = ToolSet::new(
         schema_json,
+        &schema,
         tools,
+        &serve_options.custom_tools,
+        config,
+        metadata,
+    );
+    tool_set.check_subprocess_tools(executable_path.as_deref(), in_process_handler.is_some())?;
+
+    let inner = Arc::new(ServeHandlerInner {
+        tool_set: RwLock::new(Arc::new(tool_set)),
+        config: config.clone(),
+        metadata: metadata.clone(),
+        custom_tools: serve_options.custom_tools.clone(),
         executable_path,
         in_process_handler,
         root_name,
         catch_in_process_panics: config.catch_in_process_panics,
+        poison_policy: config.poison_policy,
+        poisoned_tools: Mutex::new(Vec::new()),
         custom_resources: serve_options.custom_resources.clone(),
         custom_resource_templates: serve_options.custom_resource_templates.clone(),
         custom_prompts: serve_options.custom_prompts.clone(),
         custom_tool_names,
         cache_hints: serve_options.cache_hints,
         resource_read_cache_hints: serve_options.resource_read_cache_hints,
+        page_size: serve_options.page_size,
+        default_log_level,
+        confine_paths_to_roots: serve_options.confine_paths_to_roots,
+        state_persistence: serve_options.state_persistence.clone(),
         logging_enabled,
+        server_info,
+        instructions,
         task_augmented_tools: metadata.task_augmented_tools,
         task_tool_filter,
+        task_retention: serve_options.task_retention,
         serialize_tools: metadata.serialize_tools.clone(),
         serialize_topic_args: metadata.serialize_topic_args.clone(),
+        completion_provider: serve_options.completion_provider.clone(),
+        resource_updates: serve_options.resource_updates.clone(),
+        registry: serve_options.registry.clone(),
     });
+    serve_options.registry.attach(&inner);
 
+    let log_level = inner.default_log_level;
     Ok(ClapMcpServer {
         inner,
         parallel_safe: config.parallel_safe,
         tool_execution_lock,
         topic_lock_registry: Arc::new(TopicLockRegistry::new()),
+        concurrency_limits: Arc::new(ConcurrencyLimits::new(config, metadata)),
         log_peer: Arc::new(Mutex::new(None)),
+        log_level: Arc::new(Mutex::new(log_level)),
         task_manager: TaskManager::new(),
-        subscribed_uris: Arc::new(Mutex::new(HashSet::new())),
+        task_index: TaskIndex::new(serve_options.task_retention.max_retained),
+        subscriptions: serve_options.resource_updates.new_session(),
+        roots: Arc::default(),
     })
 }
 
@@ -662,11 +1509,12 @@ pub(crate) fn spawn_log_forwarder(
         let log_peer = server.log_peer.clone();
         tokio::spawn(async move {
             while let Some(params) = log_rx.recv().await {
-                let peer = log_peer.lock().ok().and_then(|g| g.clone());
-                let Some(peer) = peer else {
+                let Some(target) = log_target(&log_peer) else {
                     continue;
                 };
-                let _ = notify_log(&peer, params).await;
+                if target.forwards(params.level) {
+                    let _ = notify_log(&target.peer, params).await;
+                }
             }
         });
     }
@@ -677,7 +1525,7 @@ pub(crate) async fn serve_schema_json_over_stdio(
     schema_json: String,
     executable_path: Option<PathBuf>,
     config: ClapMcpConfig,
-    in_process_handler: Option<InProcessToolHandler>,
+    in_process_handler: Option<ToolHandler>,
     mut serve_options: ClapMcpServeOptions,
     metadata: &ClapMcpSchemaMetadata,
     stdio_io: crate::serve::McpStdioIo,
@@ -704,6 +1552,23 @@ pub(crate) async fn serve_schema_json_over_stdio(
     // `serve_directly` so stdio `initialize` uses this handler (and its
     // conformance-tested version set) instead of rmcp's default handshake.
     let service = match stdio_io {
+        #[cfg(unix)]
+        crate::serve::McpStdioIo::Process => match crate::stdio::StdioGuard::install() {
+            Ok((guard, protocol, stray_rx)) => {
+                spawn_stray_stdout_forwarder(&server, stray_rx);
+                let service = serve_directly(
+                    server,
+                    (tokio::io::stdin(), protocol).into_transport(),
+                    None,
+                );
+                let result = service.waiting().await;
+                drop(guard);
+                result.map_err(ClapMcpError::Join)?;
+                return Ok(());
+            }
+            Err(_) => serve_directly(server, rmcp::transport::stdio(), None),
+        },
+        #[cfg(not(unix))]
         crate::serve::McpStdioIo::Process => serve_directly(server, rmcp::transport::stdio(), None),
         crate::serve::McpStdioIo::Custom { read, write } => {
             serve_directly(server, (read, write).into_transport(), None)
@@ -713,6 +1578,39 @@ pub(crate) async fn serve_schema_json_over_stdio(
     Ok(())
 }
 
+/// Forward lines written to fd 1 while serving stdio as `notifications/message` (logger
+/// `stdout`, level `info`) when logging is enabled and a client that accepts `info` is
+/// connected; otherwise to stderr.
+#[cfg(unix)]
+fn spawn_stray_stdout_forwarder(
+    server: &ClapMcpServer,
+    mut stray_rx: crate::stdio::StrayStdoutReceiver,
+) {
+    let log_peer = server
+        .inner
+        .logging_enabled
+        .then(|| server.log_peer.clone());
+    tokio::spawn(async move {
+        while let Some(line) = stray_rx.recv().await {
+            let target = log_peer
+                .as_deref()
+                .and_then(log_target)
+                .filter(|target| target.forwards(LoggingLevel::Info));
+            let Some(target) = target else {
+                eprintln!("{line}");
+                continue;
+            };
+            let params = LoggingMessageNotificationParams {
+                level: LoggingLevel::Info,
+                logger: Some(crate::stdio::STRAY_STDOUT_LOGGER.to_string()),
+                data: serde_json::Value::String(line),
+                meta: None,
+            };
+            let _ = notify_log(&target.peer, params).await;
+        }
+    });
+}
+
 #[allow(deprecated)]
 async fn notify_log(
     peer: &Peer<RoleServer>,
@@ -806,6 +1704,83 @@ pub(crate) fn build_execution_command(
     command
 }
 
+/// Run a subprocess tool for a task-augmented call. Each stderr line becomes the task's status
+/// message; on `tasks/cancel` the child's process group is killed and `None` is returned.
+async fn run_subprocess_task(
+    command: std::process::Command,
+    task: &ClapMcpTask,
+) -> Option<std::io::Result<std::process::Output>> {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+    let mut command = tokio::process::Command::from(command);
+    command
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    // Own process group, so cancelling also stops anything the tool started.
+    #[cfg(unix)]
+    command.process_group(0);
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(error) => return Some(Err(error)),
+    };
+    let pid = child.id();
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let read_stdout = async move {
+        let mut buf = Vec::new();
+        if let Some(mut stdout) = stdout {
+            let _ = stdout.read_to_end(&mut buf).await;
+        }
+        buf
+    };
+    let read_stderr = async move {
+        let mut buf = Vec::new();
+        if let Some(stderr) = stderr {
+            let mut stderr = tokio::io::BufReader::new(stderr);
+            loop {
+                let start = buf.len();
+                match stderr.read_until(b'\n', &mut buf).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        let line = String::from_utf8_lossy(&buf[start..]);
+                        let line = line.trim();
+                        if !line.is_empty() {
+                            task.set_status_message(line);
+                        }
+                    }
+                }
+            }
+        }
+        buf
+    };
+    let run = async {
+        let (stdout, stderr, status) = tokio::join!(read_stdout, read_stderr, child.wait());
+        status.map(|status| std::process::Output {
+            status,
+            stdout,
+            stderr,
+        })
+    };
+    tokio::select! {
+        output = run => Some(output),
+        _ = task.cancelled() => {
+            #[cfg(unix)]
+            if let Some(pid) = pid {
+                // SAFETY: kill(2) with a negative pid signals that process group only.
+                unsafe {
+                    libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+                }
+            }
+            #[cfg(not(unix))]
+            let _ = pid;
+            // `kill_on_drop` stops the child itself where process groups are unavailable.
+            None
+        }
+    }
+}
+
 pub(crate) fn subprocess_stderr_log_params(
     tool_name: &str,
     stderr: &str,
@@ -879,6 +1854,41 @@ fn clap_schema_resource() -> Resource {
         .with_mime_type("application/json")
 }
 
+fn clap_status_resource() -> Resource {
+    Resource::new(MCP_RESOURCE_URI_STATUS, "clap-status")
+        .with_title("Server status")
+        .with_description("Poisoned status and tools that panicked under catch_in_process_panics")
+        .with_mime_type("application/json")
+}
+
+fn clap_tasks_resource() -> Resource {
+    Resource::new(MCP_RESOURCE_URI_TASKS, "clap-tasks")
+        .with_title("Tasks")
+        .with_description("Task-augmented tool calls with their tool, status, and timestamps")
+        .with_mime_type("application/json")
+}
+
+fn clap_state_resource() -> Resource {
+    Resource::new(MCP_RESOURCE_URI_STATE, "clap-state")
+        .with_title("Session state snapshot")
+        .with_description("Read-only JSON snapshot of the persisted session state")
+        .with_mime_type("application/json")
+}
+
+pub(crate) fn read_state_resource_result(
+    persisted: &crate::state::PersistedState,
+    cache_hints: CacheHints,
+    params: ReadResourceRequestParams,
+) -> Result<ReadResourceResult, McpError> {
+    let snapshot = persisted
+        .snapshot()
+        .map_err(|e| McpError::internal_error(format!("failed to snapshot state: {e}"), None))?;
+    let text = serde_json::to_string_pretty(&snapshot).unwrap_or_else(|_| snapshot.to_string());
+    Ok(cache_hints.apply_to_read(ReadResourceResult::new(vec![
+        ResourceContents::text(text, params.uri).with_mime_type("application/json"),
+    ])))
+}
+
 pub(crate) fn list_resources_result(
     custom_resources: &[content::CustomResource],
     cache_hints: CacheHints,
@@ -989,6 +1999,98 @@ pub(crate) fn list_prompts_result(
     cache_hints.apply_to_prompts(ListPromptsResult::with_all_items(prompts))
 }
 
+/// `completion/complete`: custom prompt arguments and resource template placeholders go to
+/// [`ClapMcpServeOptions::completion_provider`]; a `ref/prompt` naming a clap tool completes
+/// that tool's arguments (see [`crate::completion`]).
+pub(crate) async fn complete_result(
+    inner: &ServeHandlerInner,
+    request: CompleteRequestParams,
+    roots: &SessionRoots,
+    peer: &Peer<RoleServer>,
+) -> Result<CompleteResult, McpError> {
+    let argument = request.argument.name.as_str();
+    let value = request.argument.value.as_str();
+    let completion_context = request
+        .context
+        .and_then(|c| c.arguments)
+        .unwrap_or_default();
+    let unknown_argument = |owner: &str| {
+        McpError::invalid_params(format!("unknown argument for {owner}: {argument}"), None)
+    };
+    let reference = match &request.r#ref {
+        Reference::Prompt(prompt) => {
+            // Entries disabled in the registry are unknown, as in `prompts/get` and `tools/call`.
+            let unknown_prompt =
+                || McpError::invalid_params(format!("unknown prompt: {}", prompt.name), None);
+            if let Some(custom) = inner.custom_prompts.iter().find(|p| p.name == prompt.name) {
+                if !inner.registry.is_prompt_enabled(&custom.name) {
+                    return Err(unknown_prompt());
+                }
+                if !custom.arguments.iter().any(|a| a.name == argument) {
+                    return Err(unknown_argument(&prompt.name));
+                }
+                content::CompletionReference::Prompt(&custom.name)
+            } else if prompt.name == PROMPT_LOGGING_GUIDE && inner.logging_enabled {
+                if !inner.registry.is_prompt_enabled(&prompt.name) {
+                    return Err(unknown_prompt());
+                }
+                return Err(unknown_argument(&prompt.name));
+            } else if let Some(args) = inner
+                .tool_set()
+                .tool_arg_completions
+                .get(&prompt.name)
+                .filter(|_| inner.registry.is_tool_enabled(&prompt.name))
+            {
+                let Some(spec) = args.get(argument) else {
+                    return Err(unknown_argument(&prompt.name));
+                };
+                let arg_request = crate::ArgCompletionRequest {
+                    tool: &prompt.name,
+                    arg: argument,
+                    value,
+                    context: &completion_context,
+                };
+                let values =
+                    crate::completion::complete_tool_arg(spec, &arg_request, roots, peer).await;
+                return Ok(crate::completion::completion_result(values));
+            } else {
+                return Err(unknown_prompt());
+            }
+        }
+        Reference::Resource(resource) => {
+            let Some(template) = inner
+                .custom_resource_templates
+                .iter()
+                .find(|t| t.uri_template == resource.uri)
+                .filter(|t| inner.registry.is_resource_enabled(&t.uri_template))
+            else {
+                return Err(McpError::invalid_params(
+                    format!("unknown resource template: {}", resource.uri),
+                    None,
+                ));
+            };
+            if !content::uri_template_params(&template.uri_template).contains(&argument) {
+                return Err(unknown_argument(&resource.uri));
+            }
+            content::CompletionReference::ResourceTemplate(&template.uri_template)
+        }
+        _ => {
+            return Err(McpError::invalid_params(
+                "unsupported completion reference",
+                None,
+            ));
+        }
+    };
+    let Some(provider) = inner.completion_provider.as_ref() else {
+        return Ok(crate::completion::completion_result(Vec::new()));
+    };
+    let values = provider
+        .complete(reference, argument, value, &completion_context)
+        .await
+        .map_err(|e| McpError::internal_error(format!("completion failed: {e}"), None))?;
+    Ok(crate::completion::completion_result(values))
+}
+
 pub(crate) async fn get_prompt_result(
     logging_enabled: bool,
     custom_prompts: &[content::CustomPrompt],
@@ -0,0 +1,242 @@
+//! Opt-in persistence for shared session state.
+//!
+//! Stateful servers built with [`crate::parse_or_serve_mcp_with_state`] or
+//! [`crate::ServeMcpBuilder::for_cli_with_state`] keep state in memory for the server process
+//! lifetime. [`StatePersistence`](crate::state::StatePersistence) snapshots that state to a
+//! JSON file so it survives restarts: load it before creating the `Arc`, then
+//! [`bind`](crate::state::StatePersistence::bind) the `Arc` into
+//! [`crate::ClapMcpServeOptions::state_persistence`]. While serving, the snapshot is written
+//! periodically (when
+//! [`snapshot_interval`](crate::state::StatePersistence::snapshot_interval) is set) and on
+//! graceful shutdown, and exposed read-only as the [`crate::MCP_RESOURCE_URI_STATE`] resource.
+
+use crate::ClapMcpError;
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+/// Where and how often to snapshot shared session state.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use clap_mcp::{ClapMcpServeOptions, state::StatePersistence};
+/// use std::{sync::{Arc, Mutex}, time::Duration};
+///
+/// let persistence = StatePersistence::new("counter-state.json")
+///     .with_snapshot_interval(Duration::from_secs(30));
+/// let state = Arc::new(persistence.load_or_default::<Mutex<CounterState>>()?);
+/// let serve = ClapMcpServeOptions {
+///     state_persistence: Some(persistence.bind(Arc::clone(&state))),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatePersistence {
+    /// JSON snapshot file. Written atomically (temporary file, then rename).
+    pub path: PathBuf,
+    /// When set, also snapshot on this interval while serving (not only on shutdown).
+    pub snapshot_interval: Option<Duration>,
+}
+
+impl StatePersistence {
+    /// Persist to `path` on graceful shutdown only.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            snapshot_interval: None,
+        }
+    }
+
+    /// Also snapshot every `interval` while serving.
+    pub fn with_snapshot_interval(mut self, interval: Duration) -> Self {
+        self.snapshot_interval = Some(interval);
+        self
+    }
+
+    /// Load a previous snapshot. Returns `Ok(None)` when the file does not exist.
+    pub fn load<S: DeserializeOwned>(&self) -> Result<Option<S>, ClapMcpError> {
+        let bytes = match std::fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Some(serde_json::from_slice(&bytes)?))
+    }
+
+    /// Like [`Self::load`], falling back to `S::default()` when no snapshot exists.
+    pub fn load_or_default<S: DeserializeOwned + Default>(&self) -> Result<S, ClapMcpError> {
+        Ok(self.load()?.unwrap_or_default())
+    }
+
+    /// Write `state` to [`Self::path`].
+    pub fn save<S: Serialize + ?Sized>(&self, state: &S) -> Result<(), ClapMcpError> {
+        write_snapshot(&self.path, &serde_json::to_value(state)?)
+    }
+
+    /// Attach the live session state so the server can snapshot it.
+    ///
+    /// Pass the same `Arc` given to the stateful entrypoint. `S` is the executor's
+    /// [`crate::ClapMcpToolExecutorWithState::State`] (for example `Mutex<CounterState>`; serde
+    /// serializes a `Mutex` by locking it).
+    pub fn bind<S>(self, state: Arc<S>) -> PersistedState
+    where
+        S: Serialize + Send + Sync + 'static,
+    {
+        PersistedState {
+            persistence: self,
+            snapshot: Arc::new(move || serde_json::to_value(state.as_ref())),
+        }
+    }
+}
+
+type SnapshotFn = Arc<dyn Fn() -> Result<serde_json::Value, serde_json::Error> + Send + Sync>;
+
+/// [`StatePersistence`] bound to live session state; set on
+/// [`crate::ClapMcpServeOptions::state_persistence`].
+#[derive(Clone)]
+pub struct PersistedState {
+    persistence: StatePersistence,
+    snapshot: SnapshotFn,
+}
+
+impl std::fmt::Debug for PersistedState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersistedState")
+            .field("persistence", &self.persistence)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PersistedState {
+    /// Snapshot file and interval.
+    pub fn persistence(&self) -> &StatePersistence {
+        &self.persistence
+    }
+
+    /// Serialize the current state to JSON.
+    pub fn snapshot(&self) -> Result<serde_json::Value, ClapMcpError> {
+        Ok((self.snapshot)()?)
+    }
+
+    /// Serialize the current state and write it to the snapshot file.
+    pub fn save(&self) -> Result<(), ClapMcpError> {
+        write_snapshot(&self.persistence.path, &self.snapshot()?)
+    }
+
+    /// Run `serve` while snapshotting periodically; save once more when it returns or on
+    /// SIGINT / SIGTERM.
+    pub(crate) async fn run_with_snapshots<F>(&self, serve: F) -> Result<(), ClapMcpError>
+    where
+        F: std::future::Future<Output = Result<(), ClapMcpError>>,
+    {
+        let periodic = self.persistence.snapshot_interval.map(|interval| {
+            let persisted = self.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.tick().await;
+                loop {
+                    ticker.tick().await;
+                    if let Err(e) = persisted.save() {
+                        eprintln!("clap-mcp: failed to snapshot session state: {e}");
+                    }
+                }
+            })
+        });
+        let result = tokio::select! {
+            result = serve => result,
+            () = shutdown_signal() => Ok(()),
+        };
+        if let Some(handle) = periodic {
+            handle.abort();
+        }
+        let saved = self.save();
+        result.and(saved)
+    }
+}
+
+fn write_snapshot(path: &Path, value: &serde_json::Value) -> Result<(), ClapMcpError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, serde_json::to_vec_pretty(value)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Resolves on ctrl-c, or SIGTERM on Unix.
+pub(crate) async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = term.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StatePersistence;
+    use serde::{Deserialize, Serialize};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Counter {
+        count: u64,
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "clap-mcp-state-{}-{}.json",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn load_missing_snapshot_returns_none_or_default() {
+        let persistence = StatePersistence::new(temp_path("missing"));
+        let _ = std::fs::remove_file(&persistence.path);
+        assert!(persistence.load::<Counter>().unwrap().is_none());
+        assert_eq!(
+            persistence.load_or_default::<Counter>().unwrap(),
+            Counter::default()
+        );
+    }
+
+    #[test]
+    fn bound_state_round_trips_through_snapshot_file() {
+        let persistence = StatePersistence::new(temp_path("roundtrip"));
+        let state = Arc::new(Mutex::new(Counter { count: 3 }));
+        let persisted = persistence.clone().bind(Arc::clone(&state));
+        state.lock().unwrap().count = 7;
+        assert_eq!(
+            persisted.snapshot().unwrap(),
+            serde_json::json!({ "count": 7 })
+        );
+        persisted.save().unwrap();
+
+        let reloaded: Mutex<Counter> = persistence.load_or_default().unwrap();
+        assert_eq!(reloaded.into_inner().unwrap(), Counter { count: 7 });
+        std::fs::remove_file(&persistence.path).unwrap();
+    }
+}
@@ -0,0 +1,115 @@
+//! Protects the stdio JSON-RPC stream from stray writes to stdout.
+//!
+//! In stdio mode the protocol shares fd 1 with everything else in the process, so a `println!`
+//! from an in-process tool (or any library it calls) lands in the middle of the JSON-RPC stream.
+//! [`StdioGuard::install`] moves the protocol onto a private duplicate of fd 1 and points fd 1
+//! at a pipe. A reader thread turns whatever arrives on that pipe into lines for the server to
+//! forward as log notifications (or to stderr). `capture_stdout` still attributes output to
+//! the active call: it redirects fd 1 for the duration of the call and restores the pipe.
+
+use std::os::unix::io::{FromRawFd, RawFd};
+
+/// Lines written to fd 1 while the guard was installed.
+pub(crate) type StrayStdoutReceiver = tokio::sync::mpsc::UnboundedReceiver<String>;
+
+/// Logger name used for forwarded stray stdout lines.
+pub(crate) const STRAY_STDOUT_LOGGER: &str = "stdout";
+
+/// Keeps fd 1 redirected to the stray-output pipe until dropped.
+pub(crate) struct StdioGuard {
+    protocol_fd: RawFd,
+}
+
+impl StdioGuard {
+    /// Duplicate fd 1 for the protocol and redirect fd 1 to a pipe.
+    ///
+    /// Returns the guard, the protocol writer, and a receiver of stray stdout lines. On error
+    /// nothing has been changed and the caller should serve on the process stdio as before.
+    pub(crate) fn install() -> std::io::Result<(Self, tokio::fs::File, StrayStdoutReceiver)> {
+        use std::io::Write;
+
+        let _ = std::io::stdout().flush();
+        // SAFETY: every fd below is either freshly created by fcntl/pipe or is STDOUT_FILENO.
+        // fd 1 is only redirected as the last step, so each earlier error path just closes what
+        // it created. After dup2 the pipe's write end lives only at fd 1; the read end is owned
+        // by the reader thread's File.
+        let protocol_fd = unsafe { libc::fcntl(libc::STDOUT_FILENO, libc::F_DUPFD_CLOEXEC, 3) };
+        if protocol_fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let protocol_dup = unsafe { libc::fcntl(protocol_fd, libc::F_DUPFD_CLOEXEC, 3) };
+        if protocol_dup < 0 {
+            let err = std::io::Error::last_os_error();
+            unsafe { libc::close(protocol_fd) };
+            return Err(err);
+        }
+        let protocol = unsafe { std::fs::File::from_raw_fd(protocol_dup) };
+        let mut fds: [libc::c_int; 2] = [0, 0];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            let err = std::io::Error::last_os_error();
+            unsafe { libc::close(protocol_fd) };
+            return Err(err);
+        }
+        let (read_fd, write_fd) = (fds[0], fds[1]);
+        unsafe { libc::fcntl(read_fd, libc::F_SETFD, libc::FD_CLOEXEC) };
+        let reader = unsafe { std::fs::File::from_raw_fd(read_fd) };
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        if let Err(err) = std::thread::Builder::new()
+            .name("clap-mcp-stdout".into())
+            .spawn(move || forward_lines(reader, tx))
+        {
+            unsafe {
+                libc::close(protocol_fd);
+                libc::close(write_fd);
+            }
+            return Err(err);
+        }
+        // On failure, closing write_fd hands the reader thread EOF so it exits.
+        let redirected = unsafe { libc::dup2(write_fd, libc::STDOUT_FILENO) };
+        let err = std::io::Error::last_os_error();
+        unsafe { libc::close(write_fd) };
+        if redirected < 0 {
+            unsafe { libc::close(protocol_fd) };
+            return Err(err);
+        }
+        Ok((
+            Self { protocol_fd },
+            tokio::fs::File::from_std(protocol),
+            rx,
+        ))
+    }
+}
+
+impl Drop for StdioGuard {
+    /// Point fd 1 back at the original stdout. Closing the pipe's write end ends the reader.
+    fn drop(&mut self) {
+        use std::io::Write;
+
+        let _ = std::io::stdout().flush();
+        unsafe {
+            libc::dup2(self.protocol_fd, libc::STDOUT_FILENO);
+            libc::close(self.protocol_fd);
+        }
+    }
+}
+
+fn forward_lines(reader: std::fs::File, tx: tokio::sync::mpsc::UnboundedSender<String>) {
+    use std::io::BufRead;
+
+    let mut reader = std::io::BufReader::new(reader);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buf);
+                let line = line.trim_end_matches(['\n', '\r']);
+                if !line.is_empty() && tx.send(line.to_string()).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,343 @@
+//! Status updates from inside task-augmented tools (SEP-2663 tasks).
+//!
+//! While a task-augmented `tools/call` runs, [`ClapMcpTask::current`](crate::tasks::ClapMcpTask::current)
+//! returns a handle for that task. Status messages and progress set through it are stored on
+//! the task (so `tasks/get` reports them) and pushed to the client as `notifications/tasks`.
+//! [`set_status_message`](crate::tasks::set_status_message) and
+//! [`set_progress`](crate::tasks::set_progress) wrap it for code shared with the normal CLI:
+//! outside a task they do nothing.
+//!
+//! [`ClapMcpTask::request_input`](crate::tasks::ClapMcpTask::request_input) pauses the task as
+//! `input_required` until the client answers a form through `tasks/update`, for multi-step
+//! subcommands (pick a branch, confirm a plan) without eliciting on the original request.
+//!
+//! Works from sync `run` and inside [`crate::run_async_tool`]. Progress is reported in the
+//! status message (`"Indexing (40%)"`) because tasks carry no progress field. Subprocess tools
+//! have no handle: each line the child writes to stderr becomes the task's status message.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! fn reindex(files: &[PathBuf]) {
+//!     for (i, file) in files.iter().enumerate() {
+//!         clap_mcp::tasks::set_progress((i * 100 / files.len()) as u8, Some(&format!("Indexing {}", file.display())));
+//!         index(file);
+//!     }
+//! }
+//!
+//! #[derive(serde::Deserialize)]
+//! struct Choice {
+//!     branch: String,
+//! }
+//!
+//! async fn pick_branch(task: &ClapMcpTask) -> Result<Option<String>, ClapMcpError> {
+//!     let branches = EnumSchema::builder(vec!["main".into(), "next".into()]).build();
+//!     let schema = ElicitationSchema::builder()
+//!         .required_property("branch", PrimitiveSchemaDefinition::Enum(branches))
+//!         .build_unchecked();
+//!     Ok(match task.request_input::<Choice>("Which branch?", schema).await? {
+//!         TaskInput::Accepted(choice) => Some(choice.branch),
+//!         TaskInput::Declined | TaskInput::Cancelled => None,
+//!     })
+//! }
+//! ```
+
+use crate::{ClapMcpError, call_context::CallHandles};
+use rmcp::{
+    Peer,
+    model::{
+        ElicitRequestParams, ElicitResult, ElicitationAction, ElicitationSchema, InputRequest,
+        Request, ServerNotification, TaskStatusNotification, TaskStatusNotificationParams,
+    },
+    service::RoleServer,
+    task_manager::{TaskContext, TaskExit, TaskManager},
+};
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// The task-augmented call running the current tool body. Cloning shares the task.
+#[derive(Clone)]
+pub struct ClapMcpTask {
+    context: TaskContext,
+    status: Arc<Mutex<TaskStatusParts>>,
+    updated: mpsc::UnboundedSender<()>,
+    /// Set when clap-mcp stopped the tool for `tasks/cancel` (a killed subprocess, or a
+    /// cancelled [`request_input`](Self::request_input)).
+    stopped: Arc<AtomicBool>,
+    /// Input requests made so far; keys must be unique for the task's lifetime.
+    input_requests: Arc<AtomicUsize>,
+}
+
+/// The client's answer to [`ClapMcpTask::request_input`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskInput<T> {
+    /// The user submitted the form; the content deserialized as `T`.
+    Accepted(T),
+    /// The user explicitly declined to answer.
+    Declined,
+    /// The user dismissed the form without choosing.
+    Cancelled,
+}
+
+/// Status message and progress, combined into the task's `statusMessage`.
+#[derive(Default)]
+struct TaskStatusParts {
+    message: Option<String>,
+    percent: Option<u8>,
+}
+
+impl TaskStatusParts {
+    fn render(&self) -> String {
+        match (&self.message, self.percent) {
+            (Some(message), Some(percent)) => format!("{message} ({percent}%)"),
+            (Some(message), None) => message.clone(),
+            (None, Some(percent)) => format!("{percent}%"),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for ClapMcpTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClapMcpTask")
+            .field("task_id", &self.task_id())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ClapMcpTask {
+    /// The task for the current tool body, if it runs as a task-augmented call.
+    pub fn current() -> Option<Self> {
+        CallHandles::current().and_then(|handles| handles.task)
+    }
+
+    /// The task id the client polls with `tasks/get`.
+    pub fn task_id(&self) -> &str {
+        self.context.task_id()
+    }
+
+    /// Replace the task's status message, keeping any progress.
+    pub fn set_status_message(&self, message: impl Into<String>) {
+        self.update(|status| status.message = Some(message.into()));
+    }
+
+    /// Set progress (clamped to 100%) and, when `message` is given, the status message.
+    pub fn set_progress(&self, percent: u8, message: Option<&str>) {
+        self.update(|status| {
+            status.percent = Some(percent.min(100));
+            if let Some(message) = message {
+                status.message = Some(message.to_string());
+            }
+        });
+    }
+
+    /// Returns `true` once the client has sent `tasks/cancel` for this task.
+    pub fn is_cancel_requested(&self) -> bool {
+        self.context.is_cancel_requested()
+    }
+
+    /// Ask the client for input mid-task: the task reports `input_required` with a form
+    /// elicitation (`message` and `schema`) until the client answers it with `tasks/update`.
+    ///
+    /// The accepted form content is deserialized as `T`. If the client cancels the task while
+    /// it waits, this returns [`ClapMcpError::TaskInput`] and the task settles as `cancelled`
+    /// whatever the tool returns next.
+    pub async fn request_input<T: DeserializeOwned>(
+        &self,
+        message: impl Into<String>,
+        schema: ElicitationSchema,
+    ) -> Result<TaskInput<T>, ClapMcpError> {
+        let key = format!(
+            "input-{}",
+            self.input_requests.fetch_add(1, Ordering::SeqCst) + 1
+        );
+        let request =
+            InputRequest::Elicitation(Request::new(ElicitRequestParams::FormElicitationParams {
+                meta: None,
+                message: message.into(),
+                requested_schema: schema,
+            }));
+        let answer = self.context.request_input(key, request);
+        tokio::pin!(answer);
+        // The first poll registers the request; then tell the client the task needs input.
+        let answer = tokio::select! {
+            biased;
+            answer = &mut answer => answer,
+            _ = std::future::ready(()) => {
+                let _ = self.updated.send(());
+                answer.await
+            }
+        };
+        let _ = self.updated.send(());
+        let value = match answer {
+            Ok(value) => value,
+            Err(TaskExit::Cancelled) => {
+                self.mark_stopped();
+                return Err(ClapMcpError::TaskInput(
+                    "task was cancelled while waiting for input".into(),
+                ));
+            }
+            Err(TaskExit::Error(error)) => {
+                return Err(ClapMcpError::TaskInput(error.message.into()));
+            }
+        };
+        let result: ElicitResult = serde_json::from_value(value)
+            .map_err(|e| ClapMcpError::TaskInput(format!("invalid elicitation result: {e}")))?;
+        match result.action {
+            ElicitationAction::Accept => {
+                let content = result.content.unwrap_or(serde_json::Value::Null);
+                serde_json::from_value(content)
+                    .map(TaskInput::Accepted)
+                    .map_err(|e| ClapMcpError::TaskInput(format!("invalid input: {e}")))
+            }
+            ElicitationAction::Decline => Ok(TaskInput::Declined),
+            _ => Ok(TaskInput::Cancelled),
+        }
+    }
+
+    /// Resolves once the client has sent `tasks/cancel` for this task.
+    pub(crate) async fn cancelled(&self) {
+        self.context.cancelled().await;
+    }
+
+    /// Record that the tool was stopped for `tasks/cancel`; the task settles as `cancelled`.
+    pub(crate) fn mark_stopped(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn was_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    fn update(&self, f: impl FnOnce(&mut TaskStatusParts)) {
+        let rendered = {
+            let mut status = self.status.lock().unwrap_or_else(|e| e.into_inner());
+            f(&mut status);
+            status.render()
+        };
+        self.context.set_status_message(rendered);
+        let _ = self.updated.send(());
+    }
+
+    /// Handle for one task; status notifications go to `peer` until every clone is dropped.
+    pub(crate) fn start(
+        context: TaskContext,
+        manager: TaskManager,
+        peer: Peer<RoleServer>,
+    ) -> Self {
+        let (updated, mut updates) = mpsc::unbounded_channel::<()>();
+        let task_id = context.task_id().to_string();
+        tokio::spawn(async move {
+            while updates.recv().await.is_some() {
+                // Coalesce bursts: the notification carries the latest state anyway.
+                while updates.try_recv().is_ok() {}
+                let Ok(task) = manager.get_task(&task_id) else {
+                    break;
+                };
+                let notification =
+                    TaskStatusNotification::new(TaskStatusNotificationParams::new(task));
+                let _ = peer
+                    .send_notification(ServerNotification::TaskStatusNotification(notification))
+                    .await;
+            }
+        });
+        Self {
+            context,
+            status: Arc::default(),
+            updated,
+            stopped: Arc::default(),
+            input_requests: Arc::default(),
+        }
+    }
+}
+
+/// Set the current task's status message; does nothing outside a task-augmented call.
+pub fn set_status_message(message: impl Into<String>) {
+    if let Some(task) = ClapMcpTask::current() {
+        task.set_status_message(message);
+    }
+}
+
+/// Set the current task's progress (and optionally its message); does nothing outside a
+/// task-augmented call.
+pub fn set_progress(percent: u8, message: Option<&str>) {
+    if let Some(task) = ClapMcpTask::current() {
+        task.set_progress(percent, message);
+    }
+}
+
+/// Tasks the server created, oldest first: backs `clap://tasks` and
+/// [`TaskRetention::max_retained`](crate::TaskRetention::max_retained).
+#[derive(Clone, Default)]
+pub(crate) struct TaskIndex {
+    records: Arc<Mutex<VecDeque<TaskRecord>>>,
+    max_retained: Option<usize>,
+}
+
+struct TaskRecord {
+    task_id: String,
+    tool: String,
+}
+
+impl TaskIndex {
+    pub(crate) fn new(max_retained: Option<usize>) -> Self {
+        Self {
+            records: Arc::default(),
+            max_retained,
+        }
+    }
+
+    /// Record a new task, then forget the oldest finished tasks beyond `max_retained`.
+    pub(crate) fn insert(&self, manager: &TaskManager, task_id: String, tool: String) {
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records.push_back(TaskRecord { task_id, tool });
+        // Tasks evicted by the TTL sweep are gone from the manager already.
+        records.retain(|record| manager.get_task(&record.task_id).is_ok());
+        let Some(max) = self.max_retained else {
+            return;
+        };
+        while records.len() > max {
+            let finished = records.iter().position(|record| {
+                manager
+                    .get_task(&record.task_id)
+                    .is_ok_and(|task| task.status().is_terminal())
+            });
+            match finished {
+                Some(index) => {
+                    records.remove(index);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Whether `task_id` is still served by `tasks/get`, `tasks/update`, and `tasks/cancel`.
+    pub(crate) fn contains(&self, task_id: &str) -> bool {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .any(|record| record.task_id == task_id)
+    }
+
+    /// `clap://tasks` body: each retained task's state plus the tool it runs, oldest first.
+    pub(crate) fn list_json(&self, manager: &TaskManager) -> serde_json::Value {
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        let tasks = records
+            .iter()
+            .filter_map(|record| {
+                let task = manager.get_task(&record.task_id).ok()?.task;
+                let mut entry = match serde_json::to_value(task) {
+                    Ok(serde_json::Value::Object(entry)) => entry,
+                    _ => return None,
+                };
+                entry.insert("tool".into(), record.tool.clone().into());
+                Some(serde_json::Value::Object(entry))
+            })
+            .collect::<Vec<_>>();
+        serde_json::json!({ "tasks": tasks })
+    }
+}
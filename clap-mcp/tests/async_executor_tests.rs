@@ -0,0 +1,156 @@
+//! Tests for `#[clap_mcp_output_from_async]` / [`clap_mcp::ClapMcpAsyncToolExecutor`].
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, ClapMcpAsyncToolExecutor, McpListen, ServeMcpBuilder};
+use common::{
+    TasksClientHandler, call_tool_task, connect_duplex, get_task_payload, poll_until_completed,
+    shutdown_duplex, task_call_params, tool_text,
+};
+use rmcp::RoleClient;
+use rmcp::model::{CallToolRequestParams, CallToolResult};
+use rmcp::service::RunningService;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(
+    reinvocation_safe,
+    parallel_safe,
+    catch_in_process_panics,
+    task_augmented_tools
+)]
+#[clap_mcp_output_from_async = "run_async_test"]
+#[command(name = "async-test-cli")]
+enum AsyncTestCli {
+    Greet {
+        #[arg(long)]
+        name: String,
+    },
+    #[clap_mcp(serialized)]
+    Exclusive,
+    #[clap_mcp(task)]
+    TaskId,
+    Boom,
+}
+
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+static MAX_IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+async fn run_async_test(cmd: AsyncTestCli) -> Result<String, String> {
+    match cmd {
+        AsyncTestCli::Greet { name } => {
+            tokio::task::yield_now().await;
+            Ok(format!("hello {name}"))
+        }
+        AsyncTestCli::Exclusive => {
+            let now = IN_FLIGHT.fetch_add(1, Ordering::SeqCst) + 1;
+            MAX_IN_FLIGHT.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+            Ok("exclusive done".to_string())
+        }
+        AsyncTestCli::TaskId => {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            clap_mcp::logging::current_mcp_task_id().ok_or_else(|| "no task id".to_string())
+        }
+        AsyncTestCli::Boom => {
+            tokio::task::yield_now().await;
+            panic!("async boom")
+        }
+    }
+}
+
+async fn serve_async_test() -> (
+    RunningService<RoleClient, TasksClientHandler>,
+    tokio::task::JoinHandle<()>,
+) {
+    connect_duplex(
+        ServeMcpBuilder::for_cli_async::<AsyncTestCli>(McpListen::Stdio),
+        TasksClientHandler,
+    )
+    .await
+}
+
+async fn call(
+    client: &RunningService<RoleClient, TasksClientHandler>,
+    name: &'static str,
+    args: serde_json::Value,
+) -> CallToolResult {
+    let args = args.as_object().cloned().unwrap_or_default();
+    client
+        .call_tool(CallToolRequestParams::new(name).with_arguments(args))
+        .await
+        .expect("tool call should return a result")
+}
+
+#[tokio::test]
+async fn execute_for_mcp_async_awaits_run() {
+    let output = AsyncTestCli::Greet {
+        name: "direct".into(),
+    }
+    .execute_for_mcp_async()
+    .await
+    .expect("run should succeed");
+    assert_eq!(output.into_string(), "hello direct");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn async_tools_run_on_server_runtime_with_panics_caught() {
+    let (client, server) = serve_async_test().await;
+
+    let greet = call(&client, "greet", serde_json::json!({ "name": "mcp" })).await;
+    assert_eq!(greet.is_error, Some(false));
+    assert_eq!(tool_text(&greet), "hello mcp");
+
+    let boom = call(&client, "boom", serde_json::json!({})).await;
+    assert_eq!(boom.is_error, Some(true));
+    assert!(
+        tool_text(&boom).contains("async boom"),
+        "got: {}",
+        tool_text(&boom)
+    );
+
+    let again = call(&client, "greet", serde_json::json!({ "name": "again" })).await;
+    assert_eq!(tool_text(&again), "hello again");
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn serialized_async_tool_calls_do_not_overlap() {
+    let (client, server) = serve_async_test().await;
+
+    let (a, b) = tokio::join!(
+        call(&client, "exclusive", serde_json::json!({})),
+        call(&client, "exclusive", serde_json::json!({})),
+    );
+    assert_eq!(tool_text(&a), "exclusive done");
+    assert_eq!(tool_text(&b), "exclusive done");
+    assert_eq!(MAX_IN_FLIGHT.load(Ordering::SeqCst), 1);
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn async_task_tool_sees_its_task_id() {
+    let (client, server) = serve_async_test().await;
+    let peer = client.peer();
+
+    let create = call_tool_task(peer, task_call_params("task-id", serde_json::Map::new()))
+        .await
+        .expect("task should be created");
+    let task_id = create.task.task_id.clone();
+    poll_until_completed(peer, &task_id)
+        .await
+        .expect("task should complete");
+    let payload = get_task_payload(peer, &task_id)
+        .await
+        .expect("task payload");
+    let result: CallToolResult = serde_json::from_value(payload).expect("call tool result");
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(tool_text(&result), task_id);
+
+    shutdown_duplex(client, server).await;
+}
@@ -2,14 +2,15 @@
 
 #![allow(dead_code)]
 
+use clap_mcp::ServeMcpBuilder;
 use rmcp::{
     ClientHandler, RoleClient, ServiceExt,
     model::{
-        CallToolRequestParams, CallToolResult, ClientCapabilities, ClientInfo, ClientRequest,
+        CallToolRequestParams, CallToolResult, ClientCapabilities, ClientConfig, ClientRequest,
         ContentBlock, CreateTaskResult, GetTaskParams, GetTaskResult, Implementation,
         ReadResourceResult, Request, ResourceContents, ServerResult, TaskPayload, TaskStatus,
     },
-    service::Peer,
+    service::{Peer, RunningService},
     transport::{ConfigureCommandExt, TokioChildProcess},
 };
 use std::path::Path;
@@ -49,8 +50,8 @@ impl ClientHandler for NoOpHandler {}
 pub struct TasksClientHandler;
 
 impl ClientHandler for TasksClientHandler {
-    fn get_info(&self) -> ClientInfo {
-        ClientInfo::new(
+    fn get_info(&self) -> ClientConfig {
+        ClientConfig::new(
             ClientCapabilities::builder().enable_tasks().build(),
             Implementation::from_build_env(),
         )
@@ -74,6 +75,16 @@ pub async fn launch_example_with_stdio_flag(
     extra_args: &[&str],
     features: Option<&str>,
     stdio_flag: &str,
+) -> Result<ExampleClient, rmcp::RmcpError> {
+    launch_example_with_env(bin, extra_args, features, stdio_flag, &[]).await
+}
+
+pub async fn launch_example_with_env(
+    bin: &str,
+    extra_args: &[&str],
+    features: Option<&str>,
+    stdio_flag: &str,
+    envs: &[(&str, &std::ffi::OsStr)],
 ) -> Result<ExampleClient, rmcp::RmcpError> {
     {
         let _guard = BUILD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
@@ -94,6 +105,9 @@ pub async fn launch_example_with_stdio_flag(
             for arg in extra_args {
                 cmd.arg(arg);
             }
+            for (key, value) in envs {
+                cmd.env(key, value);
+            }
             cmd.arg(stdio_flag);
         }),
     )
@@ -150,6 +164,35 @@ pub async fn shutdown<H: ClientHandler>(client: rmcp::service::RunningService<Ro
     let _ = client.cancel().await;
 }
 
+/// Serves `builder` over an in-memory pipe and connects `handler` to it as the client. The
+/// server task panics if `serve` fails.
+pub async fn connect_duplex<H: ClientHandler>(
+    builder: ServeMcpBuilder,
+    handler: H,
+) -> (RunningService<RoleClient, H>, tokio::task::JoinHandle<()>) {
+    let (io1, io2) = tokio::io::duplex(8192);
+    let (server_read, server_write) = tokio::io::split(io1);
+    let builder = builder.stdio_io(server_read, server_write);
+    let server = tokio::spawn(async move {
+        builder.serve().await.expect("test server should start");
+    });
+    let client = handler
+        .serve(tokio::io::split(io2))
+        .await
+        .expect("test client should connect");
+    (client, server)
+}
+
+/// Cancels a [`connect_duplex`] client and stops its server.
+pub async fn shutdown_duplex<H: ClientHandler>(
+    client: RunningService<RoleClient, H>,
+    server: tokio::task::JoinHandle<()>,
+) {
+    let _ = client.cancel().await;
+    server.abort();
+    let _ = server.await;
+}
+
 /// Params for a task-eligible `tools/call` (SEP-2663 is server-directed; no client task hint).
 pub fn task_call_params(
     name: impl Into<String>,
@@ -0,0 +1,301 @@
+//! Tests for `completion/complete`: tool args (possible values, `complete_with`, path hints
+//! under client roots), custom prompt arguments, and resource template placeholders.
+
+// Roots remain functional in rmcp 3.x but are deprecated by SEP-2577.
+#![allow(deprecated)]
+
+mod common;
+
+use async_trait::async_trait;
+use clap::{Parser, ValueEnum, ValueHint};
+use clap_mcp::content::{
+    CompletionProvider, CompletionReference, CustomPrompt, CustomResourceTemplate, PromptContent,
+    ResourceContent,
+};
+use clap_mcp::{ArgCompletionRequest, ClapMcp, ClapMcpServeOptions, McpListen, ServeMcpBuilder};
+use common::{connect_duplex, shutdown_duplex};
+use rmcp::model::{
+    ClientCapabilities, ClientInfo, CompletionContext, CompletionInfo, Implementation,
+    ListRootsResult, PromptArgument, Root,
+};
+use rmcp::service::{RequestContext, RunningService};
+use rmcp::{ClientHandler, ErrorData, RoleClient};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Profile {
+    Debug,
+    Release,
+    Bench,
+}
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false)]
+#[clap_mcp_output_from = "run_completion_test"]
+#[command(name = "completion-test-cli")]
+enum CompletionTestCli {
+    Build {
+        #[arg(long, value_enum)]
+        profile: Profile,
+        #[arg(long, value_hint = ValueHint::DirPath)]
+        out_dir: Option<PathBuf>,
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        manifest: Option<PathBuf>,
+        #[arg(long)]
+        #[clap_mcp(complete_with = "complete_targets")]
+        target: Option<String>,
+        #[arg(long)]
+        label: Option<String>,
+    },
+}
+
+fn run_completion_test(_cmd: CompletionTestCli) -> String {
+    String::new()
+}
+
+fn complete_targets(request: &ArgCompletionRequest<'_>) -> Vec<String> {
+    let os = request
+        .context
+        .get("profile")
+        .map(|p| if p == "bench" { "linux" } else { "darwin" })
+        .unwrap_or("windows");
+    [format!("x86_64-{os}"), format!("aarch64-{os}")]
+        .into_iter()
+        .filter(|t| t.starts_with(request.value))
+        .collect()
+}
+
+struct Colors;
+
+#[async_trait]
+impl CompletionProvider for Colors {
+    async fn complete(
+        &self,
+        reference: CompletionReference<'_>,
+        argument: &str,
+        value: &str,
+        _context: &HashMap<String, String>,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let values = match reference {
+            CompletionReference::Prompt("paint") => vec!["red", "green", "grey"],
+            CompletionReference::ResourceTemplate("test://shade/{color}") => vec!["navy", "teal"],
+            _ => return Err(format!("unexpected {reference:?} {argument}").into()),
+        };
+        Ok(values
+            .into_iter()
+            .filter(|v| v.starts_with(value))
+            .map(String::from)
+            .collect())
+    }
+}
+
+#[derive(Clone, Default)]
+struct RootsHandler {
+    roots: Vec<String>,
+}
+
+impl ClientHandler for RootsHandler {
+    fn get_info(&self) -> ClientInfo {
+        let capabilities = if self.roots.is_empty() {
+            ClientCapabilities::default()
+        } else {
+            ClientCapabilities::builder().enable_roots().build()
+        };
+        ClientInfo::new(capabilities, Implementation::from_build_env())
+    }
+
+    async fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<ListRootsResult, ErrorData> {
+        Ok(ListRootsResult::new(
+            self.roots.iter().map(Root::new).collect(),
+        ))
+    }
+}
+
+async fn connect(
+    handler: RootsHandler,
+) -> (
+    RunningService<RoleClient, RootsHandler>,
+    tokio::task::JoinHandle<()>,
+) {
+    let mut serve_options = ClapMcpServeOptions::default();
+    serve_options.custom_prompts.push(CustomPrompt {
+        name: "paint".into(),
+        title: None,
+        description: None,
+        arguments: vec![PromptArgument::new("color")],
+        content: PromptContent::Static(vec![]),
+    });
+    serve_options
+        .custom_resource_templates
+        .push(CustomResourceTemplate {
+            uri_template: "test://shade/{color}".into(),
+            name: "shade".into(),
+            title: None,
+            description: None,
+            mime_type: None,
+            content: ResourceContent::Static("{color}".into()),
+        });
+    serve_options.completion_provider = Some(Arc::new(Colors));
+
+    let builder = ServeMcpBuilder::for_cli::<CompletionTestCli>(McpListen::Stdio)
+        .serve_options(serve_options);
+    connect_duplex(builder, handler).await
+}
+
+async fn complete_build(
+    client: &RunningService<RoleClient, RootsHandler>,
+    arg: &str,
+    value: &str,
+) -> CompletionInfo {
+    client
+        .complete_prompt_argument("build", arg, value, None)
+        .await
+        .expect("tool arg completion should succeed")
+}
+
+fn temp_root(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "clap-mcp-completion-tests-{name}-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("assets")).unwrap();
+    std::fs::create_dir_all(dir.join("build")).unwrap();
+    std::fs::write(dir.join("Cargo.toml"), "").unwrap();
+    dir
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tool_args_complete_from_possible_values_and_complete_with() {
+    let (client, server) = connect(RootsHandler::default()).await;
+    let info = client.peer_info().expect("server info");
+    assert!(info.capabilities.completions.is_some());
+
+    assert_eq!(
+        complete_build(&client, "profile", "").await.values,
+        ["debug", "release", "bench"]
+    );
+    assert_eq!(
+        complete_build(&client, "profile", "re").await.values,
+        ["release"]
+    );
+    assert_eq!(
+        complete_build(&client, "target", "x86").await.values,
+        ["x86_64-windows"]
+    );
+    let with_context = client
+        .complete_prompt_argument(
+            "build",
+            "target",
+            "",
+            Some(CompletionContext::with_arguments(HashMap::from([(
+                "profile".to_string(),
+                "bench".to_string(),
+            )]))),
+        )
+        .await
+        .unwrap();
+    assert_eq!(with_context.values, ["x86_64-linux", "aarch64-linux"]);
+    assert!(complete_build(&client, "label", "").await.values.is_empty());
+
+    let err = client
+        .complete_prompt_argument("build", "nope", "", None)
+        .await
+        .expect_err("unknown tool arg should be rejected");
+    assert!(err.to_string().contains("unknown argument"), "{err}");
+    let err = client
+        .complete_prompt_argument("missing", "x", "", None)
+        .await
+        .expect_err("unknown prompt should be rejected");
+    assert!(err.to_string().contains("unknown prompt"), "{err}");
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn path_hints_complete_under_client_roots() {
+    let root = temp_root("roots");
+    let root_str = root.display().to_string();
+    let (client, server) = connect(RootsHandler {
+        roots: vec![format!("file://{root_str}")],
+    })
+    .await;
+
+    assert_eq!(
+        complete_build(&client, "out_dir", &format!("{root_str}/"))
+            .await
+            .values,
+        [format!("{root_str}/assets/"), format!("{root_str}/build/")]
+    );
+    assert_eq!(
+        complete_build(&client, "manifest", &format!("{root_str}/"))
+            .await
+            .values,
+        [
+            format!("{root_str}/Cargo.toml"),
+            format!("{root_str}/assets/"),
+            format!("{root_str}/build/"),
+        ]
+    );
+    assert_eq!(
+        complete_build(&client, "out_dir", "/").await.values,
+        [format!("{root_str}/")]
+    );
+    assert!(
+        complete_build(&client, "out_dir", "/etc/")
+            .await
+            .values
+            .is_empty()
+    );
+    shutdown_duplex(client, server).await;
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn path_hints_complete_nothing_without_roots() {
+    let root = temp_root("no-roots");
+    let (client, server) = connect(RootsHandler::default()).await;
+    let info = complete_build(&client, "out_dir", &format!("{}/", root.display())).await;
+    assert!(info.values.is_empty());
+    shutdown_duplex(client, server).await;
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn custom_prompts_and_templates_complete_through_provider() {
+    let (client, server) = connect(RootsHandler::default()).await;
+    let prompt = client
+        .complete_prompt_argument("paint", "color", "gr", None)
+        .await
+        .unwrap();
+    assert_eq!(prompt.values, ["green", "grey"]);
+    let template = client
+        .complete_resource_argument("test://shade/{color}", "color", "t", None)
+        .await
+        .unwrap();
+    assert_eq!(template.values, ["teal"]);
+
+    let err = client
+        .complete_prompt_argument("paint", "size", "", None)
+        .await
+        .expect_err("undeclared prompt argument should be rejected");
+    assert!(err.to_string().contains("unknown argument"), "{err}");
+    let err = client
+        .complete_resource_argument("test://shade/{color}", "size", "", None)
+        .await
+        .expect_err("unknown placeholder should be rejected");
+    assert!(err.to_string().contains("unknown argument"), "{err}");
+    let err = client
+        .complete_resource_argument("test://other/{id}", "id", "", None)
+        .await
+        .expect_err("unknown template should be rejected");
+    assert!(
+        err.to_string().contains("unknown resource template"),
+        "{err}"
+    );
+    shutdown_duplex(client, server).await;
+}
@@ -0,0 +1,127 @@
+//! Tests for `max_concurrency` semaphores and the queue timeout "server busy" error.
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::{
+    ClapMcp, ClapMcpConfigProvider, ClapMcpSchemaMetadataProvider, McpListen, ServeMcpBuilder,
+};
+use common::{NoOpHandler, connect_duplex, shutdown_duplex};
+use rmcp::RoleClient;
+use rmcp::model::{CallToolRequestParams, CallToolResult, ContentBlock};
+use rmcp::service::RunningService;
+use std::time::Duration;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe, queue_timeout_ms = 50)]
+#[clap_mcp_output_from = "run_limit_test"]
+#[command(name = "limit-test-cli")]
+enum LimitTestCli {
+    #[clap_mcp(max_concurrency = 1)]
+    Slow,
+    Fast,
+}
+
+fn run_limit_test(cmd: LimitTestCli) -> String {
+    match cmd {
+        LimitTestCli::Slow => {
+            std::thread::sleep(Duration::from_millis(400));
+            "slow done".to_string()
+        }
+        LimitTestCli::Fast => {
+            std::thread::sleep(Duration::from_millis(400));
+            "fast done".to_string()
+        }
+    }
+}
+
+async fn serve_limit_test(
+    global_cap: Option<usize>,
+) -> (
+    RunningService<RoleClient, NoOpHandler>,
+    tokio::task::JoinHandle<()>,
+) {
+    let mut config = LimitTestCli::clap_mcp_config();
+    config.max_concurrency = global_cap;
+    let builder = ServeMcpBuilder::for_cli::<LimitTestCli>(McpListen::Stdio).config(config);
+    connect_duplex(builder, NoOpHandler).await
+}
+
+fn text(result: &CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text(text) => Some(text.text.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn max_concurrency_attributes_populate_config_and_metadata() {
+    let config = LimitTestCli::clap_mcp_config();
+    assert_eq!(config.max_concurrency, None);
+    assert_eq!(config.queue_timeout, Some(Duration::from_millis(50)));
+    let metadata = LimitTestCli::clap_mcp_schema_metadata();
+    assert_eq!(metadata.tool_max_concurrency.get("slow"), Some(&1));
+    assert!(!metadata.tool_max_concurrency.contains_key("fast"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn per_tool_cap_returns_server_busy_after_queue_timeout() {
+    let (client, server) = serve_limit_test(None).await;
+
+    let tools = client.list_tools(None).await.expect("list tools").tools;
+    let slow = tools.iter().find(|t| t.name == "slow").expect("slow tool");
+    let meta = slow.meta.as_ref().expect("tool meta");
+    assert_eq!(meta.get("clapMcp").unwrap()["maxConcurrency"], 1);
+
+    let (a, b) = tokio::join!(
+        client.call_tool(CallToolRequestParams::new("slow")),
+        client.call_tool(CallToolRequestParams::new("slow")),
+    );
+    let results = [a.expect("first call"), b.expect("second call")];
+    let busy: Vec<_> = results
+        .iter()
+        .filter(|r| r.is_error == Some(true))
+        .collect();
+    assert_eq!(busy.len(), 1, "exactly one call should be rejected");
+    assert!(
+        text(busy[0]).contains("Server busy"),
+        "got: {}",
+        text(busy[0])
+    );
+
+    let (a, b) = tokio::join!(
+        client.call_tool(CallToolRequestParams::new("fast")),
+        client.call_tool(CallToolRequestParams::new("fast")),
+    );
+    assert_eq!(a.expect("fast a").is_error, Some(false));
+    assert_eq!(b.expect("fast b").is_error, Some(false));
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn global_cap_limits_calls_across_tools() {
+    let (client, server) = serve_limit_test(Some(1)).await;
+
+    let (a, b) = tokio::join!(
+        client.call_tool(CallToolRequestParams::new("fast")),
+        client.call_tool(CallToolRequestParams::new("slow")),
+    );
+    let results = [a.expect("fast call"), b.expect("slow call")];
+    let busy: Vec<_> = results
+        .iter()
+        .filter(|r| r.is_error == Some(true))
+        .collect();
+    assert_eq!(busy.len(), 1, "global cap should reject one call");
+    assert!(
+        text(busy[0]).contains("Server busy"),
+        "got: {}",
+        text(busy[0])
+    );
+
+    shutdown_duplex(client, server).await;
+}
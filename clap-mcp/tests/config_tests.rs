@@ -4,12 +4,12 @@ use clap::{Args, CommandFactory, Parser, Subcommand};
 use clap_mcp::AsStructured;
 use clap_mcp::ClapMcp;
 use clap_mcp::{
-    ClapMcpConfig, ClapMcpConfigProvider, ClapMcpError, ClapMcpRunnable, ClapMcpSchemaMetadata,
-    ClapMcpSchemaMetadataProvider, ClapMcpSerializeScope, ClapMcpToolExecutor, ClapMcpToolOutput,
-    LOG_INTERPRETATION_INSTRUCTIONS, LOGGING_GUIDE_CONTENT, McpListen, PROMPT_LOGGING_GUIDE,
-    ParseOrServeMcp, ServeMcpBuilder, argv_contains_clap_mcp_flags, run_async_tool,
-    schema_from_command, schema_from_command_with_metadata, serve_mcp,
-    tools_from_schema_with_metadata,
+    ClapMcpConfig, ClapMcpConfigProvider, ClapMcpError, ClapMcpExecutionMode, ClapMcpRunnable,
+    ClapMcpSchemaMetadata, ClapMcpSchemaMetadataProvider, ClapMcpSerializeScope,
+    ClapMcpToolExecutor, ClapMcpToolOutput, LOG_INTERPRETATION_INSTRUCTIONS, LOGGING_GUIDE_CONTENT,
+    McpListen, PROMPT_LOGGING_GUIDE, ParseOrServeMcp, ServeMcpBuilder,
+    argv_contains_clap_mcp_flags, run_async_tool, schema_from_command,
+    schema_from_command_with_metadata, serve_mcp, tools_from_schema_with_metadata,
 };
 use serde::Serialize;
 
@@ -291,6 +291,36 @@ fn test_default_derive_builtin_flags_unchanged() {
     assert_eq!(config.builtin_flags.stdio_long, clap_mcp::MCP_FLAG_LONG);
 }
 
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(
+    instructions = "Call `ping` before anything else.",
+    reinvocation_safe,
+    parallel_safe = false
+)]
+#[clap_mcp_output_from = "run_instructions"]
+#[command(name = "test-cli-instructions")]
+enum TestCliInstructions {
+    Ping,
+}
+
+fn run_instructions(cmd: TestCliInstructions) -> String {
+    match cmd {
+        TestCliInstructions::Ping => "pong".to_string(),
+    }
+}
+
+#[test]
+fn test_instructions_derive_sets_config() {
+    let config = TestCliInstructions::clap_mcp_config();
+    assert_eq!(
+        config.instructions.as_deref(),
+        Some("Call `ping` before anything else.")
+    );
+    // Keys after a `key = value` pair are still parsed.
+    assert!(config.reinvocation_safe);
+    assert!(TestCliDefaults::clap_mcp_config().instructions.is_none());
+}
+
 #[test]
 fn test_config_default() {
     let config = ClapMcpConfig::default();
@@ -402,6 +432,38 @@ fn test_task_augmented_meta_on_tools() {
     }
 }
 
+#[test]
+fn test_execution_mode_override_meta_on_tools() {
+    let mut metadata = ClapMcpSchemaMetadata {
+        task_augmented_tools: true,
+        ..Default::default()
+    };
+    metadata
+        .tool_execution_modes
+        .insert("test-cli".into(), ClapMcpExecutionMode::Subprocess);
+    let config = ClapMcpConfig {
+        reinvocation_safe: true,
+        ..Default::default()
+    };
+    let schema = schema_from_command(&TestCliDefaults::command());
+    let tools = tools_from_schema_with_metadata(&schema, &config, &metadata);
+    let tool = tools
+        .iter()
+        .find(|t| t.name == "test-cli")
+        .expect("root tool");
+    let obj = tool.meta.as_ref().expect("tool meta")["clapMcp"]
+        .as_object()
+        .expect("clapMcp object");
+    assert_eq!(
+        obj.get("reinvocationSafe").and_then(|v| v.as_bool()),
+        Some(false)
+    );
+    assert!(
+        obj.get("taskAugmented").is_none(),
+        "subprocess tools are not task-augmented"
+    );
+}
+
 #[test]
 fn test_tools_from_schema_with_config_meta() {
     let cmd = TestCliDefaults::command();
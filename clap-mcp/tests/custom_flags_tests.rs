@@ -12,6 +12,8 @@ fn default_flags_match_constants() {
     assert_eq!(flags.export_skills_long, EXPORT_SKILLS_FLAG_LONG);
     #[cfg(feature = "http")]
     assert_eq!(flags.http_long, clap_mcp::MCP_HTTP_FLAG_LONG);
+    #[cfg(all(unix, feature = "http"))]
+    assert_eq!(flags.unix_long, clap_mcp::MCP_UNIX_FLAG_LONG);
 }
 
 #[tokio::test(flavor = "current_thread")]
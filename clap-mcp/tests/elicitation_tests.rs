@@ -0,0 +1,314 @@
+//! Tests for `elicitation/create` during `tools/call`: `#[clap_mcp(elicit_missing)]` asks the
+//! user for omitted required arguments, `#[clap_mcp(confirm = "...")]` asks before running.
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, McpListen, ServeMcpBuilder};
+use common::{connect_duplex, shutdown_duplex};
+use rmcp::model::{
+    CallToolRequestParams, CallToolResult, ClientCapabilities, ClientConfig, ElicitRequestParams,
+    ElicitResult, ElicitationAction, Implementation,
+};
+use rmcp::service::{RequestContext, RunningService};
+use rmcp::{ClientHandler, ErrorData as McpError, RoleClient};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false)]
+#[clap_mcp_output_from = "run_elicit_test"]
+#[command(name = "elicit-test-cli")]
+enum ElicitTestCli {
+    #[clap_mcp(elicit_missing)]
+    Greet {
+        /// Who to greet
+        #[arg(long)]
+        name: String,
+        #[arg(long, value_parser = ["formal", "casual"])]
+        tone: String,
+    },
+    Plain {
+        #[arg(long)]
+        name: String,
+    },
+    #[clap_mcp(confirm = "Delete {name}?")]
+    Delete {
+        #[arg(long)]
+        name: String,
+    },
+    /// Runs `echo` (see [`connect`]), so the output is the rebuilt argv.
+    #[clap_mcp(subprocess, confirm = "Prune entries older than {older_than}?")]
+    Prune {
+        #[arg(long)]
+        older_than: String,
+    },
+}
+
+fn run_elicit_test(cmd: ElicitTestCli) -> String {
+    match cmd {
+        ElicitTestCli::Greet { name, tone } => format!("{tone} hello {name}"),
+        ElicitTestCli::Plain { name } => format!("hello {name}"),
+        ElicitTestCli::Delete { name } => format!("deleted {name}"),
+        ElicitTestCli::Prune { older_than } => format!("pruned {older_than}"),
+    }
+}
+
+/// Answers every form with `action` (and fixed content on accept), recording each request's
+/// message and schema. `None` does not declare the elicitation capability. With `gate`, each
+/// answer waits until the gate is notified.
+#[derive(Clone)]
+struct Answering {
+    action: Option<ElicitationAction>,
+    requests: Arc<Mutex<Vec<serde_json::Value>>>,
+    gate: Option<Arc<tokio::sync::Notify>>,
+}
+
+impl ClientHandler for Answering {
+    fn get_info(&self) -> ClientConfig {
+        let capabilities = if self.action.is_some() {
+            ClientCapabilities::builder().enable_elicitation().build()
+        } else {
+            ClientCapabilities::default()
+        };
+        ClientConfig::new(capabilities, Implementation::from_build_env())
+    }
+
+    async fn create_elicitation(
+        &self,
+        request: ElicitRequestParams,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<ElicitResult, McpError> {
+        if let ElicitRequestParams::FormElicitationParams {
+            message,
+            requested_schema,
+            ..
+        } = request
+        {
+            self.requests
+                .lock()
+                .expect("requests lock")
+                .push(serde_json::json!({ "message": message, "schema": requested_schema }));
+        }
+        if let Some(gate) = &self.gate {
+            gate.notified().await;
+        }
+        let action = self.action.clone().unwrap_or(ElicitationAction::Decline);
+        let result = ElicitResult::new(action.clone());
+        Ok(match action {
+            ElicitationAction::Accept => result.with_content(
+                serde_json::json!({ "name": "Ada", "tone": "casual", "confirm": true }),
+            ),
+            _ => result,
+        })
+    }
+}
+
+async fn connect(
+    action: Option<ElicitationAction>,
+) -> (
+    RunningService<RoleClient, Answering>,
+    Arc<Mutex<Vec<serde_json::Value>>>,
+    tokio::task::JoinHandle<()>,
+) {
+    connect_gated(action, None).await
+}
+
+async fn connect_gated(
+    action: Option<ElicitationAction>,
+    gate: Option<Arc<tokio::sync::Notify>>,
+) -> (
+    RunningService<RoleClient, Answering>,
+    Arc<Mutex<Vec<serde_json::Value>>>,
+    tokio::task::JoinHandle<()>,
+) {
+    let builder = ServeMcpBuilder::for_cli::<ElicitTestCli>(McpListen::Stdio)
+        .executable_path(Some("echo".into()));
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let handler = Answering {
+        action,
+        requests: requests.clone(),
+        gate,
+    };
+    let (client, server) = connect_duplex(builder, handler).await;
+    (client, requests, server)
+}
+
+fn call(name: &'static str, args: serde_json::Value) -> CallToolRequestParams {
+    CallToolRequestParams::new(name).with_arguments(args.as_object().cloned().expect("object"))
+}
+
+fn text(result: &CallToolResult) -> &str {
+    &result.content[0].as_text().expect("text").text
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn accepted_answers_fill_missing_args() {
+    let (client, requests, server) = connect(Some(ElicitationAction::Accept)).await;
+    let result = client
+        .call_tool(call("greet", serde_json::json!({ "name": "Grace" })))
+        .await
+        .expect("call greet");
+    assert_ne!(result.is_error, Some(true), "{result:?}");
+    assert_eq!(text(&result), "casual hello Grace");
+
+    let requests = requests.lock().expect("requests lock").clone();
+    assert_eq!(requests.len(), 1);
+    let schema = &requests[0]["schema"];
+    assert_eq!(schema["required"], serde_json::json!(["tone"]));
+    assert_eq!(
+        schema["properties"]["tone"]["enum"],
+        serde_json::json!(["formal", "casual"])
+    );
+    assert!(schema["properties"].get("name").is_none());
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn decline_and_cancel_are_tool_errors() {
+    for (action, verb) in [
+        (ElicitationAction::Decline, "declined"),
+        (ElicitationAction::Cancel, "cancelled"),
+    ] {
+        let (client, _requests, server) = connect(Some(action)).await;
+        let result = client
+            .call_tool(call("greet", serde_json::json!({})))
+            .await
+            .expect("call greet");
+        assert_eq!(result.is_error, Some(true));
+        assert!(text(&result).contains(verb), "{}", text(&result));
+        assert!(text(&result).contains("name, tone"), "{}", text(&result));
+        shutdown_duplex(client, server).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tools_without_opt_in_report_missing_args() {
+    let (client, requests, server) = connect(Some(ElicitationAction::Accept)).await;
+    let result = client
+        .call_tool(call("plain", serde_json::json!({})))
+        .await
+        .expect("call plain");
+    assert_eq!(result.is_error, Some(true));
+    assert!(
+        text(&result).contains("Missing required argument(s): name"),
+        "{}",
+        text(&result)
+    );
+    assert!(requests.lock().expect("requests lock").is_empty());
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn confirmed_calls_run_with_rendered_message() {
+    let (client, requests, server) = connect(Some(ElicitationAction::Accept)).await;
+    let result = client
+        .call_tool(call("delete", serde_json::json!({ "name": "cache" })))
+        .await
+        .expect("call delete");
+    assert_ne!(result.is_error, Some(true), "{result:?}");
+    assert_eq!(text(&result), "deleted cache");
+
+    let requests = requests.lock().expect("requests lock").clone();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0]["message"], "Delete cache?");
+    assert_eq!(
+        requests[0]["schema"]["properties"]["confirm"]["type"],
+        "boolean"
+    );
+    shutdown_duplex(client, server).await;
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn subprocess_tools_are_confirmed_before_launch() {
+    let (client, requests, server) = connect(Some(ElicitationAction::Decline)).await;
+    let result = client
+        .call_tool(call("prune", serde_json::json!({ "older_than": "30d" })))
+        .await
+        .expect("call prune");
+    assert_eq!(result.is_error, Some(true));
+    assert!(
+        text(&result).contains("did not confirm"),
+        "{}",
+        text(&result)
+    );
+    shutdown_duplex(client, server).await;
+
+    let (client, requests_ok, server) = connect(Some(ElicitationAction::Accept)).await;
+    let result = client
+        .call_tool(call("prune", serde_json::json!({ "older_than": "30d" })))
+        .await
+        .expect("call prune");
+    assert_ne!(result.is_error, Some(true), "{result:?}");
+    assert!(
+        text(&result).contains("prune --older-than 30d"),
+        "{}",
+        text(&result)
+    );
+    shutdown_duplex(client, server).await;
+
+    for requests in [requests, requests_ok] {
+        let requests = requests.lock().expect("requests lock").clone();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["message"], "Prune entries older than 30d?");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn confirmation_fails_closed_without_elicitation() {
+    let (client, _requests, server) = connect(None).await;
+    let result = client
+        .call_tool(call("delete", serde_json::json!({ "name": "cache" })))
+        .await
+        .expect("call delete");
+    assert_eq!(result.is_error, Some(true));
+    assert!(
+        text(&result).contains("does not support elicitation"),
+        "{}",
+        text(&result)
+    );
+
+    let plain = client
+        .call_tool(call("plain", serde_json::json!({ "name": "Ada" })))
+        .await
+        .expect("call plain");
+    assert_eq!(text(&plain), "hello Ada");
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pending_confirmation_does_not_block_other_calls() {
+    // `parallel_safe = false`: tools share one execution lock, which the confirmation
+    // round-trip must not hold while the user decides.
+    let gate = Arc::new(tokio::sync::Notify::new());
+    let (client, requests, server) =
+        connect_gated(Some(ElicitationAction::Accept), Some(gate.clone())).await;
+    let peer = client.peer().clone();
+    let pending = tokio::spawn(async move {
+        peer.call_tool(call("delete", serde_json::json!({ "name": "cache" })))
+            .await
+            .expect("call delete")
+    });
+    for _ in 0..250 {
+        if !requests.lock().expect("requests lock").is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(requests.lock().expect("requests lock").len(), 1);
+
+    let plain = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        client.call_tool(call("plain", serde_json::json!({ "name": "Ada" }))),
+    )
+    .await
+    .expect("plain call should not wait for the confirmation")
+    .expect("call plain");
+    assert_eq!(text(&plain), "hello Ada");
+
+    gate.notify_one();
+    let deleted = pending.await.expect("delete task");
+    assert_eq!(text(&deleted), "deleted cache");
+    shutdown_duplex(client, server).await;
+}
@@ -0,0 +1,168 @@
+//! Tests for [`clap_mcp::HttpAuth`]: requests without a valid bearer token get `401`, and a
+//! token's principal is visible to the tool as [`clap_mcp::HttpPrincipal::current`] or, for
+//! subprocess tools, [`clap_mcp::MCP_HTTP_PRINCIPAL_ENV`].
+#![cfg(feature = "http")]
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::{
+    ClapMcp, HttpAuth, HttpPrincipal, MCP_HTTP_PRINCIPAL_ENV, MCP_HTTP_TOKEN_ENV,
+    MCP_HTTP_TOKEN_FILE_ENV, McpListen, ServeMcpBuilder,
+};
+use common::{shutdown, tool_text};
+use rmcp::model::CallToolRequestParams;
+use rmcp::transport::StreamableHttpClientTransport;
+use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
+use rmcp::{ClientHandler, ServiceExt};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe)]
+#[clap_mcp_output_from = "run_http_auth_test"]
+#[command(name = "http-auth-test-cli")]
+enum HttpAuthTestCli {
+    Whoami,
+}
+
+fn run_http_auth_test(_cmd: HttpAuthTestCli) -> String {
+    HttpPrincipal::current().map_or_else(|| "anonymous".into(), |p| p.name().to_string())
+}
+
+#[derive(Clone, Default)]
+struct NoOpHandler;
+
+impl ClientHandler for NoOpHandler {}
+
+/// Serves in process, or through `executable` as a subprocess tool when set.
+async fn start_server(executable: Option<PathBuf>) -> (SocketAddr, tokio::task::JoinHandle<()>) {
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    let server = tokio::spawn(async move {
+        let mut builder = ServeMcpBuilder::for_cli::<HttpAuthTestCli>(McpListen::Http(addr))
+            .http_auth(HttpAuth::bearer("shared-token").with_principal("alice-token", "alice"));
+        if executable.is_some() {
+            builder = builder.in_process_handler(None).executable_path(executable);
+        }
+        let _ = builder.serve().await;
+    });
+    for _ in 0..100 {
+        if tokio::net::TcpStream::connect(addr).await.is_ok() {
+            return (addr, server);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("HTTP server not ready at {addr}");
+}
+
+async fn whoami(addr: SocketAddr, token: &str) -> String {
+    let config = StreamableHttpClientTransportConfig::with_uri(format!("http://{addr}/mcp"))
+        .auth_header(token);
+    let client = NoOpHandler
+        .serve(StreamableHttpClientTransport::from_config(config))
+        .await
+        .expect("connect with token");
+    let result = client
+        .call_tool(CallToolRequestParams::new("whoami"))
+        .await
+        .expect("call whoami");
+    shutdown(client).await;
+    tool_text(&result)
+}
+
+/// Raw `POST /mcp` so the refused response can be inspected; returns the response head.
+async fn post_initialize(addr: SocketAddr, authorization: Option<&str>) -> String {
+    let body = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#;
+    let authorization = authorization
+        .map(|value| format!("Authorization: {value}\r\n"))
+        .unwrap_or_default();
+    let request = format!(
+        "POST /mcp HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\n\
+         Accept: application/json, text/event-stream\r\n{authorization}\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn requests_without_a_valid_token_are_unauthorized() {
+    let (addr, server) = start_server(None).await;
+
+    let missing = post_initialize(addr, None).await;
+    assert!(missing.starts_with("HTTP/1.1 401"), "{missing}");
+    assert!(
+        missing
+            .to_ascii_lowercase()
+            .contains("www-authenticate: bearer realm=\"mcp\""),
+        "{missing}"
+    );
+
+    let wrong = post_initialize(addr, Some("Bearer not-a-token")).await;
+    assert!(wrong.starts_with("HTTP/1.1 401"), "{wrong}");
+    assert!(wrong.contains(r#"error="invalid_token""#), "{wrong}");
+
+    // Past the auth layer, MCP itself answers (here rejecting the bare `initialize`).
+    let accepted = post_initialize(addr, Some("Bearer shared-token")).await;
+    assert!(!accepted.starts_with("HTTP/1.1 401"), "{accepted}");
+
+    server.abort();
+    let _ = server.await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn token_principal_is_visible_to_tools() {
+    let (addr, server) = start_server(None).await;
+
+    assert_eq!(whoami(addr, "alice-token").await, "alice");
+    assert_eq!(whoami(addr, "shared-token").await, "anonymous");
+
+    server.abort();
+    let _ = server.await;
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn subprocess_tools_get_the_principal_but_not_server_secrets() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("clap-mcp-auth-env-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("whoami.sh");
+    std::fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\necho \"${{{MCP_HTTP_PRINCIPAL_ENV}:-anonymous}} \
+             ${{{MCP_HTTP_TOKEN_ENV}:-no-token}} ${{{MCP_HTTP_TOKEN_FILE_ENV}:-no-file}}\"\n"
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    // The server's auth is set in code, so these only matter to what the tool inherits.
+    unsafe {
+        std::env::set_var(MCP_HTTP_TOKEN_ENV, "server-secret");
+        std::env::set_var(MCP_HTTP_TOKEN_FILE_ENV, "/run/secrets/mcp-token");
+        std::env::set_var(MCP_HTTP_PRINCIPAL_ENV, "server-principal");
+    }
+    let (addr, server) = start_server(Some(script)).await;
+
+    assert_eq!(whoami(addr, "alice-token").await, "alice no-token no-file");
+    assert_eq!(
+        whoami(addr, "shared-token").await,
+        "anonymous no-token no-file"
+    );
+
+    server.abort();
+    let _ = server.await;
+    let _ = std::fs::remove_dir_all(&dir);
+}
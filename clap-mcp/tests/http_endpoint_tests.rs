@@ -0,0 +1,196 @@
+//! Tests for [`clap_mcp::HttpEndpoint`]: a custom route under a reverse-proxy base path,
+//! extra allowed hosts, and CORS for allowed browser origins.
+#![cfg(feature = "http")]
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, ClapMcpError, HttpAuth, HttpEndpoint, McpListen, ServeMcpBuilder};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe)]
+#[clap_mcp_output_from = "run_http_endpoint_test"]
+#[command(name = "http-endpoint-test-cli")]
+enum HttpEndpointTestCli {
+    Ping,
+}
+
+fn run_http_endpoint_test(_cmd: HttpEndpointTestCli) -> String {
+    "pong".into()
+}
+
+const INITIALIZE: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-11-25","capabilities":{},"clientInfo":{"name":"endpoint-test","version":"0"}}}"#;
+
+const ROUTE: &str = "/tools/myctl/rpc";
+
+fn endpoint() -> HttpEndpoint {
+    HttpEndpoint {
+        path: Some("/rpc".into()),
+        base_path: Some("/tools/myctl".into()),
+        allowed_hosts: vec!["myctl.local".into()],
+        allowed_origins: vec!["https://console.example.com".into()],
+    }
+}
+
+async fn start_server() -> (SocketAddr, tokio::task::JoinHandle<()>) {
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    let server = tokio::spawn(async move {
+        let _ = ServeMcpBuilder::for_cli::<HttpEndpointTestCli>(McpListen::Http(addr))
+            .http_endpoint(endpoint())
+            .http_auth(HttpAuth::bearer("secret"))
+            .serve()
+            .await;
+    });
+    for _ in 0..100 {
+        if tokio::net::TcpStream::connect(addr).await.is_ok() {
+            return (addr, server);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("HTTP server not ready at {addr}");
+}
+
+/// Sends a raw request with `headers` (and the `initialize` body for `POST`); returns the
+/// response with header names lowercased.
+async fn send(addr: SocketAddr, method: &str, path: &str, headers: &[&str]) -> String {
+    let body = if method == "POST" { INITIALIZE } else { "" };
+    let headers: String = headers.iter().map(|h| format!("{h}\r\n")).collect();
+    let request = format!(
+        "{method} {path} HTTP/1.1\r\n{headers}Content-Type: application/json\r\n\
+         Accept: application/json, text/event-stream\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+    format!("{}\r\n\r\n{body}", head.to_ascii_lowercase())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn custom_route_and_allowed_hosts() {
+    let (addr, server) = start_server().await;
+    let auth = "Authorization: Bearer secret";
+
+    let response = send(addr, "POST", ROUTE, &["Host: myctl.local", auth]).await;
+    assert!(response.starts_with("http/1.1 200"), "{response}");
+    let response = send(addr, "POST", ROUTE, &[&format!("Host: {addr}"), auth]).await;
+    assert!(response.starts_with("http/1.1 200"), "{response}");
+
+    let response = send(addr, "POST", "/mcp", &["Host: myctl.local", auth]).await;
+    assert!(response.starts_with("http/1.1 404"), "{response}");
+    let response = send(addr, "POST", ROUTE, &["Host: evil.example", auth]).await;
+    assert!(response.starts_with("http/1.1 403"), "{response}");
+
+    server.abort();
+    let _ = server.await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn allowed_origins_get_cors_headers() {
+    let (addr, server) = start_server().await;
+    let host = "Host: myctl.local";
+    let allowed = "Origin: https://console.example.com";
+
+    // Preflights carry no credentials, so they are answered before auth.
+    let preflight = send(
+        addr,
+        "OPTIONS",
+        ROUTE,
+        &[
+            host,
+            allowed,
+            "Access-Control-Request-Method: POST",
+            "Access-Control-Request-Headers: authorization, content-type",
+        ],
+    )
+    .await;
+    assert!(preflight.starts_with("http/1.1 204"), "{preflight}");
+    assert!(
+        preflight.contains("access-control-allow-origin: https://console.example.com"),
+        "{preflight}"
+    );
+    assert!(
+        preflight.contains("access-control-allow-methods: get, post, delete"),
+        "{preflight}"
+    );
+    assert!(preflight.contains("authorization"), "{preflight}");
+
+    let response = send(
+        addr,
+        "POST",
+        ROUTE,
+        &[host, allowed, "Authorization: Bearer secret"],
+    )
+    .await;
+    assert!(response.starts_with("http/1.1 200"), "{response}");
+    assert!(
+        response.contains("access-control-allow-origin: https://console.example.com"),
+        "{response}"
+    );
+    assert!(response.contains("mcp-session-id"), "{response}");
+
+    // A 401 still carries CORS headers, so the browser can show it.
+    let unauthorized = send(addr, "POST", ROUTE, &[host, allowed]).await;
+    assert!(unauthorized.starts_with("http/1.1 401"), "{unauthorized}");
+    assert!(
+        unauthorized.contains("access-control-allow-origin"),
+        "{unauthorized}"
+    );
+
+    let other = "Origin: https://evil.example";
+    let refused = send(
+        addr,
+        "POST",
+        ROUTE,
+        &[host, other, "Authorization: Bearer secret"],
+    )
+    .await;
+    assert!(refused.starts_with("http/1.1 403"), "{refused}");
+    assert!(
+        !refused.contains("access-control-allow-origin"),
+        "{refused}"
+    );
+    let preflight = send(
+        addr,
+        "OPTIONS",
+        ROUTE,
+        &[host, other, "Access-Control-Request-Method: POST"],
+    )
+    .await;
+    assert!(
+        !preflight.contains("access-control-allow-origin"),
+        "{preflight}"
+    );
+
+    server.abort();
+    let _ = server.await;
+}
+
+#[tokio::test]
+async fn invalid_endpoint_settings_are_rejected() {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+    for endpoint in [
+        HttpEndpoint {
+            path: Some("rpc".into()),
+            ..Default::default()
+        },
+        HttpEndpoint {
+            allowed_origins: vec!["console.example.com".into()],
+            ..Default::default()
+        },
+    ] {
+        let result = ServeMcpBuilder::for_cli::<HttpEndpointTestCli>(McpListen::Http(addr))
+            .http_endpoint(endpoint)
+            .serve()
+            .await;
+        assert!(matches!(result, Err(ClapMcpError::InvalidConfig(_))));
+    }
+}
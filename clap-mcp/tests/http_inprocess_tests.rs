@@ -295,3 +295,81 @@ async fn http_inprocess_unspecified_bind_accepts_localhost() {
     let connect = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), addr.port());
     exercise_inprocess_http(addr, connect).await;
 }
+
+/// Legacy-protocol client that records `notifications/resources/updated` URIs.
+#[derive(Clone)]
+struct UpdateCollector {
+    updates: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl ClientHandler for UpdateCollector {
+    fn get_info(&self) -> rmcp::model::ClientInfo {
+        rmcp::model::ClientInfo::new(
+            rmcp::model::ClientCapabilities::default(),
+            rmcp::model::Implementation::from_build_env(),
+        )
+        .with_protocol_version(rmcp::model::ProtocolVersion::V_2025_11_25)
+    }
+
+    async fn on_resource_updated(
+        &self,
+        params: rmcp::model::ResourceUpdatedNotificationParam,
+        _context: rmcp::service::NotificationContext<RoleClient>,
+    ) {
+        self.updates.lock().unwrap().push(params.uri);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn http_resource_updates_reach_only_subscribed_sessions() {
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let builder = ServeMcpBuilder::for_cli::<HttpTestCli>(McpListen::Http(addr));
+    let notifier = builder.resource_update_notifier();
+    let server = tokio::spawn(async move {
+        let _ = builder.serve().await;
+    });
+    wait_for_http(addr).await;
+
+    let uri = format!("http://{addr}/mcp");
+    let subscribed = UpdateCollector {
+        updates: Default::default(),
+    };
+    let other = UpdateCollector {
+        updates: Default::default(),
+    };
+    let subscribed_client = subscribed
+        .clone()
+        .serve(StreamableHttpClientTransport::from_uri(uri.clone()))
+        .await
+        .expect("connect subscribed");
+    let other_client = other
+        .clone()
+        .serve(StreamableHttpClientTransport::from_uri(uri))
+        .await
+        .expect("connect other");
+
+    subscribed_client
+        .subscribe(rmcp::model::SubscribeRequestParams::new("test://doc"))
+        .await
+        .expect("subscribe");
+    assert_eq!(notifier.notify("test://doc"), 1);
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while subscribed.updates.lock().unwrap().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("subscribed session should be notified");
+    assert_eq!(*subscribed.updates.lock().unwrap(), ["test://doc"]);
+    assert!(other.updates.lock().unwrap().is_empty());
+
+    shutdown(subscribed_client).await;
+    shutdown(other_client).await;
+    server.abort();
+}
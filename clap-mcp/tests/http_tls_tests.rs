@@ -0,0 +1,191 @@
+//! Tests for [`clap_mcp::HttpTls`]: MCP over HTTPS keeps the allowed-host check, and a
+//! configured client CA turns away clients without a matching certificate.
+#![cfg(feature = "http-tls")]
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, HttpTls, McpListen, ServeMcpBuilder};
+use rcgen::{
+    BasicConstraints, CertificateParams, CertifiedIssuer, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_rustls::TlsConnector;
+use tokio_rustls::rustls::{
+    ClientConfig, RootCertStore,
+    crypto::ring,
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName, pem::PemObject},
+};
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe)]
+#[clap_mcp_output_from = "run_http_tls_test"]
+#[command(name = "http-tls-test-cli")]
+enum HttpTlsTestCli {
+    Ping,
+}
+
+fn run_http_tls_test(_cmd: HttpTlsTestCli) -> String {
+    "pong".into()
+}
+
+const INITIALIZE: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-11-25","capabilities":{},"clientInfo":{"name":"tls-test","version":"0"}}}"#;
+
+/// A CA with a `localhost` server certificate and a client certificate it issued.
+struct Pki {
+    ca: String,
+    server_cert: String,
+    server_key: String,
+    client_cert: String,
+    client_key: String,
+}
+
+impl Pki {
+    fn generate() -> Self {
+        let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = CertifiedIssuer::self_signed(ca_params, KeyPair::generate().unwrap()).unwrap();
+        let leaf = |name: &str, usage: ExtendedKeyUsagePurpose| {
+            let key = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::new(vec![name.to_string()]).unwrap();
+            params.extended_key_usages = vec![usage];
+            let cert = params.signed_by(&key, &ca).unwrap();
+            (cert.pem(), key.serialize_pem())
+        };
+        let (server_cert, server_key) = leaf("localhost", ExtendedKeyUsagePurpose::ServerAuth);
+        let (client_cert, client_key) = leaf("client", ExtendedKeyUsagePurpose::ClientAuth);
+        Self {
+            ca: ca.pem(),
+            server_cert,
+            server_key,
+            client_cert,
+            client_key,
+        }
+    }
+
+    fn server_tls(&self) -> HttpTls {
+        HttpTls::from_pem(self.server_cert.as_bytes(), self.server_key.as_bytes())
+            .expect("server certificate and key")
+    }
+
+    fn connector(&self, with_client_cert: bool) -> TlsConnector {
+        let mut roots = RootCertStore::empty();
+        roots
+            .add(CertificateDer::from_pem_slice(self.ca.as_bytes()).unwrap())
+            .unwrap();
+        let builder = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots);
+        let config = if with_client_cert {
+            builder
+                .with_client_auth_cert(
+                    vec![CertificateDer::from_pem_slice(self.client_cert.as_bytes()).unwrap()],
+                    PrivateKeyDer::from_pem_slice(self.client_key.as_bytes()).unwrap(),
+                )
+                .unwrap()
+        } else {
+            builder.with_no_client_auth()
+        };
+        TlsConnector::from(Arc::new(config))
+    }
+}
+
+async fn start_server(tls: HttpTls) -> (SocketAddr, tokio::task::JoinHandle<()>) {
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    let server = tokio::spawn(async move {
+        let _ = ServeMcpBuilder::for_cli::<HttpTlsTestCli>(McpListen::Http(addr))
+            .http_tls(tls)
+            .serve()
+            .await;
+    });
+    for _ in 0..100 {
+        if tokio::net::TcpStream::connect(addr).await.is_ok() {
+            return (addr, server);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("HTTPS server not ready at {addr}");
+}
+
+/// `POST /mcp` with an `initialize` body over TLS; returns the raw response.
+async fn post_initialize(
+    connector: &TlsConnector,
+    addr: SocketAddr,
+    host: &str,
+) -> std::io::Result<String> {
+    let request = format!(
+        "POST /mcp HTTP/1.1\r\nHost: {host}\r\nContent-Type: application/json\r\n\
+         Accept: application/json, text/event-stream\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{INITIALIZE}",
+        INITIALIZE.len()
+    );
+    let tcp = tokio::net::TcpStream::connect(addr).await?;
+    let server_name = ServerName::try_from("localhost").unwrap();
+    let mut stream = connector.connect(server_name, tcp).await?;
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    Ok(String::from_utf8_lossy(&response).into_owned())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn https_serves_mcp_and_checks_allowed_hosts() {
+    let pki = Pki::generate();
+    let (addr, server) = start_server(pki.server_tls()).await;
+    let connector = pki.connector(false);
+    let port = addr.port();
+
+    let response = post_initialize(&connector, addr, &format!("localhost:{port}"))
+        .await
+        .expect("HTTPS request");
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+    let response = post_initialize(&connector, addr, "evil.example")
+        .await
+        .expect("HTTPS request");
+    assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+
+    // Plain HTTP on the TLS port gets no HTTP response.
+    let mut plain = tokio::net::TcpStream::connect(addr).await.unwrap();
+    plain
+        .write_all(format!("GET /mcp HTTP/1.1\r\nHost: localhost:{port}\r\n\r\n").as_bytes())
+        .await
+        .unwrap();
+    let mut reply = Vec::new();
+    let _ = plain.read_to_end(&mut reply).await;
+    assert!(!reply.starts_with(b"HTTP/"));
+
+    server.abort();
+    let _ = server.await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn client_certificate_is_required_when_configured() {
+    let pki = Pki::generate();
+    let tls = pki
+        .server_tls()
+        .require_client_cert(pki.ca.as_bytes())
+        .expect("client CA");
+    let (addr, server) = start_server(tls).await;
+    let host = format!("localhost:{}", addr.port());
+
+    let response = post_initialize(&pki.connector(true), addr, &host)
+        .await
+        .expect("HTTPS request with client certificate");
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+    let refused = post_initialize(&pki.connector(false), addr, &host).await;
+    assert!(
+        !refused.as_ref().is_ok_and(|r| r.starts_with("HTTP/")),
+        "{refused:?}"
+    );
+
+    server.abort();
+    let _ = server.await;
+}
@@ -0,0 +1,139 @@
+//! Tests for `logging/setLevel`: forwarded log messages are filtered by the session's level,
+//! starting from [`clap_mcp::ClapMcpServeOptions::default_log_level`].
+
+// Logging types remain functional in rmcp 3.x but are deprecated by SEP-2577.
+#![allow(deprecated)]
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::logging::{LoggingMessageNotificationParams, is_level_forwarded, log_params};
+use clap_mcp::{ClapMcp, ClapMcpServeOptions, McpListen, ServeMcpBuilder, logging::log_channel};
+use common::{connect_duplex, shutdown_duplex};
+use rmcp::model::{LoggingLevel, LoggingMessageNotificationParam, SetLevelRequestParams};
+use rmcp::service::{NotificationContext, RunningService};
+use rmcp::{ClientHandler, RoleClient};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false)]
+#[clap_mcp_output_from = "run_log_level_test"]
+#[command(name = "log-level-test-cli")]
+enum LogLevelTestCli {
+    Noop,
+}
+
+fn run_log_level_test(_cmd: LogLevelTestCli) -> String {
+    String::new()
+}
+
+/// Records the `data` of every `notifications/message`.
+#[derive(Clone, Default)]
+struct Messages {
+    received: Arc<Mutex<Vec<String>>>,
+}
+
+impl ClientHandler for Messages {
+    async fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        if let Some(data) = params.data.as_str() {
+            self.received
+                .lock()
+                .expect("messages lock")
+                .push(data.to_string());
+        }
+    }
+}
+
+impl Messages {
+    fn contains(&self, message: &str) -> bool {
+        self.received
+            .lock()
+            .expect("messages lock")
+            .iter()
+            .any(|m| m == message)
+    }
+
+    /// Wait for `message`; anything forwarded before it has arrived by then.
+    async fn wait_for(&self, message: &str) {
+        for _ in 0..100 {
+            if self.contains(message) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("log message {message:?} was not forwarded");
+    }
+}
+
+async fn connect(
+    default_log_level: Option<LoggingLevel>,
+) -> (
+    RunningService<RoleClient, Messages>,
+    Messages,
+    mpsc::Sender<LoggingMessageNotificationParams>,
+    tokio::task::JoinHandle<()>,
+) {
+    let (log_tx, log_rx) = log_channel(32);
+    let serve_options = ClapMcpServeOptions {
+        log_rx: Some(log_rx),
+        default_log_level,
+        ..Default::default()
+    };
+
+    let builder =
+        ServeMcpBuilder::for_cli::<LogLevelTestCli>(McpListen::Stdio).serve_options(serve_options);
+    let messages = Messages::default();
+    let (client, server) = connect_duplex(builder, messages.clone()).await;
+    // Log messages go to the session that last sent a request.
+    client.list_tools(None).await.expect("list tools");
+    (client, messages, log_tx, server)
+}
+
+async fn send(tx: &mpsc::Sender<LoggingMessageNotificationParams>, level: LoggingLevel, msg: &str) {
+    tx.send(log_params(level, Some("app".into()), msg))
+        .await
+        .expect("log channel open");
+}
+
+// One test: the bridges' level check is process-wide, so parallel servers would race on it.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn set_level_filters_forwarded_messages() {
+    let (client, messages, tx, server) = connect(None).await;
+    assert!(is_level_forwarded(LoggingLevel::Debug));
+    send(&tx, LoggingLevel::Debug, "default debug").await;
+    messages.wait_for("default debug").await;
+
+    client
+        .set_level(SetLevelRequestParams::new(LoggingLevel::Error))
+        .await
+        .expect("set level");
+    assert!(!is_level_forwarded(LoggingLevel::Warning));
+    assert!(is_level_forwarded(LoggingLevel::Critical));
+    send(&tx, LoggingLevel::Info, "dropped info").await;
+    send(&tx, LoggingLevel::Critical, "kept critical").await;
+    messages.wait_for("kept critical").await;
+    assert!(!messages.contains("dropped info"));
+    shutdown_duplex(client, server).await;
+
+    let (client, messages, tx, server) = connect(Some(LoggingLevel::Warning)).await;
+    assert!(!is_level_forwarded(LoggingLevel::Info));
+    send(&tx, LoggingLevel::Debug, "before debug").await;
+    send(&tx, LoggingLevel::Warning, "before warning").await;
+    messages.wait_for("before warning").await;
+    assert!(!messages.contains("before debug"));
+
+    client
+        .set_level(SetLevelRequestParams::new(LoggingLevel::Debug))
+        .await
+        .expect("set level");
+    assert!(is_level_forwarded(LoggingLevel::Debug));
+    send(&tx, LoggingLevel::Debug, "after debug").await;
+    messages.wait_for("after debug").await;
+    shutdown_duplex(client, server).await;
+}
@@ -0,0 +1,157 @@
+//! Tests for cursor pagination of list results ([`clap_mcp::ClapMcpServeOptions::page_size`]).
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::content::{CustomPrompt, CustomResource, PromptContent, ResourceContent};
+use clap_mcp::{
+    CacheHints, CacheScope, ClapMcp, ClapMcpServeOptions, McpListen, ServeMcpBuilder,
+    json_schema_2020_12_tool,
+};
+use common::{connect_duplex, shutdown_duplex};
+use rmcp::RoleClient;
+use rmcp::model::PaginatedRequestParams;
+use rmcp::service::RunningService;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false)]
+#[clap_mcp_output_from = "run_page_test"]
+#[command(name = "page-test-cli")]
+enum PageTestCli {
+    Alpha,
+    Beta,
+    Gamma,
+    Delta,
+}
+
+fn run_page_test(_cmd: PageTestCli) -> String {
+    String::new()
+}
+
+async fn connect(
+    page_size: Option<usize>,
+) -> (RunningService<RoleClient, ()>, tokio::task::JoinHandle<()>) {
+    let mut serve_options = ClapMcpServeOptions::default();
+    serve_options.custom_tools.push(json_schema_2020_12_tool());
+    for i in 0..3 {
+        serve_options.custom_resources.push(CustomResource {
+            uri: format!("test://doc/{i}"),
+            name: format!("doc-{i}"),
+            title: None,
+            description: None,
+            mime_type: None,
+            content: ResourceContent::Static(String::new()),
+        });
+        serve_options.custom_prompts.push(CustomPrompt {
+            name: format!("prompt-{i}"),
+            title: None,
+            description: None,
+            arguments: vec![],
+            content: PromptContent::Static(vec![]),
+        });
+    }
+
+    let builder = ServeMcpBuilder::for_cli::<PageTestCli>(McpListen::Stdio)
+        .serve_options(serve_options)
+        .page_size(page_size)
+        .cache_hints(CacheHints {
+            ttl_ms: 60_000,
+            cache_scope: CacheScope::Private,
+        });
+    connect_duplex(builder, ()).await
+}
+
+fn page(cursor: Option<String>) -> Option<PaginatedRequestParams> {
+    Some(PaginatedRequestParams::default().with_cursor(cursor))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tools_page_through_clap_and_custom_tools() {
+    let (client, server) = connect(None).await;
+    let unpaged: Vec<String> = client
+        .list_tools(None)
+        .await
+        .expect("list tools")
+        .tools
+        .into_iter()
+        .map(|t| t.name.to_string())
+        .collect();
+    shutdown_duplex(client, server).await;
+
+    let (client, server) = connect(Some(2)).await;
+    let mut names = Vec::new();
+    let mut cursor = None;
+    let mut pages = 0;
+    loop {
+        let result = client.list_tools(page(cursor)).await.expect("list tools");
+        assert!(result.tools.len() <= 2);
+        assert_eq!(result.ttl_ms, Some(60_000));
+        assert_eq!(result.cache_scope, Some(CacheScope::Private));
+        names.extend(result.tools.into_iter().map(|t| t.name.to_string()));
+        pages += 1;
+        cursor = result.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    // Root + 4 subcommands + the custom tool, in the unpaginated order.
+    assert_eq!(unpaged.len(), 6);
+    assert_eq!(names, unpaged);
+    assert_eq!(pages, 3);
+    assert_eq!(
+        names.last().map(String::as_str),
+        Some("json_schema_2020_12_tool")
+    );
+
+    let all = client.list_all_tools().await.expect("list all tools");
+    assert_eq!(all.len(), 6);
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn resources_and_prompts_paginate() {
+    let (client, server) = connect(Some(2)).await;
+
+    let first = client.list_resources(None).await.expect("list resources");
+    assert_eq!(first.resources.len(), 2);
+    assert_eq!(first.resources[0].uri, "clap://schema");
+    let resources = client
+        .list_all_resources()
+        .await
+        .expect("list all resources");
+    assert_eq!(resources.len(), 4);
+
+    let first = client.list_prompts(None).await.expect("list prompts");
+    assert_eq!(first.prompts.len(), 2);
+    let second = client
+        .list_prompts(page(first.next_cursor))
+        .await
+        .expect("list prompts page 2");
+    assert_eq!(second.prompts.len(), 1);
+    assert_eq!(second.prompts[0].name, "prompt-2");
+    assert_eq!(second.next_cursor, None);
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn invalid_cursor_is_rejected() {
+    let (client, server) = connect(Some(2)).await;
+    let tools = client.list_tools(None).await.expect("list tools");
+    let tools_cursor = tools.next_cursor.expect("more tools");
+
+    let err = client
+        .list_tools(page(Some("not-a-cursor".into())))
+        .await
+        .expect_err("garbage cursor");
+    assert!(
+        err.to_string().contains("invalid or expired cursor"),
+        "{err}"
+    );
+    client
+        .list_prompts(page(Some(tools_cursor)))
+        .await
+        .expect_err("tools cursor is not a prompts cursor");
+
+    shutdown_duplex(client, server).await;
+}
@@ -0,0 +1,229 @@
+//! Tests for [`ClapMcpConfig::poison_policy`] after caught in-process panics.
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::state::StatePersistence;
+use clap_mcp::{
+    CLAP_MCP_POISONED_EXIT_CODE, ClapMcp, ClapMcpConfigProvider, ClapMcpPoisonPolicy,
+    ClapMcpServeOptions, MCP_RESOURCE_URI_STATUS, McpListen, ServeMcpBuilder,
+};
+use common::{connect_duplex, shutdown_duplex};
+use rmcp::model::{CallToolRequestParams, ReadResourceRequestParams, ResourceContents};
+use rmcp::service::{NotificationContext, RunningService};
+use rmcp::{ClientHandler, RoleClient};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(
+    reinvocation_safe,
+    catch_in_process_panics,
+    poison_policy = "disable_tool"
+)]
+#[clap_mcp_output_from = "run_poison_test"]
+#[command(name = "poison-test-cli")]
+enum PoisonTestCli {
+    Ping,
+    Boom,
+}
+
+fn run_poison_test(cmd: PoisonTestCli) -> String {
+    match cmd {
+        PoisonTestCli::Ping => "pong".to_string(),
+        PoisonTestCli::Boom => panic!("boom"),
+    }
+}
+
+#[derive(Clone, Default)]
+struct ListChangedCounter(Arc<AtomicUsize>);
+
+impl ClientHandler for ListChangedCounter {
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+async fn serve_with_policy(
+    policy: ClapMcpPoisonPolicy,
+    handler: ListChangedCounter,
+) -> (
+    RunningService<RoleClient, ListChangedCounter>,
+    tokio::task::JoinHandle<()>,
+) {
+    let mut config = PoisonTestCli::clap_mcp_config();
+    config.poison_policy = policy;
+    let builder = ServeMcpBuilder::for_cli::<PoisonTestCli>(McpListen::Stdio).config(config);
+    connect_duplex(builder, handler).await
+}
+
+async fn read_status(client: &RunningService<RoleClient, ListChangedCounter>) -> serde_json::Value {
+    let read = client
+        .read_resource(ReadResourceRequestParams::new(MCP_RESOURCE_URI_STATUS))
+        .await
+        .expect("clap://status should be readable");
+    let ResourceContents::TextResourceContents { text, .. } = &read.contents[0] else {
+        panic!("expected text status contents");
+    };
+    serde_json::from_str(text).expect("status should be JSON")
+}
+
+#[test]
+fn poison_policy_attribute_sets_config() {
+    assert_eq!(
+        PoisonTestCli::clap_mcp_config().poison_policy,
+        ClapMcpPoisonPolicy::DisableTool
+    );
+    assert_eq!(
+        clap_mcp::ClapMcpConfig::default().poison_policy,
+        ClapMcpPoisonPolicy::Continue
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn disable_tool_hides_panicked_tool_and_notifies() {
+    let handler = ListChangedCounter::default();
+    let (client, server) =
+        serve_with_policy(ClapMcpPoisonPolicy::DisableTool, handler.clone()).await;
+
+    let caps = client
+        .peer_info()
+        .expect("server info")
+        .capabilities
+        .clone();
+    assert_eq!(caps.tools.and_then(|t| t.list_changed), Some(true));
+
+    let boom = client
+        .call_tool(CallToolRequestParams::new("boom"))
+        .await
+        .expect("panicking call should return a result");
+    assert_eq!(boom.is_error, Some(true));
+
+    let tools = client.list_tools(None).await.expect("list tools").tools;
+    assert!(tools.iter().any(|t| t.name == "ping"));
+    assert!(!tools.iter().any(|t| t.name == "boom"));
+
+    let again = client
+        .call_tool(CallToolRequestParams::new("boom"))
+        .await
+        .expect("disabled call should return a result");
+    assert_eq!(again.is_error, Some(true));
+    let ping = client
+        .call_tool(CallToolRequestParams::new("ping"))
+        .await
+        .expect("ping should still work");
+    assert_eq!(ping.is_error, Some(false));
+
+    let status = read_status(&client).await;
+    assert_eq!(status["poisoned"], true);
+    assert_eq!(status["poisonPolicy"], "disable_tool");
+    assert_eq!(status["panics"][0]["tool"], "boom");
+
+    for _ in 0..20 {
+        if handler.0.load(Ordering::SeqCst) > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(25)).await;
+    }
+    assert_eq!(handler.0.load(Ordering::SeqCst), 1);
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn refuse_all_rejects_every_call_after_panic() {
+    let (client, server) = serve_with_policy(
+        ClapMcpPoisonPolicy::RefuseAll,
+        ListChangedCounter::default(),
+    )
+    .await;
+
+    let status = read_status(&client).await;
+    assert_eq!(status["poisoned"], false);
+
+    client
+        .call_tool(CallToolRequestParams::new("boom"))
+        .await
+        .expect("panicking call should return a result");
+    let ping = client
+        .call_tool(CallToolRequestParams::new("ping"))
+        .await
+        .expect("refused call should return a result");
+    assert_eq!(ping.is_error, Some(true));
+    let text = format!("{:?}", ping.content);
+    assert!(text.contains("poisoned"), "got: {text}");
+
+    let status = read_status(&client).await;
+    assert_eq!(status["refusingCalls"], true);
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn continue_policy_keeps_serving_panicked_tool() {
+    let (client, server) =
+        serve_with_policy(ClapMcpPoisonPolicy::Continue, ListChangedCounter::default()).await;
+
+    client
+        .call_tool(CallToolRequestParams::new("boom"))
+        .await
+        .expect("panicking call should return a result");
+    let tools = client.list_tools(None).await.expect("list tools").tools;
+    assert!(tools.iter().any(|t| t.name == "boom"));
+    let ping = client
+        .call_tool(CallToolRequestParams::new("ping"))
+        .await
+        .expect("ping should work");
+    assert_eq!(ping.is_error, Some(false));
+
+    shutdown_duplex(client, server).await;
+}
+
+/// Snapshot path handed to [`exit_policy_child`].
+const EXIT_SNAPSHOT_ENV: &str = "CLAP_MCP_TEST_POISON_EXIT_SNAPSHOT";
+
+/// Serves with [`ClapMcpPoisonPolicy::Exit`] and panics a tool; the process should exit.
+/// Run in a child process by [`exit_policy_saves_state_before_exiting`].
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "exits the process; run by exit_policy_saves_state_before_exiting"]
+async fn exit_policy_child() {
+    let Some(path) = std::env::var_os(EXIT_SNAPSHOT_ENV) else {
+        return;
+    };
+    let mut config = PoisonTestCli::clap_mcp_config();
+    config.poison_policy = ClapMcpPoisonPolicy::Exit;
+    let state = Arc::new(serde_json::json!({ "calls": 1 }));
+    let builder = ServeMcpBuilder::for_cli::<PoisonTestCli>(McpListen::Stdio)
+        .config(config)
+        .serve_options(ClapMcpServeOptions {
+            state_persistence: Some(StatePersistence::new(path).bind(state)),
+            ..Default::default()
+        });
+    let (client, _server) = connect_duplex(builder, ListChangedCounter::default()).await;
+    let _ = client.call_tool(CallToolRequestParams::new("boom")).await;
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    panic!("server should have exited after the panic");
+}
+
+#[test]
+fn exit_policy_saves_state_before_exiting() {
+    let path =
+        std::env::temp_dir().join(format!("clap-mcp-poison-exit-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["exit_policy_child", "--exact", "--ignored"])
+        .env(EXIT_SNAPSHOT_ENV, &path)
+        .output()
+        .expect("run child test");
+    assert_eq!(
+        output.status.code(),
+        Some(CLAP_MCP_POISONED_EXIT_CODE),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let saved: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&path).expect("snapshot saved")).unwrap();
+    assert_eq!(saved, serde_json::json!({ "calls": 1 }));
+    let _ = std::fs::remove_file(&path);
+}
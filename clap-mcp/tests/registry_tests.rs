@@ -0,0 +1,299 @@
+//! Tests for [`clap_mcp::ClapMcpRegistry`]: runtime enable/disable and schema replacement,
+//! with `notifications/*/list_changed` to sessions and `subscriptions/listen` streams.
+
+mod common;
+
+use clap::{CommandFactory, Parser};
+use clap_mcp::content::{CustomPrompt, CustomResource, PromptContent, ResourceContent};
+use clap_mcp::{
+    ClapMcp, ClapMcpRegistry, ClapMcpServeOptions, McpListen, ServeMcpBuilder, schema_from_command,
+};
+use common::{connect_duplex, shutdown_duplex};
+use rmcp::model::{
+    CallToolRequestParams, ClientCapabilities, ClientConfig, GetPromptRequestParams,
+    Implementation, PromptArgument, ProtocolVersion, ReadResourceRequestParams, ServerNotification,
+    SubscriptionFilter,
+};
+use rmcp::service::{NotificationContext, RunningService};
+use rmcp::{ClientHandler, RoleClient};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false)]
+#[clap_mcp_output_from = "run_registry_test"]
+#[command(name = "registry-test-cli")]
+enum RegistryTestCli {
+    /// Unlocks `deploy`.
+    Login,
+    Deploy {
+        #[arg(long, value_parser = ["staging", "prod"])]
+        target: Option<String>,
+    },
+    Status,
+}
+
+fn run_registry_test(cmd: RegistryTestCli) -> String {
+    match cmd {
+        RegistryTestCli::Login => {
+            let registry = ClapMcpRegistry::current().expect("in-process tool has a registry");
+            registry.set_tool_enabled("deploy", true).to_string()
+        }
+        RegistryTestCli::Deploy { .. } => "deployed".into(),
+        RegistryTestCli::Status => "ok".into(),
+    }
+}
+
+/// Records which list-changed notifications arrive. `legacy` negotiates 2025-11-25 so
+/// notifications reach the session without `subscriptions/listen`.
+#[derive(Clone)]
+struct ListCollector {
+    tx: mpsc::UnboundedSender<&'static str>,
+    legacy: bool,
+}
+
+impl ClientHandler for ListCollector {
+    fn get_info(&self) -> ClientConfig {
+        let info = ClientConfig::new(
+            ClientCapabilities::default(),
+            Implementation::from_build_env(),
+        );
+        if self.legacy {
+            info.with_protocol_version(ProtocolVersion::V_2025_11_25)
+        } else {
+            info
+        }
+    }
+
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        let _ = self.tx.send("tools");
+    }
+
+    async fn on_prompt_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        let _ = self.tx.send("prompts");
+    }
+
+    async fn on_resource_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        let _ = self.tx.send("resources");
+    }
+}
+
+async fn connect(
+    legacy: bool,
+) -> (
+    RunningService<RoleClient, ListCollector>,
+    mpsc::UnboundedReceiver<&'static str>,
+    ClapMcpRegistry,
+    tokio::task::JoinHandle<()>,
+) {
+    let mut serve_options = ClapMcpServeOptions::default();
+    serve_options.custom_resources.push(CustomResource {
+        uri: "test://notes".into(),
+        name: "notes".into(),
+        title: None,
+        description: None,
+        mime_type: None,
+        content: ResourceContent::Static("notes".into()),
+    });
+    serve_options.custom_prompts.push(CustomPrompt {
+        name: "review".into(),
+        title: None,
+        description: None,
+        arguments: vec![PromptArgument::new("topic")],
+        content: PromptContent::Static(vec![]),
+    });
+    serve_options.registry.set_tool_enabled("deploy", false);
+
+    let builder =
+        ServeMcpBuilder::for_cli::<RegistryTestCli>(McpListen::Stdio).serve_options(serve_options);
+    let registry = builder.registry();
+    let (tx, rx) = mpsc::unbounded_channel();
+    let (client, server) = connect_duplex(builder, ListCollector { tx, legacy }).await;
+    (client, rx, registry, server)
+}
+
+async fn next_change(rx: &mut mpsc::UnboundedReceiver<&'static str>) -> &'static str {
+    tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .expect("list_changed notification should arrive")
+        .expect("collector open")
+}
+
+async fn tool_names(client: &RunningService<RoleClient, ListCollector>) -> Vec<String> {
+    client
+        .list_all_tools()
+        .await
+        .expect("list tools")
+        .into_iter()
+        .map(|t| t.name.to_string())
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn disabled_tool_is_hidden_until_enabled() {
+    let (client, mut rx, registry, server) = connect(true).await;
+    let info = client.peer_info().expect("server info");
+    let tools_caps = info.capabilities.tools.as_ref().expect("tools capability");
+    assert_eq!(tools_caps.list_changed, Some(true));
+
+    assert_eq!(
+        tool_names(&client).await,
+        ["registry-test-cli", "login", "status"]
+    );
+    let err = client
+        .call_tool(CallToolRequestParams::new("deploy"))
+        .await
+        .expect_err("disabled tool call should fail");
+    assert!(err.to_string().contains("unknown tool: deploy"), "{err}");
+
+    assert!(
+        !registry.set_tool_enabled("deploy", false),
+        "already disabled"
+    );
+    let login = client
+        .call_tool(CallToolRequestParams::new("login"))
+        .await
+        .expect("call login");
+    assert_eq!(login.content[0].as_text().expect("text").text, "true");
+    assert_eq!(next_change(&mut rx).await, "tools");
+    assert!(registry.is_tool_enabled("deploy"));
+    assert_eq!(
+        tool_names(&client).await,
+        ["registry-test-cli", "login", "deploy", "status"]
+    );
+    let deploy = client
+        .call_tool(CallToolRequestParams::new("deploy"))
+        .await
+        .expect("call deploy");
+    assert_eq!(deploy.content[0].as_text().expect("text").text, "deployed");
+    assert!(rx.try_recv().is_err());
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn disabled_prompts_and_resources_are_hidden() {
+    let (client, mut rx, registry, server) = connect(true).await;
+
+    assert!(registry.set_prompt_enabled("review", false));
+    assert_eq!(next_change(&mut rx).await, "prompts");
+    let prompts = client.list_all_prompts().await.expect("list prompts");
+    assert!(prompts.iter().all(|p| p.name != "review"));
+    client
+        .get_prompt(GetPromptRequestParams::new("review"))
+        .await
+        .expect_err("disabled prompt should be unknown");
+
+    assert!(registry.set_resource_enabled("test://notes", false));
+    assert_eq!(next_change(&mut rx).await, "resources");
+    let resources = client.list_all_resources().await.expect("list resources");
+    assert!(resources.iter().all(|r| r.uri != "test://notes"));
+    client
+        .read_resource(ReadResourceRequestParams::new("test://notes"))
+        .await
+        .expect_err("disabled resource should not be readable");
+
+    assert!(registry.set_resource_enabled("test://notes", true));
+    assert_eq!(next_change(&mut rx).await, "resources");
+    client
+        .read_resource(ReadResourceRequestParams::new("test://notes"))
+        .await
+        .expect("re-enabled resource is readable");
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn disabled_tools_and_prompts_do_not_complete() {
+    let (client, mut rx, registry, server) = connect(true).await;
+
+    let err = client
+        .complete_prompt_argument("deploy", "target", "", None)
+        .await
+        .expect_err("disabled tool should not complete");
+    assert!(err.to_string().contains("unknown prompt: deploy"), "{err}");
+    assert!(registry.set_tool_enabled("deploy", true));
+    assert_eq!(next_change(&mut rx).await, "tools");
+    let completion = client
+        .complete_prompt_argument("deploy", "target", "p", None)
+        .await
+        .expect("enabled tool completes");
+    assert_eq!(completion.values, ["prod"]);
+
+    client
+        .complete_prompt_argument("review", "topic", "", None)
+        .await
+        .expect("enabled prompt completes");
+    assert!(registry.set_prompt_enabled("review", false));
+    assert_eq!(next_change(&mut rx).await, "prompts");
+    let err = client
+        .complete_prompt_argument("review", "topic", "", None)
+        .await
+        .expect_err("disabled prompt should not complete");
+    assert!(err.to_string().contains("unknown prompt: review"), "{err}");
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn replace_schema_rebuilds_tools() {
+    let (client, mut rx, registry, server) = connect(true).await;
+    registry.set_tool_enabled("deploy", true);
+    assert_eq!(next_change(&mut rx).await, "tools");
+
+    let mut schema = schema_from_command(&RegistryTestCli::command());
+    schema.root.subcommands.retain(|sub| sub.name != "login");
+    registry.replace_schema(&schema).expect("replace schema");
+    assert_eq!(next_change(&mut rx).await, "tools");
+    assert_eq!(
+        tool_names(&client).await,
+        ["registry-test-cli", "deploy", "status"]
+    );
+
+    let status = client
+        .call_tool(CallToolRequestParams::new("status"))
+        .await
+        .expect("call status");
+    assert_eq!(status.content[0].as_text().expect("text").text, "ok");
+    client
+        .call_tool(CallToolRequestParams::new("login"))
+        .await
+        .expect_err("removed tool should be unknown");
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn listen_stream_receives_opted_in_list_changes() {
+    let (client, _rx, registry, server) = connect(false).await;
+    let mut subscription = client
+        .peer()
+        .listen(SubscriptionFilter::builder().tools_list_changed().build())
+        .await
+        .expect("listen");
+    assert_eq!(subscription.acknowledged().tools_list_changed, Some(true));
+
+    // The SDK acknowledges the stream before the server's `listen` registers it, so keep
+    // toggling until a change lands; the prompt change goes first and must be filtered out.
+    let mut notification = None;
+    for _ in 0..100 {
+        registry.set_prompt_enabled("review", !registry.is_prompt_enabled("review"));
+        registry.set_tool_enabled("status", !registry.is_tool_enabled("status"));
+        if let Ok(next) = tokio::time::timeout(Duration::from_millis(50), subscription.next()).await
+        {
+            notification = Some(next.expect("subscription open").expect("notification"));
+            break;
+        }
+    }
+    let notification = notification.expect("notification should arrive");
+    assert!(
+        matches!(
+            notification,
+            ServerNotification::ToolListChangedNotification(_)
+        ),
+        "unexpected notification: {notification:?}"
+    );
+
+    subscription.cancel().await.expect("cancel");
+    shutdown_duplex(client, server).await;
+}
@@ -0,0 +1,210 @@
+//! Tests for [`clap_mcp::ResourceUpdateNotifier`]: `notifications/resources/updated` reaches
+//! only subscribers of the URI (legacy `resources/subscribe`, including templates, and
+//! `subscriptions/listen` streams).
+
+// resources/subscribe|unsubscribe remain for legacy protocol peers (deprecated in rmcp 3).
+#![allow(deprecated)]
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, McpListen, ResourceUpdateNotifier, ServeMcpBuilder};
+use common::{connect_duplex, shutdown_duplex};
+use rmcp::model::{
+    CallToolRequestParams, ClientCapabilities, ClientInfo, Implementation, ProtocolVersion,
+    ResourceUpdatedNotificationParam, ServerNotification, SubscribeRequestParams,
+    SubscriptionFilter, UnsubscribeRequestParams,
+};
+use rmcp::service::{NotificationContext, RunningService};
+use rmcp::{ClientHandler, RoleClient};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false)]
+#[clap_mcp_output_from = "run_update_test"]
+#[command(name = "update-test-cli")]
+enum UpdateTestCli {
+    Touch,
+}
+
+fn run_update_test(_cmd: UpdateTestCli) -> String {
+    let notifier = ResourceUpdateNotifier::current().expect("in-process tool has a notifier");
+    notifier.notify("test://touched").to_string()
+}
+
+/// Collects update notifications. `legacy` negotiates 2025-11-25 so `resources/subscribe` is
+/// available; otherwise the client uses `subscriptions/listen`.
+#[derive(Clone)]
+struct UpdateCollector {
+    tx: mpsc::UnboundedSender<String>,
+    legacy: bool,
+}
+
+impl ClientHandler for UpdateCollector {
+    fn get_info(&self) -> ClientInfo {
+        let info = ClientInfo::new(
+            ClientCapabilities::default(),
+            Implementation::from_build_env(),
+        );
+        if self.legacy {
+            info.with_protocol_version(ProtocolVersion::V_2025_11_25)
+        } else {
+            info
+        }
+    }
+
+    async fn on_resource_updated(
+        &self,
+        params: ResourceUpdatedNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        let _ = self.tx.send(params.uri);
+    }
+}
+
+async fn connect(
+    legacy: bool,
+) -> (
+    RunningService<RoleClient, UpdateCollector>,
+    mpsc::UnboundedReceiver<String>,
+    ResourceUpdateNotifier,
+    tokio::task::JoinHandle<()>,
+) {
+    let builder = ServeMcpBuilder::for_cli::<UpdateTestCli>(McpListen::Stdio);
+    let notifier = builder.resource_update_notifier();
+    let (tx, rx) = mpsc::unbounded_channel();
+    let (client, server) = connect_duplex(builder, UpdateCollector { tx, legacy }).await;
+    (client, rx, notifier, server)
+}
+
+async fn next_update(rx: &mut mpsc::UnboundedReceiver<String>) -> String {
+    tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .expect("update notification should arrive")
+        .expect("collector open")
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn notifies_exact_and_template_subscriptions() {
+    let (client, mut rx, notifier, server) = connect(true).await;
+    assert_eq!(notifier.notify("test://doc"), 0);
+
+    client
+        .subscribe(SubscribeRequestParams::new("test://doc"))
+        .await
+        .expect("subscribe");
+    client
+        .subscribe(SubscribeRequestParams::new("test://item/{id}"))
+        .await
+        .expect("subscribe template");
+
+    assert_eq!(notifier.notify("test://doc"), 1);
+    assert_eq!(next_update(&mut rx).await, "test://doc");
+    assert_eq!(notifier.notify("test://item/42"), 1);
+    assert_eq!(next_update(&mut rx).await, "test://item/42");
+    assert_eq!(notifier.notify("test://item/42/extra"), 0);
+    assert_eq!(notifier.notify("test://other"), 0);
+
+    client
+        .unsubscribe(UnsubscribeRequestParams::new("test://doc"))
+        .await
+        .expect("unsubscribe");
+    assert_eq!(notifier.notify("test://doc"), 0);
+    assert!(rx.try_recv().is_err());
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn in_process_tool_notifies_through_current() {
+    assert!(ResourceUpdateNotifier::current().is_none());
+    let (client, mut rx, _notifier, server) = connect(true).await;
+    client
+        .subscribe(SubscribeRequestParams::new("test://touched"))
+        .await
+        .expect("subscribe");
+
+    let result = client
+        .call_tool(CallToolRequestParams::new("touch"))
+        .await
+        .expect("call touch");
+    let text = result.content[0].as_text().expect("text").text.clone();
+    assert_eq!(text, "1");
+    assert_eq!(next_update(&mut rx).await, "test://touched");
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn watch_file_notifies_on_change() {
+    let path = std::env::temp_dir().join(format!("clap-mcp-watch-file-{}.txt", std::process::id()));
+    std::fs::write(&path, "one").unwrap();
+    let (client, mut rx, notifier, server) = connect(true).await;
+    client
+        .subscribe(SubscribeRequestParams::new("test://file"))
+        .await
+        .expect("subscribe");
+
+    let watcher = notifier.watch_file("test://file", &path, Duration::from_millis(20));
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(rx.try_recv().is_err(), "unchanged file should not notify");
+    std::fs::write(&path, "two, longer").unwrap();
+    assert_eq!(next_update(&mut rx).await, "test://file");
+
+    watcher.abort();
+    shutdown_duplex(client, server).await;
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn notifies_listen_subscriptions_until_cancelled() {
+    let (client, _rx, notifier, server) = connect(false).await;
+    let mut subscription = client
+        .peer()
+        .listen(
+            SubscriptionFilter::builder()
+                .resource_subscriptions(["test://doc".to_string()])
+                .build(),
+        )
+        .await
+        .expect("listen");
+    assert_eq!(
+        subscription.acknowledged().resource_subscriptions,
+        Some(vec!["test://doc".to_string()])
+    );
+
+    // The SDK acknowledges the stream before the server's `listen` registers it.
+    let mut delivered = 0;
+    for _ in 0..100 {
+        delivered = notifier.notify("test://doc");
+        if delivered > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(delivered, 1);
+    assert_eq!(notifier.notify("test://other"), 0);
+    let notification = tokio::time::timeout(Duration::from_secs(5), subscription.next())
+        .await
+        .expect("update should arrive")
+        .expect("subscription open")
+        .expect("notification");
+    match notification {
+        ServerNotification::ResourceUpdatedNotification(update) => {
+            assert_eq!(update.params.uri, "test://doc");
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+
+    subscription.cancel().await.expect("cancel");
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while notifier.notify("test://doc") != 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("cancelled listen stream should stop receiving updates");
+
+    shutdown_duplex(client, server).await;
+}
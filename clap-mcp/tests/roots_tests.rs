@@ -0,0 +1,228 @@
+//! Tests for client roots: relative path args resolve against the primary root, subprocess
+//! tools start in it, `confine_paths_to_roots` rejects paths outside, and
+//! `notifications/roots/list_changed` refetches.
+
+// Roots remain functional in rmcp 3.x but are deprecated by SEP-2577.
+#![allow(deprecated)]
+
+mod common;
+
+use clap::{Parser, ValueHint};
+use clap_mcp::{ClapMcp, McpListen, ServeMcpBuilder};
+use common::{connect_duplex, shutdown_duplex};
+use rmcp::model::{
+    CallToolRequestParams, CallToolResult, ClientCapabilities, ClientInfo, Implementation,
+    ListRootsResult, Root,
+};
+use rmcp::service::{RequestContext, RunningService};
+use rmcp::{ClientHandler, ErrorData, RoleClient};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false)]
+#[clap_mcp_output_from = "run_roots_test"]
+#[command(name = "roots-test-cli")]
+enum RootsTestCli {
+    Show {
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        path: PathBuf,
+    },
+    /// Runs the `pwd` script from [`temp_root`].
+    #[clap_mcp(subprocess)]
+    Cwd,
+}
+
+fn run_roots_test(cmd: RootsTestCli) -> String {
+    match cmd {
+        RootsTestCli::Show { path } => path.display().to_string(),
+        RootsTestCli::Cwd => String::new(),
+    }
+}
+
+/// Serves `roots` as `file://` URIs; an empty list does not declare the roots capability.
+#[derive(Clone, Default)]
+struct RootsHandler {
+    roots: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl ClientHandler for RootsHandler {
+    fn get_info(&self) -> ClientInfo {
+        let capabilities = if self.roots.lock().expect("roots lock").is_empty() {
+            ClientCapabilities::default()
+        } else {
+            ClientCapabilities::builder()
+                .enable_roots()
+                .enable_roots_list_changed()
+                .build()
+        };
+        ClientInfo::new(capabilities, Implementation::from_build_env())
+    }
+
+    async fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<ListRootsResult, ErrorData> {
+        let roots = self.roots.lock().expect("roots lock");
+        Ok(ListRootsResult::new(
+            roots
+                .iter()
+                .map(|root| Root::new(format!("file://{}", root.display())))
+                .collect(),
+        ))
+    }
+}
+
+async fn connect(
+    handler: RootsHandler,
+    confine: bool,
+    executable: PathBuf,
+) -> (
+    RunningService<RoleClient, RootsHandler>,
+    tokio::task::JoinHandle<()>,
+) {
+    let builder = ServeMcpBuilder::for_cli::<RootsTestCli>(McpListen::Stdio)
+        .confine_paths_to_roots(confine)
+        .executable_path(Some(executable));
+    connect_duplex(builder, handler).await
+}
+
+fn temp_root(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "clap-mcp-roots-tests-{name}-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.canonicalize().unwrap()
+}
+
+fn handler(roots: &[&PathBuf]) -> RootsHandler {
+    RootsHandler {
+        roots: Arc::new(Mutex::new(roots.iter().map(|r| (*r).clone()).collect())),
+    }
+}
+
+async fn show(client: &RunningService<RoleClient, RootsHandler>, path: &str) -> CallToolResult {
+    client
+        .call_tool(
+            CallToolRequestParams::new("show").with_arguments(serde_json::Map::from_iter([(
+                "path".to_string(),
+                serde_json::json!(path),
+            )])),
+        )
+        .await
+        .expect("call show")
+}
+
+fn text(result: &CallToolResult) -> &str {
+    &result.content[0].as_text().expect("text").text
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn relative_paths_resolve_against_primary_root() {
+    let (app, lib) = (temp_root("primary-app"), temp_root("primary-lib"));
+    let (client, server) = connect(handler(&[&app, &lib]), false, "echo".into()).await;
+
+    let result = show(&client, "notes.txt").await;
+    assert_eq!(text(&result), app.join("notes.txt").display().to_string());
+    // Without confinement, paths outside the roots pass through unchanged.
+    assert_eq!(text(&show(&client, "/etc/hosts").await), "/etc/hosts");
+
+    shutdown_duplex(client, server).await;
+    let (client, server) = connect(RootsHandler::default(), false, "echo".into()).await;
+    assert_eq!(text(&show(&client, "notes.txt").await), "notes.txt");
+    shutdown_duplex(client, server).await;
+    for root in [app, lib] {
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn confined_paths_must_stay_inside_roots() {
+    let (app, lib) = (temp_root("confine-app"), temp_root("confine-lib"));
+    let (client, server) = connect(handler(&[&app, &lib]), true, "echo".into()).await;
+
+    let inside = lib.join("src/lib.rs").display().to_string();
+    let result = show(&client, &inside).await;
+    assert_ne!(result.is_error, Some(true), "{result:?}");
+    assert_eq!(text(&result), inside);
+
+    for outside in ["/etc/passwd", "../escape.txt"] {
+        let result = show(&client, outside).await;
+        assert_eq!(result.is_error, Some(true), "{outside}");
+        assert!(
+            text(&result).contains("outside the client's roots"),
+            "{}",
+            text(&result)
+        );
+    }
+    shutdown_duplex(client, server).await;
+
+    let (client, server) = connect(RootsHandler::default(), true, "echo".into()).await;
+    let result = show(&client, "notes.txt").await;
+    assert_eq!(result.is_error, Some(true));
+    assert!(
+        text(&result).contains("no file:// roots"),
+        "{}",
+        text(&result)
+    );
+    shutdown_duplex(client, server).await;
+    for root in [app, lib] {
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn roots_are_refetched_after_list_changed() {
+    let (first, second) = (temp_root("changed-first"), temp_root("changed-second"));
+    let handler = handler(&[&first]);
+    let roots = handler.roots.clone();
+    let (client, server) = connect(handler, false, "echo".into()).await;
+    assert_eq!(
+        text(&show(&client, "a").await),
+        first.join("a").display().to_string()
+    );
+
+    *roots.lock().expect("roots lock") = vec![second.clone()];
+    client
+        .notify_roots_list_changed()
+        .await
+        .expect("notify roots changed");
+    let expected = second.join("a").display().to_string();
+    let mut resolved = String::new();
+    for _ in 0..50 {
+        resolved = text(&show(&client, "a").await).to_string();
+        if resolved == expected {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(resolved, expected);
+
+    shutdown_duplex(client, server).await;
+    for root in [first, second] {
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn subprocess_tools_start_in_primary_root() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = temp_root("subprocess");
+    let script = root.join("pwd.sh");
+    std::fs::write(&script, "#!/bin/sh\npwd -P\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let (client, server) = connect(handler(&[&root]), false, script).await;
+    let result = client
+        .call_tool(CallToolRequestParams::new("cwd"))
+        .await
+        .expect("call cwd");
+    assert_ne!(result.is_error, Some(true), "{result:?}");
+    assert_eq!(text(&result), root.display().to_string());
+    shutdown_duplex(client, server).await;
+    std::fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,123 @@
+//! Tests for [`clap_mcp::sampling`]: in-process tools ask the client's model for completions.
+
+#![allow(deprecated)]
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, ClapMcpConfigProvider, McpListen, ServeMcpBuilder, sampling};
+use common::{connect_duplex, shutdown_duplex};
+use rmcp::model::{
+    CallToolRequestParams, ClientCapabilities, ClientConfig, CreateMessageRequestParams,
+    CreateMessageResult, Implementation, SamplingMessage,
+};
+use rmcp::service::{RequestContext, RunningService};
+use rmcp::{ClientHandler, ErrorData as McpError, RoleClient};
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false)]
+#[clap_mcp_output_from = "run_sampling_test"]
+#[command(name = "sampling-test-cli")]
+enum SamplingTestCli {
+    Summarize {
+        #[arg(long)]
+        text: String,
+    },
+}
+
+fn message_text(message: &SamplingMessage) -> String {
+    message
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.clone())
+        .unwrap_or_default()
+}
+
+/// Sync `run` that samples through `run_async_tool` (dedicated thread and runtime).
+fn run_sampling_test(cmd: SamplingTestCli) -> Result<String, String> {
+    let SamplingTestCli::Summarize { text } = cmd;
+    let config = SamplingTestCli::clap_mcp_config();
+    clap_mcp::run_async_tool(&config, || async move {
+        let params = CreateMessageRequestParams::new(
+            vec![SamplingMessage::user_text(format!("Summarize: {text}"))],
+            50,
+        );
+        sampling::create_message(params)
+            .await
+            .map(|result| format!("{} via {}", message_text(&result.message), result.model))
+            .map_err(|e| e.to_string())
+    })
+    .map_err(|e| e.to_string())?
+}
+
+/// Answers every sampling request by echoing the prompt; `sampling` controls the capability.
+#[derive(Clone)]
+struct Model {
+    sampling: bool,
+}
+
+impl ClientHandler for Model {
+    fn get_info(&self) -> ClientConfig {
+        let capabilities = if self.sampling {
+            ClientCapabilities::builder().enable_sampling().build()
+        } else {
+            ClientCapabilities::default()
+        };
+        ClientConfig::new(capabilities, Implementation::from_build_env())
+    }
+
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParams,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, McpError> {
+        let prompt = message_text(&params.messages[0]);
+        Ok(CreateMessageResult::new(
+            SamplingMessage::assistant_text(format!("short({prompt})")),
+            "test-model".into(),
+        ))
+    }
+}
+
+async fn connect(
+    sampling: bool,
+) -> (
+    RunningService<RoleClient, Model>,
+    tokio::task::JoinHandle<()>,
+) {
+    connect_duplex(
+        ServeMcpBuilder::for_cli::<SamplingTestCli>(McpListen::Stdio),
+        Model { sampling },
+    )
+    .await
+}
+
+async fn summarize(client: &RunningService<RoleClient, Model>) -> (Option<bool>, String) {
+    let result = client
+        .call_tool(CallToolRequestParams::new("summarize").with_arguments(
+            serde_json::Map::from_iter([("text".to_string(), serde_json::json!("a long story"))]),
+        ))
+        .await
+        .expect("call summarize");
+    let text = result.content[0].as_text().expect("text").text.clone();
+    (result.is_error, text)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tool_samples_from_client_model() {
+    let (client, server) = connect(true).await;
+    let (is_error, text) = summarize(&client).await;
+    assert_ne!(is_error, Some(true), "{text}");
+    assert_eq!(text, "short(Summarize: a long story) via test-model");
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn sampling_is_unavailable_without_client_capability() {
+    let (client, server) = connect(false).await;
+    let (is_error, text) = summarize(&client).await;
+    assert_eq!(is_error, Some(true));
+    assert!(text.contains("sampling unavailable"), "{text}");
+    shutdown_duplex(client, server).await;
+}
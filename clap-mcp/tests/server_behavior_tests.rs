@@ -330,3 +330,61 @@ async fn logging_enabled_servers_expose_the_logging_guide_prompt() {
 
     shutdown(client).await;
 }
+
+#[tokio::test(flavor = "current_thread")]
+async fn per_variant_execution_mode_routes_tools_and_reports_meta() {
+    let client = launch_example("mixed_execution")
+        .await
+        .expect("mixed execution client should launch");
+
+    let tools = client.list_tools(None).await.expect("list tools").tools;
+    let reinvocation_safe = |name: &str| {
+        tools
+            .iter()
+            .find(|t| t.name == name)
+            .and_then(|t| t.meta.as_ref())
+            .and_then(|m| m.get("clapMcp"))
+            .and_then(|c| c.get("reinvocationSafe"))
+            .and_then(|v| v.as_bool())
+    };
+    assert_eq!(reinvocation_safe("status"), Some(true));
+    assert_eq!(reinvocation_safe("migrate"), Some(false));
+
+    let pid_of = |text: &str| -> u32 {
+        let start = text.find("(pid ").expect("tool output should include pid") + 5;
+        let end = start + text[start..].find(')').expect("closing paren");
+        text[start..end].parse().expect("pid should parse")
+    };
+    let call = |name: &'static str| {
+        let client = &client;
+        async move {
+            let result = client
+                .call_tool(CallToolRequestParams::new(name).with_arguments(serde_json::Map::new()))
+                .await
+                .expect("tool call should succeed");
+            assert_ne!(
+                result.is_error,
+                Some(true),
+                "{name}: {}",
+                tool_text(&result)
+            );
+            pid_of(&tool_text(&result))
+        }
+    };
+
+    let status_a = call("status").await;
+    let status_b = call("status").await;
+    let migrate_a = call("migrate").await;
+    let migrate_b = call("migrate").await;
+    assert_eq!(status_a, status_b, "in-process tools share the server pid");
+    assert_ne!(
+        migrate_a, status_a,
+        "subprocess tool runs outside the server"
+    );
+    assert_ne!(
+        migrate_a, migrate_b,
+        "each subprocess call gets a fresh process"
+    );
+
+    shutdown(client).await;
+}
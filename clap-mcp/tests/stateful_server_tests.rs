@@ -1,7 +1,8 @@
 mod common;
 
-use common::{launch_example, shutdown, tool_text};
-use rmcp::model::CallToolRequestParams;
+use clap_mcp::MCP_RESOURCE_URI_STATE;
+use common::{launch_example, launch_example_with_env, read_text, shutdown, tool_text};
+use rmcp::model::{CallToolRequestParams, ReadResourceRequestParams};
 
 #[tokio::test(flavor = "current_thread")]
 async fn stateful_counter_increments_across_tool_calls() {
@@ -29,3 +30,53 @@ async fn stateful_counter_increments_across_tool_calls() {
 
     shutdown(client).await;
 }
+
+#[tokio::test(flavor = "current_thread")]
+async fn persistent_state_survives_server_restart_and_is_readable() {
+    let state_path = std::env::temp_dir().join(format!(
+        "clap-mcp-stateful-counter-{}.json",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&state_path);
+    let envs = [("STATEFUL_COUNTER_STATE", state_path.as_os_str())];
+
+    let client = launch_example_with_env("stateful_counter_persistent", &[], None, "--mcp", &envs)
+        .await
+        .expect("persistent counter server should launch");
+    let listed = client
+        .list_resources(None)
+        .await
+        .expect("resources/list should succeed");
+    assert!(
+        listed
+            .resources
+            .iter()
+            .any(|r| r.uri == MCP_RESOURCE_URI_STATE)
+    );
+    for _ in 0..2 {
+        client
+            .call_tool(CallToolRequestParams::new("increment"))
+            .await
+            .expect("increment should succeed");
+    }
+    let snapshot = client
+        .read_resource(ReadResourceRequestParams::new(MCP_RESOURCE_URI_STATE))
+        .await
+        .expect("clap://state should be readable");
+    let snapshot: serde_json::Value =
+        serde_json::from_str(&read_text(&snapshot)).expect("state snapshot should be JSON");
+    assert_eq!(snapshot, serde_json::json!({ "count": 2 }));
+    shutdown(client).await;
+
+    let client = launch_example_with_env("stateful_counter_persistent", &[], None, "--mcp", &envs)
+        .await
+        .expect("persistent counter server should relaunch");
+    let read = client
+        .call_tool(CallToolRequestParams::new("read"))
+        .await
+        .expect("read should succeed");
+    assert_eq!(tool_text(&read), "count=2");
+    shutdown(client).await;
+
+    let _ = std::fs::remove_file(&state_path);
+}
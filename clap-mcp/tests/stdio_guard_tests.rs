@@ -0,0 +1,89 @@
+//! Stdio serving keeps stray `println!` output out of the JSON-RPC stream.
+
+#![cfg(unix)]
+// Logging types remain functional in rmcp 2.x but are deprecated by SEP-2577.
+#![allow(deprecated)]
+
+mod common;
+
+use common::{example_binary_path, shutdown, tool_text, workspace_root};
+use rmcp::model::{CallToolRequestParams, LoggingMessageNotificationParam};
+use rmcp::service::NotificationContext;
+use rmcp::transport::{ConfigureCommandExt, TokioChildProcess};
+use rmcp::{ClientHandler, RoleClient, ServiceExt};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Default)]
+struct LogCollector {
+    stdout_lines: Arc<Mutex<Vec<serde_json::Value>>>,
+}
+
+impl ClientHandler for LogCollector {
+    async fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        if params.logger.as_deref() == Some("stdout") {
+            self.stdout_lines
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(params.data);
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn stray_println_is_forwarded_as_log_and_session_survives() {
+    {
+        let _guard = common::BUILD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let status = std::process::Command::new("cargo")
+            .args(["build", "-p", "clap-mcp-examples", "--bin", "stray_stdout"])
+            .current_dir(workspace_root())
+            .status()
+            .expect("cargo build");
+        assert!(status.success(), "stray_stdout should build");
+    }
+    let transport = TokioChildProcess::new(
+        tokio::process::Command::new(example_binary_path("stray_stdout")).configure(|cmd| {
+            cmd.arg("--mcp");
+        }),
+    )
+    .expect("spawn stray_stdout");
+    let handler = LogCollector::default();
+    let stdout_lines = handler.stdout_lines.clone();
+    let client = handler
+        .serve(transport)
+        .await
+        .expect("client should connect");
+
+    for name in ["first", "second"] {
+        let args = serde_json::json!({ "name": name });
+        let result = client
+            .call_tool(
+                CallToolRequestParams::new("chatty")
+                    .with_arguments(args.as_object().cloned().unwrap()),
+            )
+            .await
+            .expect("tool call should succeed despite println!");
+        assert_eq!(tool_text(&result), format!("Hello, {name}!"));
+    }
+
+    let expected = serde_json::json!("about to greet second");
+    for _ in 0..100 {
+        let seen = stdout_lines
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&expected);
+        if seen {
+            shutdown(client).await;
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!(
+        "stray stdout not forwarded: {:?}",
+        stdout_lines.lock().unwrap_or_else(|e| e.into_inner())
+    );
+}
@@ -0,0 +1,153 @@
+//! Tests for [`clap_mcp::tasks::ClapMcpTask::request_input`]: the task pauses as
+//! `input_required` until `tasks/update` answers, and resumes with the typed answer.
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::tasks::{ClapMcpTask, TaskInput};
+use clap_mcp::{ClapMcp, ClapMcpConfigProvider, McpListen, ServeMcpBuilder};
+use common::{
+    TasksClientHandler, call_tool_task, connect_duplex, get_task_info, get_task_payload,
+    shutdown_duplex, task_call_params,
+};
+use rmcp::RoleClient;
+use rmcp::model::{
+    CancelTaskParams, ClientRequest, ElicitResult, ElicitationAction, ElicitationSchema,
+    EnumSchema, InputRequest, PrimitiveSchemaDefinition, Request, TaskPayload, TaskStatus,
+    UpdateTaskParams,
+};
+use rmcp::service::RunningService;
+use std::time::Duration;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false, task_augmented_tools)]
+#[clap_mcp_output_from = "run_task_input_test"]
+#[command(name = "task-input-test-cli")]
+enum TaskInputTestCli {
+    Deploy,
+}
+
+#[derive(serde::Deserialize)]
+struct Choice {
+    branch: String,
+}
+
+fn run_task_input_test(_cmd: TaskInputTestCli) -> String {
+    let task = ClapMcpTask::current().expect("task-augmented call");
+    let branches = EnumSchema::builder(vec!["main".into(), "next".into()]).build();
+    let schema = ElicitationSchema::builder()
+        .required_property("branch", PrimitiveSchemaDefinition::Enum(branches))
+        .build_unchecked();
+    let answer =
+        clap_mcp::run_async_tool(&TaskInputTestCli::clap_mcp_config(), move || async move {
+            task.request_input::<Choice>("Deploy which branch?", schema)
+                .await
+        })
+        .expect("async tool runs");
+    match answer {
+        Ok(TaskInput::Accepted(choice)) => format!("deploying {}", choice.branch),
+        Ok(TaskInput::Declined | TaskInput::Cancelled) => "not deployed".into(),
+        Err(error) => format!("error: {error}"),
+    }
+}
+
+async fn connect() -> (
+    RunningService<RoleClient, TasksClientHandler>,
+    tokio::task::JoinHandle<()>,
+) {
+    connect_duplex(
+        ServeMcpBuilder::for_cli::<TaskInputTestCli>(McpListen::Stdio),
+        TasksClientHandler,
+    )
+    .await
+}
+
+/// Poll `tasks/get` until the task leaves `working`; returns its payload.
+async fn wait_while_working(
+    client: &RunningService<RoleClient, TasksClientHandler>,
+    task_id: &str,
+) -> TaskPayload {
+    for _ in 0..250 {
+        let info = get_task_info(client.peer(), task_id)
+            .await
+            .expect("tasks/get");
+        if info.task.status() != TaskStatus::Working {
+            return info.task.payload;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("task {task_id} stayed working");
+}
+
+async fn start_deploy(client: &RunningService<RoleClient, TasksClientHandler>) -> (String, String) {
+    let created = call_tool_task(
+        client.peer(),
+        task_call_params("deploy", serde_json::Map::new()),
+    )
+    .await
+    .expect("task-augmented call");
+    let task_id = created.task.task_id;
+    let TaskPayload::InputRequired { input_requests } = wait_while_working(client, &task_id).await
+    else {
+        panic!("task should wait for input");
+    };
+    assert_eq!(input_requests.len(), 1);
+    let (key, request) = input_requests.into_iter().next().unwrap();
+    let InputRequest::Elicitation(request) = request else {
+        panic!("expected an elicitation request, got {request:?}");
+    };
+    let request = serde_json::to_value(&request.params).expect("elicitation params");
+    assert_eq!(request["message"], "Deploy which branch?");
+    assert_eq!(request["requestedSchema"]["required"][0], "branch");
+    (task_id, key)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn input_response_resumes_the_task() {
+    let (client, server) = connect().await;
+    let (task_id, key) = start_deploy(&client).await;
+
+    let answer = ElicitResult::new(ElicitationAction::Accept)
+        .with_content(serde_json::json!({ "branch": "next" }));
+    client
+        .peer()
+        .send_request(ClientRequest::UpdateTaskRequest(Request::new(
+            UpdateTaskParams::new(
+                task_id.clone(),
+                [(key, serde_json::to_value(answer).unwrap())].into(),
+            ),
+        )))
+        .await
+        .expect("tasks/update");
+
+    assert!(matches!(
+        wait_while_working(&client, &task_id).await,
+        TaskPayload::Completed { .. }
+    ));
+    let payload = get_task_payload(client.peer(), &task_id)
+        .await
+        .expect("tasks/get");
+    assert_eq!(payload["content"][0]["text"], "deploying next");
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn cancel_while_waiting_cancels_the_task() {
+    let (client, server) = connect().await;
+    let (task_id, _key) = start_deploy(&client).await;
+
+    client
+        .peer()
+        .send_request(ClientRequest::CancelTaskRequest(Request::new(
+            CancelTaskParams::new(task_id.clone()),
+        )))
+        .await
+        .expect("tasks/cancel");
+    assert!(matches!(
+        wait_while_working(&client, &task_id).await,
+        TaskPayload::Cancelled
+    ));
+
+    shutdown_duplex(client, server).await;
+}
@@ -0,0 +1,125 @@
+//! Tests for [`clap_mcp::TaskRetention`]: TTL and poll interval hints on new tasks,
+//! `max_retained` forgetting the oldest finished tasks, and the `clap://tasks` listing.
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::{
+    ClapMcp, ClapMcpError, MCP_RESOURCE_URI_TASKS, McpListen, ServeMcpBuilder, TaskRetention,
+};
+use common::{
+    TasksClientHandler, call_tool_task, connect_duplex, get_task_info, poll_until_completed,
+    read_text, shutdown_duplex, task_call_params,
+};
+use rmcp::model::ReadResourceRequestParams;
+use std::time::Duration;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false, task_augmented_tools)]
+#[clap_mcp_output_from = "run_task_retention_test"]
+#[command(name = "task-retention-test-cli")]
+enum TaskRetentionTestCli {
+    Reindex,
+}
+
+fn run_task_retention_test(_cmd: TaskRetentionTestCli) -> String {
+    "reindexed".into()
+}
+
+fn builder(retention: TaskRetention) -> ServeMcpBuilder {
+    ServeMcpBuilder::for_cli::<TaskRetentionTestCli>(McpListen::Stdio).task_retention(retention)
+}
+
+/// Serves `retention` over an unused pipe, for configs that fail before serving.
+async fn serve_rejected(retention: TaskRetention) -> Result<(), ClapMcpError> {
+    builder(retention)
+        .stdio_io(tokio::io::empty(), tokio::io::sink())
+        .serve()
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn retention_limits_and_lists_tasks() {
+    let retention = TaskRetention {
+        ttl: Some(Duration::from_secs(60)),
+        max_retained: Some(2),
+        poll_interval: Some(Duration::from_millis(25)),
+    };
+    let (client, server) = connect_duplex(builder(retention), TasksClientHandler).await;
+
+    let mut task_ids = Vec::new();
+    for _ in 0..3 {
+        let created = call_tool_task(
+            client.peer(),
+            task_call_params("reindex", serde_json::Map::new()),
+        )
+        .await
+        .expect("task-augmented call");
+        assert_eq!(created.task.ttl_ms, Some(60_000));
+        assert_eq!(created.task.poll_interval_ms, Some(25));
+        poll_until_completed(client.peer(), &created.task.task_id)
+            .await
+            .expect("task completes");
+        task_ids.push(created.task.task_id);
+    }
+
+    // The third task pushed the oldest finished one out.
+    assert!(get_task_info(client.peer(), &task_ids[0]).await.is_err());
+    for task_id in &task_ids[1..] {
+        get_task_info(client.peer(), task_id)
+            .await
+            .expect("retained task");
+    }
+
+    let resources = client.list_resources(None).await.expect("list resources");
+    assert!(
+        resources
+            .resources
+            .iter()
+            .any(|r| r.uri == MCP_RESOURCE_URI_TASKS)
+    );
+    let listing = client
+        .read_resource(ReadResourceRequestParams::new(MCP_RESOURCE_URI_TASKS))
+        .await
+        .expect("read clap://tasks");
+    let listing: serde_json::Value =
+        serde_json::from_str(&read_text(&listing)).expect("tasks json");
+    let tasks = listing["tasks"].as_array().expect("tasks array");
+    let listed: Vec<_> = tasks
+        .iter()
+        .map(|t| t["taskId"].as_str().unwrap())
+        .collect();
+    assert_eq!(listed, [task_ids[1].as_str(), task_ids[2].as_str()]);
+    for task in tasks {
+        assert_eq!(task["tool"], "reindex");
+        assert_eq!(task["status"], "completed");
+        assert!(task["createdAt"].is_string());
+        assert!(task["lastUpdatedAt"].is_string());
+    }
+
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test]
+async fn zero_retention_limits_are_rejected() {
+    let result = serve_rejected(TaskRetention {
+        max_retained: Some(0),
+        ..Default::default()
+    })
+    .await;
+    assert!(matches!(result, Err(ClapMcpError::InvalidConfig(_))));
+}
+
+#[tokio::test]
+async fn max_retained_requires_ttl() {
+    let err = serve_rejected(TaskRetention {
+        max_retained: Some(2),
+        ..Default::default()
+    })
+    .await
+    .expect_err("max_retained without ttl");
+    assert!(
+        matches!(&err, ClapMcpError::InvalidConfig(message) if message.contains("requires a ttl")),
+        "{err}"
+    );
+}
@@ -0,0 +1,134 @@
+//! Tests for [`clap_mcp::tasks`]: task-augmented tools update their status message and progress,
+//! visible through `tasks/get` and `notifications/tasks`.
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, McpListen, ServeMcpBuilder, tasks};
+use common::{
+    call_tool_task, connect_duplex, get_task_info, poll_until_completed, shutdown_duplex,
+    task_call_params,
+};
+use rmcp::model::{
+    ClientCapabilities, ClientConfig, Implementation, TaskStatus, TaskStatusNotificationParams,
+};
+use rmcp::service::{NotificationContext, RunningService};
+use rmcp::{ClientHandler, RoleClient};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Released by the test once it has observed the in-flight status.
+static RELEASE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false, task_augmented_tools)]
+#[clap_mcp_output_from = "run_task_status_test"]
+#[command(name = "task-status-test-cli")]
+enum TaskStatusTestCli {
+    Reindex,
+}
+
+fn run_task_status_test(_cmd: TaskStatusTestCli) -> String {
+    tasks::set_status_message("Scanning");
+    tasks::set_progress(50, Some("Indexing"));
+    while !RELEASE.load(Ordering::SeqCst) {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    tasks::set_progress(100, None);
+    "reindexed".into()
+}
+
+/// Declares tasks and records every `notifications/tasks` status message.
+#[derive(Clone, Default)]
+struct StatusClient {
+    messages: Arc<Mutex<Vec<String>>>,
+}
+
+impl ClientHandler for StatusClient {
+    fn get_info(&self) -> ClientConfig {
+        ClientConfig::new(
+            ClientCapabilities::builder().enable_tasks().build(),
+            Implementation::from_build_env(),
+        )
+    }
+
+    async fn on_task_status(
+        &self,
+        params: TaskStatusNotificationParams,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        if let Some(message) = params.task.task.status_message.clone() {
+            self.messages.lock().expect("messages lock").push(message);
+        }
+    }
+}
+
+impl StatusClient {
+    async fn wait_for(&self, message: &str) {
+        for _ in 0..250 {
+            if self
+                .messages
+                .lock()
+                .expect("messages lock")
+                .iter()
+                .any(|m| m == message)
+            {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!(
+            "no status notification {message:?}; got {:?}",
+            self.messages.lock().expect("messages lock")
+        );
+    }
+}
+
+async fn connect(
+    handler: StatusClient,
+) -> (
+    RunningService<RoleClient, StatusClient>,
+    tokio::task::JoinHandle<()>,
+) {
+    connect_duplex(
+        ServeMcpBuilder::for_cli::<TaskStatusTestCli>(McpListen::Stdio),
+        handler,
+    )
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tool_reports_status_and_progress() {
+    // Outside a task-augmented call the helpers do nothing.
+    assert!(tasks::ClapMcpTask::current().is_none());
+    tasks::set_progress(10, Some("ignored"));
+
+    let handler = StatusClient::default();
+    let (client, server) = connect(handler.clone()).await;
+    let created = call_tool_task(
+        client.peer(),
+        task_call_params("reindex", serde_json::Map::new()),
+    )
+    .await
+    .expect("task-augmented call");
+    let task_id = created.task.task_id.clone();
+
+    handler.wait_for("Indexing (50%)").await;
+    let info = get_task_info(client.peer(), &task_id)
+        .await
+        .expect("tasks/get");
+    assert_eq!(info.task.status(), TaskStatus::Working);
+    assert_eq!(
+        info.task.task.status_message.as_deref(),
+        Some("Indexing (50%)")
+    );
+
+    RELEASE.store(true, Ordering::SeqCst);
+    handler.wait_for("Indexing (100%)").await;
+    poll_until_completed(client.peer(), &task_id)
+        .await
+        .expect("task completes");
+
+    shutdown_duplex(client, server).await;
+}
@@ -0,0 +1,220 @@
+//! Tests for task-augmented subprocess tools: the child's stderr lines become the task status,
+//! and `tasks/cancel` kills the child's process group.
+#![cfg(unix)]
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, McpListen, ServeMcpBuilder};
+use common::{
+    call_tool_task, connect_duplex, get_task_info, get_task_payload, poll_until_completed,
+    shutdown_duplex, task_call_params,
+};
+use rmcp::model::{
+    CancelTaskParams, ClientCapabilities, ClientConfig, ClientRequest, Implementation, Request,
+    TaskStatus, TaskStatusNotificationParams,
+};
+use rmcp::service::{NotificationContext, RunningService};
+use rmcp::{ClientHandler, RoleClient};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe = false, task_augmented_tools)]
+#[clap_mcp_output_from = "run_task_subprocess_test"]
+#[command(name = "task-subprocess-test-cli")]
+enum TaskSubprocessTestCli {
+    Fetch,
+    Hang,
+}
+
+fn run_task_subprocess_test(_cmd: TaskSubprocessTestCli) -> String {
+    String::new()
+}
+
+/// Stands in for the CLI binary: `fetch` reports two steps on stderr (apart, so the status
+/// notifications are not coalesced); `hang` starts a
+/// background `sleep` (its pid in `sleep.pid`) and waits for it.
+const SCRIPT: &str = r#"#!/bin/sh
+case "$1" in
+fetch)
+    echo "Downloading" >&2
+    sleep 0.2
+    echo "Parsing" >&2
+    echo "fetched"
+    ;;
+hang)
+    sleep 30 &
+    echo $! > "$(dirname "$0")/sleep.pid"
+    echo "Waiting" >&2
+    wait
+    ;;
+esac
+"#;
+
+/// Declares tasks and records every `notifications/tasks` status message.
+#[derive(Clone, Default)]
+struct StatusClient {
+    messages: Arc<Mutex<Vec<String>>>,
+}
+
+impl ClientHandler for StatusClient {
+    fn get_info(&self) -> ClientConfig {
+        ClientConfig::new(
+            ClientCapabilities::builder().enable_tasks().build(),
+            Implementation::from_build_env(),
+        )
+    }
+
+    async fn on_task_status(
+        &self,
+        params: TaskStatusNotificationParams,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        if let Some(message) = params.task.task.status_message.clone() {
+            self.messages.lock().expect("messages lock").push(message);
+        }
+    }
+}
+
+impl StatusClient {
+    async fn wait_for(&self, message: &str) {
+        for _ in 0..250 {
+            if self
+                .messages
+                .lock()
+                .expect("messages lock")
+                .iter()
+                .any(|m| m == message)
+            {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!(
+            "no status notification {message:?}; got {:?}",
+            self.messages.lock().expect("messages lock")
+        );
+    }
+}
+
+fn write_script(name: &str) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!(
+        "clap-mcp-task-subprocess-{name}-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("cli.sh");
+    std::fs::write(&script, SCRIPT).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    script
+}
+
+async fn connect(
+    handler: StatusClient,
+    script: &Path,
+) -> (
+    RunningService<RoleClient, StatusClient>,
+    tokio::task::JoinHandle<()>,
+) {
+    let builder = ServeMcpBuilder::for_cli::<TaskSubprocessTestCli>(McpListen::Stdio)
+        .executable_path(Some(script.to_path_buf()));
+    connect_duplex(builder, handler).await
+}
+
+/// Whether `pid` is still running; a killed process may linger as a zombie until reaped.
+fn process_alive(pid: &str) -> bool {
+    if let Ok(stat) = std::fs::read_to_string(format!("/proc/{pid}/stat")) {
+        return stat
+            .rsplit_once(')')
+            .is_some_and(|(_, rest)| !rest.trim_start().starts_with('Z'));
+    }
+    std::process::Command::new("kill")
+        .args(["-0", pid])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn stderr_lines_become_task_status() {
+    let script = write_script("status");
+    let handler = StatusClient::default();
+    let (client, server) = connect(handler.clone(), &script).await;
+
+    let created = call_tool_task(
+        client.peer(),
+        task_call_params("fetch", serde_json::Map::new()),
+    )
+    .await
+    .expect("task-augmented call");
+    let task_id = created.task.task_id.clone();
+    handler.wait_for("Downloading").await;
+    handler.wait_for("Parsing").await;
+    poll_until_completed(client.peer(), &task_id)
+        .await
+        .expect("task completes");
+    let payload = get_task_payload(client.peer(), &task_id)
+        .await
+        .expect("tasks/get");
+    let text = payload["content"][0]["text"].as_str().expect("text");
+    assert!(text.starts_with("fetched"), "{text}");
+
+    shutdown_duplex(client, server).await;
+    std::fs::remove_dir_all(script.parent().unwrap()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn cancel_kills_the_process_group() {
+    let script = write_script("cancel");
+    let handler = StatusClient::default();
+    let (client, server) = connect(handler.clone(), &script).await;
+
+    let created = call_tool_task(
+        client.peer(),
+        task_call_params("hang", serde_json::Map::new()),
+    )
+    .await
+    .expect("task-augmented call");
+    let task_id = created.task.task_id.clone();
+    handler.wait_for("Waiting").await;
+    let sleep_pid = std::fs::read_to_string(script.with_file_name("sleep.pid")).unwrap();
+    let sleep_pid = sleep_pid.trim();
+    assert!(process_alive(sleep_pid));
+
+    client
+        .peer()
+        .send_request(ClientRequest::CancelTaskRequest(Request::new(
+            CancelTaskParams::new(task_id.clone()),
+        )))
+        .await
+        .expect("tasks/cancel");
+    let mut status = TaskStatus::Working;
+    for _ in 0..250 {
+        status = get_task_info(client.peer(), &task_id)
+            .await
+            .expect("tasks/get")
+            .task
+            .status();
+        if status != TaskStatus::Working {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(status, TaskStatus::Cancelled);
+    // The background `sleep` shared the tool's process group.
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while process_alive(sleep_pid) {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("cancelled tool's process group should be killed");
+
+    shutdown_duplex(client, server).await;
+    std::fs::remove_dir_all(script.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,154 @@
+//! Tests for per-call [`clap_mcp::output::ClapMcpOutput`] / `mcp_println!` under `parallel_safe`.
+
+mod common;
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, ClapMcpConfigProvider, McpListen, ServeMcpBuilder, mcp_println};
+use common::{NoOpHandler, connect_duplex, shutdown_duplex};
+use rmcp::RoleClient;
+use rmcp::model::{CallToolRequestParams, CallToolResult, ContentBlock};
+use rmcp::service::RunningService;
+use std::time::Duration;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe)]
+#[clap_mcp_output_from = "run_output_test"]
+#[command(name = "output-test-cli")]
+enum OutputTestCli {
+    Label {
+        #[arg(long)]
+        name: String,
+    },
+    Nested,
+}
+
+fn run_output_test(cmd: OutputTestCli) -> String {
+    match cmd {
+        OutputTestCli::Label { name } => {
+            mcp_println!("start {name}");
+            std::thread::sleep(Duration::from_millis(200));
+            mcp_println!("end {name}");
+            format!("done {name}")
+        }
+        OutputTestCli::Nested => {
+            clap_mcp::run_async_tool(&OutputTestCli::clap_mcp_config(), || async {
+                tokio::task::yield_now().await;
+                mcp_println!("from nested runtime");
+            })
+            .expect("async tool should run");
+            String::new()
+        }
+    }
+}
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe)]
+#[clap_mcp_output_from_async = "run_async_output_test"]
+#[command(name = "async-output-test-cli")]
+enum AsyncOutputTestCli {
+    Label {
+        #[arg(long)]
+        name: String,
+    },
+}
+
+async fn run_async_output_test(cmd: AsyncOutputTestCli) -> String {
+    match cmd {
+        AsyncOutputTestCli::Label { name } => {
+            mcp_println!("start {name}");
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            mcp_println!("end {name}");
+            format!("done {name}")
+        }
+    }
+}
+
+async fn call(
+    client: &RunningService<RoleClient, NoOpHandler>,
+    name: &'static str,
+    args: serde_json::Value,
+) -> String {
+    let result: CallToolResult = client
+        .call_tool(
+            CallToolRequestParams::new(name).with_arguments(args.as_object().cloned().unwrap()),
+        )
+        .await
+        .expect("tool call should succeed");
+    result
+        .content
+        .iter()
+        .filter_map(|c| match c {
+            ContentBlock::Text(t) => Some(t.text.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+async fn assert_overlapping_calls_keep_their_own_output(builder: ServeMcpBuilder) {
+    let (client, server) = connect_duplex(builder, NoOpHandler).await;
+    let (a, b) = tokio::join!(
+        call(&client, "label", serde_json::json!({ "name": "a" })),
+        call(&client, "label", serde_json::json!({ "name": "b" })),
+    );
+    assert_eq!(a, "done a\nstart a\nend a");
+    assert_eq!(b, "done b\nstart b\nend b");
+    shutdown_duplex(client, server).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn parallel_sync_calls_keep_their_own_output() {
+    assert_overlapping_calls_keep_their_own_output(ServeMcpBuilder::for_cli::<OutputTestCli>(
+        McpListen::Stdio,
+    ))
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn parallel_async_calls_keep_their_own_output() {
+    assert_overlapping_calls_keep_their_own_output(ServeMcpBuilder::for_cli_async::<
+        AsyncOutputTestCli,
+    >(McpListen::Stdio))
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn run_async_tool_body_writes_to_calling_tool_output() {
+    let (client, server) = connect_duplex(
+        ServeMcpBuilder::for_cli::<OutputTestCli>(McpListen::Stdio),
+        NoOpHandler,
+    )
+    .await;
+    let text = call(&client, "nested", serde_json::json!({})).await;
+    assert_eq!(text, "from nested runtime");
+    shutdown_duplex(client, server).await;
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn capture_stdout_with_parallel_safe_is_refused() {
+    let (_io1, io2) = tokio::io::duplex(64);
+    let (read, write) = tokio::io::split(io2);
+    let err = ServeMcpBuilder::for_cli::<OutputTestCli>(McpListen::Stdio)
+        .serve_options(clap_mcp::ClapMcpServeOptions {
+            capture_stdout: true,
+            ..Default::default()
+        })
+        .stdio_io(read, write)
+        .serve()
+        .await
+        .expect_err("capture_stdout + parallel_safe should be refused");
+    assert!(
+        matches!(&err, clap_mcp::ClapMcpError::InvalidConfig(msg) if msg.contains("parallel_safe")),
+        "got: {err}"
+    );
+}
+
+#[test]
+fn mcp_println_prints_to_stdout_outside_tool_calls() {
+    let output = clap_mcp::output::ClapMcpOutput::new();
+    output.scope_sync(|| mcp_println!("inside"));
+    mcp_println!("outside");
+    assert_eq!(output.contents(), "inside\n");
+    assert!(clap_mcp::output::ClapMcpOutput::current().is_none());
+}
@@ -0,0 +1,16 @@
+use clap::Parser;
+use clap_mcp::ClapMcp;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe)]
+#[clap_mcp_output_from = "run"]
+enum Cli {
+    #[clap_mcp(in_process, subprocess)]
+    Migrate,
+}
+
+fn run(_cmd: Cli) -> String {
+    String::new()
+}
+
+fn main() {}
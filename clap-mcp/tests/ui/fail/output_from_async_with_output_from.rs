@@ -0,0 +1,20 @@
+use clap::Parser;
+use clap_mcp::ClapMcp;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe)]
+#[clap_mcp_output_from = "run"]
+#[clap_mcp_output_from_async = "run_async"]
+enum Cli {
+    Migrate,
+}
+
+fn run(_cmd: Cli) -> String {
+    String::new()
+}
+
+async fn run_async(_cmd: Cli) -> String {
+    String::new()
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use clap::Parser;
+use clap_mcp::ClapMcp;
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, catch_in_process_panics, poison_policy = "explode")]
+#[clap_mcp_output_from = "run"]
+enum Cli {
+    Ping,
+}
+
+fn run(_cmd: Cli) -> String {
+    String::new()
+}
+
+fn main() {}
@@ -1,4 +1,4 @@
-//! task_augmented_tools without reinvocation_safe must not compile.
+//! task_augmented_tools with subprocess execution (reinvocation_safe = false) compiles.
 
 use clap::Parser;
 use clap_mcp::ClapMcp;
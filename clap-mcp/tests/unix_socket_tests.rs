@@ -0,0 +1,129 @@
+//! Tests for [`clap_mcp::McpListen::Unix`]: MCP over a Unix domain socket with owner-only
+//! permissions, replacing a stale socket on start and removing it on shutdown.
+#![cfg(all(unix, feature = "http"))]
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, ClapMcpError, McpListen, ServeMcpBuilder};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe)]
+#[clap_mcp_output_from = "run_unix_socket_test"]
+#[command(name = "unix-socket-test-cli")]
+enum UnixSocketTestCli {
+    Ping,
+}
+
+fn run_unix_socket_test(_cmd: UnixSocketTestCli) -> String {
+    "pong".into()
+}
+
+const INITIALIZE: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-11-25","capabilities":{},"clientInfo":{"name":"unix-test","version":"0"}}}"#;
+
+fn socket_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("clap-mcp-unix-{}-{name}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("mcp.sock")
+}
+
+/// `POST /mcp` with an `initialize` body over the socket; returns the raw response.
+async fn post_initialize(path: &Path, host: &str) -> std::io::Result<String> {
+    let request = format!(
+        "POST /mcp HTTP/1.1\r\nHost: {host}\r\nContent-Type: application/json\r\n\
+         Accept: application/json, text/event-stream\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{INITIALIZE}",
+        INITIALIZE.len()
+    );
+    let mut stream = tokio::net::UnixStream::connect(path).await?;
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    Ok(response)
+}
+
+fn spawn_server(builder: ServeMcpBuilder) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let _ = builder.serve().await;
+    })
+}
+
+async fn wait_until_serving(path: &Path) -> String {
+    for _ in 0..100 {
+        if let Ok(response) = post_initialize(path, "localhost").await {
+            return response;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Unix socket server not ready at {}", path.display());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn serves_mcp_and_replaces_a_stale_socket() {
+    let path = socket_path("stale");
+    // Bound and dropped: the socket file stays behind with nothing accepting on it.
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    let server = spawn_server(ServeMcpBuilder::for_cli::<UnixSocketTestCli>(
+        McpListen::Unix(path.clone()),
+    ));
+    let response = wait_until_serving(&path).await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    let entries = std::fs::read_dir(path.parent().unwrap()).unwrap().count();
+    assert_eq!(entries, 1, "the temporary bind directory should be removed");
+
+    let response = post_initialize(&path, "evil.example").await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+
+    server.abort();
+    let _ = server.await;
+    assert!(!path.exists(), "socket should be removed on shutdown");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn socket_mode_is_configurable() {
+    let path = socket_path("mode");
+    let server = spawn_server(
+        ServeMcpBuilder::for_cli::<UnixSocketTestCli>(McpListen::Unix(path.clone()))
+            .unix_socket_mode(0o660),
+    );
+    wait_until_serving(&path).await;
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o660);
+
+    server.abort();
+    let _ = server.await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn live_sockets_and_other_files_are_not_replaced() {
+    let path = socket_path("live");
+    let live = std::os::unix::net::UnixListener::bind(&path).unwrap();
+    let err = ServeMcpBuilder::for_cli::<UnixSocketTestCli>(McpListen::Unix(path.clone()))
+        .serve()
+        .await
+        .expect_err("socket in use");
+    assert!(
+        matches!(&err, ClapMcpError::InvalidConfig(message) if message.contains("another server")),
+        "{err}"
+    );
+    drop(live);
+    std::fs::remove_file(&path).unwrap();
+
+    std::fs::write(&path, "not a socket").unwrap();
+    let err = ServeMcpBuilder::for_cli::<UnixSocketTestCli>(McpListen::Unix(path.clone()))
+        .serve()
+        .await
+        .expect_err("regular file in the way");
+    assert!(
+        matches!(&err, ClapMcpError::InvalidConfig(message) if message.contains("not a socket")),
+        "{err}"
+    );
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "not a socket");
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,55 @@
+//! Example: native async `run` with `#[clap_mcp_output_from_async]`.
+//!
+//! Run: `cargo run -p clap-mcp-examples --bin async_native -- sleep --ms 50`
+//! Run: `cargo run -p clap-mcp-examples --bin async_native -- --mcp`
+//!
+//! `run` is an `async fn`. In-process MCP tool calls await it directly on the
+//! server runtime; the normal CLI path awaits it on a runtime built by
+//! `run_or_serve_mcp_async`. No `run_async_tool` bridging is needed.
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, ParseOrServeMcpAsync};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe, catch_in_process_panics)]
+#[clap_mcp_output_from_async = "run"]
+#[command(
+    name = "async-native",
+    about = "CLI whose run function is async",
+    subcommand_required = true
+)]
+enum Cli {
+    /// Sleep without blocking the runtime and report the elapsed time.
+    Sleep {
+        #[arg(long, default_value_t = 100)]
+        ms: u64,
+    },
+    /// Sleep three times concurrently; total time is the longest sleep.
+    Fanout,
+}
+
+async fn run(cmd: Cli) -> Result<String, String> {
+    let start = Instant::now();
+    match cmd {
+        Cli::Sleep { ms } => {
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+            Ok(format!("slept {ms}ms"))
+        }
+        Cli::Fanout => {
+            let sleep = |ms| tokio::time::sleep(Duration::from_millis(ms));
+            tokio::join!(sleep(100), sleep(200), sleep(300));
+            Ok(format!("fanout done in {}ms", start.elapsed().as_millis()))
+        }
+    }
+}
+
+fn main() {
+    match Cli::run_or_serve_mcp_async() {
+        Ok(output) => println!("{}", output.into_string()),
+        Err(e) => {
+            eprintln!("{}", e.message);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Example: MCP `completion/complete` for tool arguments, prompt arguments, and resource
+//! template parameters.
+//!
+//! - `--profile` completes from clap `possible_values` (a `ValueEnum`).
+//! - `--out-dir` has `ValueHint::DirPath`, so it completes to directories under the client's
+//!   roots (nothing when the client exposes no roots).
+//! - `--target` uses `#[clap_mcp(complete_with = "complete_targets")]`.
+//! - The `review` prompt's `language` argument and the `example://lang/{name}` template complete
+//!   through a `CompletionProvider`.
+//!
+//! MCP has no tool reference for completions; clap-mcp answers a `ref/prompt` whose name is a
+//! tool name (here `build`).
+//!
+//! Run:
+//! - `cargo run -p clap-mcp-examples --bin completions -- --mcp`
+
+use async_trait::async_trait;
+use clap::{Parser, ValueEnum, ValueHint};
+use clap_mcp::content::{
+    CompletionProvider, CompletionReference, CustomPrompt, CustomResourceTemplate, PromptContent,
+    ResourceContent,
+};
+use clap_mcp::{ArgCompletionRequest, ClapMcp, ClapMcpServeOptions};
+use rmcp::model::{PromptArgument, PromptMessage, Role};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const TARGETS: &[&str] = &[
+    "aarch64-apple-darwin",
+    "aarch64-unknown-linux-gnu",
+    "x86_64-pc-windows-msvc",
+    "x86_64-unknown-linux-gnu",
+];
+
+const LANGUAGES: &[&str] = &["go", "python", "rust", "typescript"];
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Profile {
+    Debug,
+    Release,
+    Bench,
+}
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false)]
+#[clap_mcp_output_from = "run"]
+#[command(name = "completions", about = "Example with MCP argument completions")]
+enum Cli {
+    /// Pretend to build for a target.
+    Build {
+        #[arg(long, value_enum)]
+        profile: Profile,
+        #[arg(long, value_hint = ValueHint::DirPath)]
+        out_dir: Option<PathBuf>,
+        #[arg(long)]
+        #[clap_mcp(complete_with = "complete_targets")]
+        target: Option<String>,
+    },
+}
+
+fn complete_targets(request: &ArgCompletionRequest<'_>) -> Vec<String> {
+    TARGETS
+        .iter()
+        .filter(|t| t.starts_with(request.value))
+        .map(|t| t.to_string())
+        .collect()
+}
+
+fn run(cmd: Cli) -> String {
+    match cmd {
+        Cli::Build {
+            profile,
+            out_dir,
+            target,
+        } => format!(
+            "built {profile:?} for {} into {}",
+            target.as_deref().unwrap_or("host"),
+            out_dir.unwrap_or_else(|| "target".into()).display()
+        ),
+    }
+}
+
+struct Languages;
+
+#[async_trait]
+impl CompletionProvider for Languages {
+    async fn complete(
+        &self,
+        _reference: CompletionReference<'_>,
+        _argument: &str,
+        value: &str,
+        _context: &HashMap<String, String>,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(LANGUAGES
+            .iter()
+            .filter(|l| l.starts_with(value))
+            .map(|l| l.to_string())
+            .collect())
+    }
+}
+
+fn main() {
+    let mut serve_options = ClapMcpServeOptions::default();
+
+    serve_options.custom_prompts.push(CustomPrompt {
+        name: "review".into(),
+        title: Some("Code review".into()),
+        description: Some("Review code in a given language".into()),
+        arguments: vec![PromptArgument::new("language").with_required(true)],
+        content: PromptContent::Static(vec![PromptMessage::new_text(
+            Role::User,
+            "Review the attached code.",
+        )]),
+    });
+    serve_options
+        .custom_resource_templates
+        .push(CustomResourceTemplate {
+            uri_template: "example://lang/{name}".into(),
+            name: "lang".into(),
+            title: Some("Language notes".into()),
+            description: Some("Notes for one language".into()),
+            mime_type: Some("text/plain".into()),
+            content: ResourceContent::Static("Notes for {name}.".into()),
+        });
+    serve_options.completion_provider = Some(Arc::new(Languages));
+
+    let cli = clap_mcp::parse_or_serve_mcp_with::<Cli>(clap_mcp::ClapMcpRunOptions {
+        config: clap_mcp::ClapMcpConfig::default(),
+        serve: serve_options,
+    });
+    println!("{}", run(cli));
+}
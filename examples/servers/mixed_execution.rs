@@ -0,0 +1,42 @@
+//! Example: mixed execution modes per subcommand.
+//!
+//! The CLI is `reinvocation_safe`, so most tools run in the MCP server process.
+//! `migrate` is marked `#[clap_mcp(subprocess)]` and re-invokes the executable for
+//! each call, isolating it from the server process. Each tool reports the process
+//! id it ran in, so the difference is visible from an MCP client.
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, ParseOrServeMcp};
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false)]
+#[clap_mcp_output_from = "run"]
+#[command(
+    name = "mixed-execution",
+    about = "Example: per-subcommand in-process vs subprocess execution",
+    subcommand_required = true
+)]
+enum Cli {
+    /// Pure query; runs in-process (inherits the root `reinvocation_safe`).
+    Status,
+    /// Mutates global state; always runs in a fresh subprocess.
+    #[clap_mcp(subprocess)]
+    Migrate {
+        /// Target schema version.
+        #[arg(long, default_value_t = 1)]
+        version: u32,
+    },
+}
+
+fn run(cmd: Cli) -> String {
+    let pid = std::process::id();
+    match cmd {
+        Cli::Status => format!("status ok (pid {pid})"),
+        Cli::Migrate { version } => format!("migrated to v{version} (pid {pid})"),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse_or_serve_mcp();
+    println!("{}", run(cli));
+}
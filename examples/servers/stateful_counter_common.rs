@@ -1,8 +1,9 @@
 use clap::{Parser, Subcommand};
 use clap_mcp::ClapMcp;
+use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct CounterState {
     pub count: u64,
 }
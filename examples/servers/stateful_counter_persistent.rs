@@ -0,0 +1,40 @@
+mod stateful_counter_common;
+
+use clap_mcp::{
+    ClapMcpConfigProvider, ClapMcpRunOptions, ClapMcpServeOptions, parse_or_serve_mcp_with_state,
+    state::StatePersistence,
+};
+use stateful_counter_common::{App, CounterState, run};
+use std::sync::{Arc, Mutex};
+
+/// Stateful MCP counter that survives server restarts.
+///
+/// The count is reloaded from `STATEFUL_COUNTER_STATE` (default `stateful-counter.json`) on
+/// start, snapshotted on shutdown, and readable as the `clap://state` resource.
+///
+/// Try:
+/// - `cargo run -p clap-mcp-examples --bin stateful_counter_persistent -- --mcp`
+fn main() {
+    let path = std::env::var_os("STATEFUL_COUNTER_STATE")
+        .unwrap_or_else(|| "stateful-counter.json".into());
+    let persistence = StatePersistence::new(path);
+    let state: Arc<Mutex<CounterState>> = Arc::new(
+        persistence
+            .load_or_default()
+            .expect("state snapshot should load"),
+    );
+    let app = parse_or_serve_mcp_with_state::<App>(
+        ClapMcpRunOptions {
+            config: App::clap_mcp_config(),
+            serve: ClapMcpServeOptions {
+                state_persistence: Some(persistence.clone().bind(Arc::clone(&state))),
+                ..Default::default()
+            },
+        },
+        Arc::clone(&state),
+    );
+    println!("{}", run(app.command, state.as_ref()));
+    persistence
+        .save(state.as_ref())
+        .expect("state snapshot should save");
+}
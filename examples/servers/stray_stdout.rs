@@ -0,0 +1,45 @@
+//! In-process tool that prints to stdout without `capture_stdout`.
+//!
+//! Run: `cargo run -p clap-mcp-examples --bin stray_stdout -- chatty --name world`
+//! Run: `cargo run -p clap-mcp-examples --bin stray_stdout -- --mcp`
+//!
+//! In stdio MCP mode the protocol runs on a private copy of stdout, so the
+//! `println!` below cannot corrupt the JSON-RPC stream. Because this example
+//! enables logging, stray lines reach the client as `notifications/message`
+//! with logger `stdout`; without logging they go to stderr.
+
+use clap::Parser;
+use clap_mcp::{ClapMcp, ClapMcpConfigProvider};
+
+#[derive(Debug, Parser, ClapMcp)]
+#[clap_mcp(reinvocation_safe, parallel_safe = false)]
+#[clap_mcp_output_from = "run"]
+#[command(name = "stray-stdout", subcommand_required = true)]
+enum Cli {
+    /// Greet someone, printing progress to stdout along the way.
+    Chatty {
+        #[arg(long)]
+        name: String,
+    },
+}
+
+fn run(cmd: Cli) -> String {
+    match cmd {
+        Cli::Chatty { name } => {
+            println!("about to greet {name}");
+            format!("Hello, {name}!")
+        }
+    }
+}
+
+fn main() {
+    let (_log_tx, log_rx) = clap_mcp::logging::log_channel(32);
+    let cli = clap_mcp::parse_or_serve_mcp_with::<Cli>(clap_mcp::ClapMcpRunOptions {
+        config: Cli::clap_mcp_config(),
+        serve: clap_mcp::ClapMcpServeOptions {
+            log_rx: Some(log_rx),
+            ..Default::default()
+        },
+    });
+    println!("{}", run(cli));
+}
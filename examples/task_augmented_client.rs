@@ -13,7 +13,7 @@ use clap::Parser;
 use rmcp::{
     ClientHandler, RoleClient, ServiceExt,
     model::{
-        CallToolRequestParams, ClientCapabilities, ClientInfo, ClientRequest, ContentBlock,
+        CallToolRequestParams, ClientCapabilities, ClientConfig, ClientRequest, ContentBlock,
         CreateTaskResult, GetTaskParams, Implementation, Request, ServerResult, TaskPayload,
         TaskStatus,
     },
@@ -53,8 +53,8 @@ static BUILD_LOCK: Mutex<()> = Mutex::new(());
 struct TasksClientHandler;
 
 impl ClientHandler for TasksClientHandler {
-    fn get_info(&self) -> ClientInfo {
-        ClientInfo::new(
+    fn get_info(&self) -> ClientConfig {
+        ClientConfig::new(
             ClientCapabilities::builder().enable_tasks().build(),
             Implementation::from_build_env(),
         )